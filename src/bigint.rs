@@ -1,5 +1,9 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
 /// Represents a base-10 number that can have any number of digits.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BigInt {
 	digits: Vec<u8>,
 }
@@ -19,6 +23,7 @@ impl BigInt {
 	/// assert_eq!(&vec![4, 1, 3], bigint.digits());
 	/// ```
 	///
+	#[allow(clippy::len_zero)]
 	pub fn new(it: impl Iterator<Item = u8>) -> Self {
 		let mut digits: Vec<u8> = it.collect();
 		for d in &digits {
@@ -36,6 +41,7 @@ impl BigInt {
 		}
 	}
 
+	#[allow(clippy::assign_op_pattern, clippy::redundant_field_names)]
 	pub fn from_int(n: u64) -> Self {
 		let mut digits: Vec<u8> = vec![];
 
@@ -77,6 +83,7 @@ impl BigInt {
 	/// assert_eq!(&vec![7, 8, 9, 1], b.digits());
 	/// assert_eq!(&vec![8, 1, 0, 2], sum.digits());
 	/// ```
+	#[allow(clippy::assign_op_pattern)]
 	pub fn add(&self, other: &BigInt) -> Self {
 		let mut result = vec![];
 
@@ -104,6 +111,42 @@ impl BigInt {
 		}
 	}
 
+	/// Produce a new BigInt object whose digits correspond to the difference
+	/// of the number represented by `self` and `other`. Panics if `other` is
+	/// larger than `self`, since negative numbers cannot be represented.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::BigInt;
+	/// let a = BigInt::from_int(1018);
+	/// let b = BigInt::from_int(987);
+	/// assert_eq!(BigInt::from_int(31), a.subtract(&b));
+	/// ```
+	pub fn subtract(&self, other: &BigInt) -> Self {
+		if self < other {
+			panic!("subtraction would produce a negative number");
+		}
+		let mut result = vec![];
+
+		// Subtract digit-by-digit, borrowing from the next digit as needed
+		let mut borrow = 0;
+		for i in 0..self.digits.len() {
+			let b = borrow + if i < other.digits.len() { other.digits[i] } else { 0 };
+			let a = self.digits[i];
+			if a >= b {
+				result.push(a - b);
+				borrow = 0;
+			} else {
+				result.push(a + 10 - b);
+				borrow = 1;
+			}
+		}
+
+		BigInt::new(result.into_iter())
+	}
+
+	#[allow(clippy::same_item_push)]
 	pub fn multiply(&self, other: &BigInt) -> Self {
 		let mut products = vec![];
 
@@ -137,6 +180,112 @@ impl BigInt {
 
 		result
 	}
+
+	/// Divide the number represented by `self` by `divisor`, producing the
+	/// quotient as a new BigInt object together with the remainder. Panics if
+	/// `divisor` is 0.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::BigInt;
+	/// let (q, r) = BigInt::from_int(1018).divide_int(7);
+	/// assert_eq!(BigInt::from_int(145), q);
+	/// assert_eq!(3, r);
+	/// ```
+	pub fn divide_int(&self, divisor: u64) -> (Self, u64) {
+		if divisor == 0 {
+			panic!("division by zero");
+		}
+		let divisor = divisor as u128;
+		let mut quotient = vec![];
+
+		// Long division, starting from the most-significant digit
+		let mut remainder: u128 = 0;
+		for d in self.digits.iter().rev() {
+			remainder = remainder * 10 + *d as u128;
+			quotient.push((remainder / divisor) as u8);
+			remainder %= divisor;
+		}
+
+		quotient.reverse();
+		(BigInt::new(quotient.into_iter()), remainder as u64)
+	}
+}
+
+impl From<u64> for BigInt {
+	fn from(n: u64) -> Self {
+		BigInt::from_int(n)
+	}
+}
+
+impl From<u128> for BigInt {
+	fn from(n: u128) -> Self {
+		let mut digits = vec![];
+
+		let mut k = n;
+		loop {
+			digits.push((k % 10) as u8);
+			k /= 10;
+
+			if k == 0 {
+				break;
+			}
+		}
+
+		BigInt {
+			digits,
+		}
+	}
+}
+
+/// The error returned when parsing a [`BigInt`] from a string that is not
+/// made up of decimal digits only.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBigIntError;
+
+impl fmt::Display for ParseBigIntError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "invalid decimal digits for BigInt")
+	}
+}
+
+impl std::error::Error for ParseBigIntError {}
+
+/// Parses a non-empty string of decimal digits, most-significant digit first.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::BigInt;
+/// let n: BigInt = "1018".parse().unwrap();
+/// assert_eq!(BigInt::from_int(1018), n);
+/// assert!("-5".parse::<BigInt>().is_err());
+/// ```
+impl FromStr for BigInt {
+	type Err = ParseBigIntError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+			return Err(ParseBigIntError);
+		}
+		Ok(BigInt::new(s.bytes().rev().map(|b| b - b'0')))
+	}
+}
+
+impl Ord for BigInt {
+	fn cmp(&self, other: &Self) -> Ordering {
+		// Digits are stored without leading zeros, so a longer number is larger.
+		// Otherwise compare starting from the most-significant digit.
+		self.digits.len().cmp(&other.digits.len())
+			.then_with(|| self.digits.iter().rev().cmp(other.digits.iter().rev()))
+	}
+}
+
+impl PartialOrd for BigInt {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
 }
 
 #[cfg(test)]
@@ -201,4 +350,60 @@ mod tests {
 		let bigint = BigInt::from_int(12345);
 		assert_eq!(&vec![5, 4, 3, 2, 1], bigint.digits());
 	}
+
+	#[test]
+	fn bigint_subtract_with_borrow() {
+		// 1000 minus 1 is 999
+		let a = BigInt::from_int(1000);
+		let b = BigInt::from_int(1);
+		assert_eq!(&vec![9, 9, 9], a.subtract(&b).digits());
+		assert_eq!(&vec![0], a.subtract(&a).digits());
+	}
+
+	#[test]
+	#[should_panic(expected = "negative")]
+	fn bigint_subtract_larger() {
+		BigInt::from_int(5).subtract(&BigInt::from_int(6));
+	}
+
+	#[test]
+	fn bigint_ordering() {
+		assert!(BigInt::from_int(99) < BigInt::from_int(100));
+		assert!(BigInt::from_int(123) < BigInt::from_int(132));
+		assert!(BigInt::from_int(7) > BigInt::from_int(0));
+		assert_eq!(Ordering::Equal, BigInt::from_int(42).cmp(&BigInt::from_int(42)));
+	}
+
+	#[test]
+	fn bigint_divide_int() {
+		let (q, r) = BigInt::from_int(123_456_789).divide_int(1000);
+		assert_eq!(BigInt::from_int(123_456), q);
+		assert_eq!(789, r);
+
+		let (q, r) = BigInt::from_int(12).divide_int(u64::MAX);
+		assert_eq!(BigInt::from_int(0), q);
+		assert_eq!(12, r);
+	}
+
+	#[test]
+	#[should_panic(expected = "division by zero")]
+	fn bigint_divide_by_zero() {
+		BigInt::from_int(1).divide_int(0);
+	}
+
+	#[test]
+	fn bigint_from_u128() {
+		let bigint = BigInt::from(u128::MAX);
+		assert_eq!(39, bigint.digits().len());
+		assert_eq!(BigInt::from_int(0), BigInt::from(0u128));
+		assert_eq!(BigInt::from_int(u64::MAX), BigInt::from(u64::MAX as u128));
+	}
+
+	#[test]
+	fn bigint_from_str() {
+		let n: BigInt = "000123".parse().unwrap();
+		assert_eq!(&vec![3, 2, 1], n.digits());
+		assert_eq!(Err(ParseBigIntError), "".parse::<BigInt>());
+		assert_eq!(Err(ParseBigIntError), "12a".parse::<BigInt>());
+	}
 }
@@ -0,0 +1,643 @@
+use std::iter::FusedIterator;
+
+/// An iterator over the `k`-element subsets of a slice, each given as a
+/// `Vec` of its elements in their original order. Subsets are produced in
+/// lexicographic order of their positions in the slice, one at a time.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::comb::Combinations;
+/// let pairs: Vec<Vec<char>> = Combinations::new(&['a', 'b', 'c', 'd'], 2).collect();
+/// assert_eq!(6, pairs.len());
+/// assert_eq!(vec!['a', 'b'], pairs[0]);
+/// assert_eq!(vec!['a', 'c'], pairs[1]);
+/// assert_eq!(vec!['c', 'd'], pairs[5]);
+/// ```
+#[derive(Clone)]
+pub struct Combinations<'a, T> {
+	items: &'a [T],
+	// The positions of the next subset, or None once all have been produced
+	indices: Option<Vec<usize>>,
+}
+
+impl<'a, T: Clone> Combinations<'a, T> {
+	/// Produces nothing if `k` exceeds the number of items, and a single
+	/// empty subset if `k` is 0.
+	pub fn new(items: &'a [T], k: usize) -> Self {
+		Self {
+			items,
+			indices: (k <= items.len()).then(|| (0..k).collect()),
+		}
+	}
+}
+
+impl<T: Clone> Iterator for Combinations<'_, T> {
+	type Item = Vec<T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let indices = self.indices.as_mut()?;
+		let result = indices.iter().map(|&i| self.items[i].clone()).collect();
+
+		// Advance the rightmost position that has room to move, then reset
+		// the ones after it to follow it directly.
+		let n = self.items.len();
+		let k = indices.len();
+		match (0..k).rev().find(|&i| indices[i] < n - k + i) {
+			Some(i) => {
+				indices[i] += 1;
+				for j in i + 1..k {
+					indices[j] = indices[j - 1] + 1;
+				}
+			},
+			None => self.indices = None,
+		}
+		Some(result)
+	}
+}
+
+impl<T: Clone> FusedIterator for Combinations<'_, T> {}
+
+/// An iterator over the distinct permutations of a slice, in lexicographic
+/// order. Repeated elements are handled correctly: each arrangement is
+/// produced once, so `[1, 1, 2]` gives three permutations rather than six.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::comb::Permutations;
+/// let perms: Vec<Vec<u8>> = Permutations::new(&[2, 1, 1]).collect();
+/// assert_eq!(vec![vec![1, 1, 2], vec![1, 2, 1], vec![2, 1, 1]], perms);
+/// assert_eq!(24, Permutations::new(&['a', 'b', 'c', 'd']).count());
+/// ```
+#[derive(Clone)]
+pub struct Permutations<T> {
+	// The next permutation, or None once all have been produced
+	current: Option<Vec<T>>,
+}
+
+impl<T: Ord + Clone> Permutations<T> {
+	/// Starts from the items in sorted order, whatever their order in
+	/// `items`.
+	pub fn new(items: &[T]) -> Self {
+		let mut current = items.to_vec();
+		current.sort();
+		Self {
+			current: Some(current),
+		}
+	}
+}
+
+impl<T: Ord + Clone> Iterator for Permutations<T> {
+	type Item = Vec<T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let current = self.current.as_mut()?;
+		let result = current.clone();
+		if !next_permutation(current) {
+			self.current = None;
+		}
+		Some(result)
+	}
+}
+
+impl<T: Ord + Clone> FusedIterator for Permutations<T> {}
+
+/// Rearranges `items` into the next permutation in lexicographic order,
+/// returning true. If they are already in the last permutation, they are
+/// sorted instead, wrapping around to the first, and false is returned.
+///
+/// Repeated items are handled correctly, so starting from sorted items and
+/// stepping until false visits each distinct permutation once.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::comb;
+/// let mut items = [1, 2, 3];
+/// assert!(comb::next_permutation(&mut items));
+/// assert_eq!([1, 3, 2], items);
+///
+/// let mut count = 1;
+/// let mut items = ['a', 'b', 'b', 'c'];
+/// while comb::next_permutation(&mut items) {
+///     count += 1;
+/// }
+/// assert_eq!(12, count);
+/// assert_eq!(['a', 'b', 'b', 'c'], items);
+/// ```
+pub fn next_permutation<T: Ord>(items: &mut [T]) -> bool {
+	step_permutation(items, |a, b| a < b)
+}
+
+/// Rearranges `items` into the previous permutation in lexicographic order,
+/// returning true. If they are already in the first permutation, they are
+/// sorted in reverse instead, wrapping around to the last, and false is
+/// returned. This undoes [`next_permutation`].
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::comb;
+/// let mut items = [1, 3, 2];
+/// assert!(comb::prev_permutation(&mut items));
+/// assert_eq!([1, 2, 3], items);
+/// assert!(!comb::prev_permutation(&mut items));
+/// assert_eq!([3, 2, 1], items);
+/// ```
+pub fn prev_permutation<T: Ord>(items: &mut [T]) -> bool {
+	step_permutation(items, |a, b| a > b)
+}
+
+/// Steps `items` to the next permutation in the order given by `before`,
+/// which is `<` for the next permutation and `>` for the previous one.
+fn step_permutation<T>(items: &mut [T], before: impl Fn(&T, &T) -> bool) -> bool {
+	// Find the longest suffix in which no item comes before the one after
+	// it; the item preceding it is the pivot, which is swapped with the last
+	// item in the suffix that comes after the pivot.
+	let Some(start) = (1..items.len()).rev().find(|&i| before(&items[i - 1], &items[i])) else {
+		items.reverse();
+		return false;
+	};
+	let pivot = start - 1;
+	let successor = (start..items.len()).rev()
+		.find(|&i| before(&items[pivot], &items[i]))
+		.unwrap();
+	items.swap(pivot, successor);
+	items[pivot + 1..].reverse();
+	true
+}
+
+/// The permutation at index `n`, counting from 0, in the lexicographic
+/// order of the distinct permutations of `items`, as produced by
+/// [`Permutations`]. Returns `None` if there are not that many.
+///
+/// Each position is decided by counting the permutations that start with
+/// each smaller candidate, which for distinct items is the factorial-base
+/// decomposition of `n`. It takes $O(k^2)$ time for $k$ items.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::comb;
+/// let digits: Vec<u8> = (0..10).collect();
+/// // Project Euler 24: the millionth permutation of 0 to 9
+/// let perm = comb::nth_permutation(&digits, 999_999).unwrap();
+/// assert_eq!(vec![2, 7, 8, 3, 9, 1, 5, 4, 6, 0], perm);
+/// assert_eq!(None, comb::nth_permutation(&[1, 1, 2], 3));
+/// ```
+pub fn nth_permutation<T: Ord + Clone>(items: &[T], mut n: u64) -> Option<Vec<T>> {
+	let mut sorted = items.to_vec();
+	sorted.sort();
+	// The distinct values with their multiplicities, in increasing order
+	let mut groups: Vec<(T, u128)> = vec![];
+	for item in sorted {
+		match groups.last_mut() {
+			Some((value, count)) if *value == item => *count += 1,
+			_ => groups.push((item, 1)),
+		}
+	}
+
+	let mut remaining = items.len() as u128;
+	// The number of permutations of the remaining items, or None if it
+	// does not fit in a u128
+	let mut total = multinomial(groups.iter().map(|&(_, c)| c));
+	if total.is_some_and(|t| (n as u128) >= t) {
+		return None;
+	}
+	let mut result = Vec::with_capacity(items.len());
+	while remaining > 0 {
+		// If the total does not fit in a u128, at least 2^128 / remaining > 2^64
+		// permutations start with the smallest value, so n is among them.
+		let mut chosen = 0;
+		if let Some(t) = total {
+			for (i, &(_, c)) in groups.iter().enumerate() {
+				// t * c / remaining, without overflowing
+				let starting = t / remaining * c + t % remaining * c / remaining;
+				if (n as u128) < starting {
+					chosen = i;
+					total = Some(starting);
+					break;
+				}
+				n -= starting as u64;
+			}
+		}
+
+		let (value, count) = &mut groups[chosen];
+		result.push(value.clone());
+		*count -= 1;
+		if *count == 0 {
+			groups.remove(chosen);
+		}
+		remaining -= 1;
+		if total.is_none() {
+			total = multinomial(groups.iter().map(|&(_, c)| c));
+		}
+	}
+	Some(result)
+}
+
+/// The number of arrangements of a multiset with the given multiplicities,
+/// or `None` if it does not fit in a `u128`.
+fn multinomial(counts: impl Iterator<Item = u128>) -> Option<u128> {
+	let mut total = 1u128;
+	let mut size = 0;
+	for c in counts {
+		size += c;
+		total = total.checked_mul(binomial(size, c)?)?;
+	}
+	Some(total)
+}
+
+/// The binomial coefficient C(n, k), or `None` if it does not fit in a
+/// `u128`.
+fn binomial(n: u128, k: u128) -> Option<u128> {
+	let k = k.min(n - k);
+	let mut result = 1u128;
+	for i in 0..k {
+		// C(n, i + 1) = C(n, i) * (n - i) / (i + 1), dividing before
+		// multiplying so that only a result that overflows fails
+		let g = gcd(result, i + 1);
+		result = (result / g).checked_mul((n - i) / ((i + 1) / g))?;
+	}
+	Some(result)
+}
+
+fn gcd(mut p: u128, mut q: u128) -> u128 {
+	while q != 0 {
+		(p, q) = (q, p % q);
+	}
+	p
+}
+
+/// Iterates over all `bits`-bit values in binary reflected Gray code order,
+/// in which consecutive values differ in exactly one bit. Each value comes
+/// with the index of the bit that changed from the previous one, which is
+/// `None` for the first value, 0. Panics if `bits` exceeds 64.
+///
+/// Reading each value as a subset of `bits` items, this visits every subset
+/// while adding or removing a single item at each step.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::comb;
+/// let codes: Vec<(u64, Option<u32>)> = comb::gray_codes(2).collect();
+/// assert_eq!(vec![(0b00, None), (0b01, Some(0)), (0b11, Some(1)), (0b10, Some(0))], codes);
+///
+/// // Keep a running subset sum, updating it by one item at a time
+/// let items = [3, 5, 9];
+/// let mut sum = 0;
+/// let mut sums = vec![];
+/// for (code, changed) in comb::gray_codes(3) {
+///     if let Some(i) = changed {
+///         if code & (1 << i) != 0 {
+///             sum += items[i as usize];
+///         } else {
+///             sum -= items[i as usize];
+///         }
+///     }
+///     sums.push(sum);
+/// }
+/// sums.sort();
+/// assert_eq!(vec![0, 3, 5, 8, 9, 12, 14, 17], sums);
+/// ```
+pub fn gray_codes(bits: u32) -> GrayCodes {
+	if bits > 64 {
+		panic!("cannot produce Gray codes of more than 64 bits");
+	}
+	GrayCodes {
+		last: if bits == 0 { 0 } else { u64::MAX >> (64 - bits) },
+		index: Some(0),
+	}
+}
+
+/// An iterator over Gray codes, created by [`gray_codes`].
+#[derive(Clone)]
+pub struct GrayCodes {
+	// The index of the final code, 2^bits - 1
+	last: u64,
+	// The index of the next code, or None once all have been produced
+	index: Option<u64>,
+}
+
+impl Iterator for GrayCodes {
+	type Item = (u64, Option<u32>);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let i = self.index?;
+		// Going from i - 1 to i flips the lowest set bit of i, and the Gray
+		// code i ^ (i >> 1) flips the bit at the same position.
+		let changed = (i > 0).then(|| i.trailing_zeros());
+		self.index = (i < self.last).then(|| i + 1);
+		Some((i ^ (i >> 1), changed))
+	}
+}
+
+impl FusedIterator for GrayCodes {}
+
+/// Iterates over the partitions of `n`, the ways of writing it as a sum of
+/// positive integers without regard to order. Each partition is a `Vec` of
+/// its parts in non-increasing order, and the partitions come in decreasing
+/// lexicographic order, starting with `[n]`. The only partition of 0 is the
+/// empty one.
+///
+/// Use [`Partitions::max_part`] and [`Partitions::parts`] to restrict the
+/// partitions produced. See [`numt::partition_count`](crate::numt::partition_count)
+/// to count them without producing them.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::comb;
+/// let parts: Vec<Vec<u64>> = comb::partitions(4).collect();
+/// assert_eq!(vec![vec![4], vec![3, 1], vec![2, 2], vec![2, 1, 1], vec![1, 1, 1, 1]], parts);
+/// assert_eq!(42, comb::partitions(10).count());
+/// ```
+pub fn partitions(n: u64) -> Partitions {
+	let mut partitions = Partitions {
+		n,
+		max_part: n,
+		parts: None,
+		current: None,
+	};
+	partitions.restart();
+	partitions
+}
+
+/// An iterator over integer partitions, created by [`partitions`].
+#[derive(Clone)]
+pub struct Partitions {
+	n: u64,
+	max_part: u64,
+	// The exact number of parts, if restricted
+	parts: Option<usize>,
+	// The next partition, or None once all have been produced
+	current: Option<Vec<u64>>,
+}
+
+impl Partitions {
+	/// Only produce partitions whose parts are all at most `k`. This starts
+	/// the iteration over.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::comb;
+	/// // Ways to make 10 from coins worth 1, 2 and 3
+	/// assert_eq!(14, comb::partitions(10).max_part(3).count());
+	/// ```
+	pub fn max_part(mut self, k: u64) -> Self {
+		self.max_part = k;
+		self.restart();
+		self
+	}
+
+	/// Only produce partitions with exactly `k` parts. This starts the
+	/// iteration over.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::comb;
+	/// let parts: Vec<Vec<u64>> = comb::partitions(7).parts(3).max_part(3).collect();
+	/// assert_eq!(vec![vec![3, 3, 1], vec![3, 2, 2]], parts);
+	/// ```
+	pub fn parts(mut self, k: usize) -> Self {
+		self.parts = Some(k);
+		self.restart();
+		self
+	}
+
+	fn restart(&mut self) {
+		let mut first = vec![];
+		self.current = complete(&mut first, self.n, self.max_part, self.parts).then_some(first);
+	}
+}
+
+impl Iterator for Partitions {
+	type Item = Vec<u64>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let current = self.current.take()?;
+
+		// Decrease the rightmost part that can be decreased while still
+		// completing the parts after it, then make those as large as possible.
+		let mut next = current.clone();
+		let mut suffix = 0;
+		for i in (0..current.len()).rev() {
+			suffix += current[i];
+			let part = current[i] - 1;
+			if part == 0 {
+				continue;
+			}
+			next.truncate(i);
+			next.push(part);
+			if complete(&mut next, suffix - part, part, self.parts.map(|k| k - i - 1)) {
+				self.current = Some(next);
+				break;
+			}
+		}
+		Some(current)
+	}
+}
+
+impl FusedIterator for Partitions {}
+
+/// Appends to `prefix` the lexicographically largest non-increasing parts
+/// that sum to `sum`, each at most `bound`, and with `count` of them if
+/// given. Returns false, leaving `prefix` unchanged, if there are none.
+fn complete(prefix: &mut Vec<u64>, mut sum: u64, bound: u64, count: Option<usize>) -> bool {
+	let feasible = match count {
+		Some(k) => k as u64 <= sum && sum as u128 <= k as u128 * bound as u128,
+		None => sum == 0 || bound > 0,
+	};
+	if !feasible {
+		return false;
+	}
+	// Each part is as large as it can be while leaving at least 1 for each
+	// part still to come
+	let mut left = count.map(|k| k as u64);
+	while sum > 0 {
+		let part = match left.as_mut() {
+			Some(k) => {
+				*k -= 1;
+				bound.min(sum - *k)
+			},
+			None => bound.min(sum),
+		};
+		prefix.push(part);
+		sum -= part;
+	}
+	true
+}
+
+/// Iterates over the distinct arrangements of a multiset in which element
+/// `i` appears `counts[i]` times, each given as a `Vec` of element indices.
+/// Arrangements come in lexicographic order, and each is produced once
+/// without generating and discarding duplicates, so there are
+/// $\frac{(c_0 + c_1 + \cdots)!}{c_0! \, c_1! \cdots}$ of them.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::comb;
+/// let words: Vec<String> = comb::multiset_permutations(&[2, 1])
+///     .map(|p| p.iter().map(|&i| ['a', 'b'][i]).collect())
+///     .collect();
+/// assert_eq!(vec!["aab", "aba", "baa"], words);
+///
+/// // 20! / (10! 10!) lattice paths through a 10 by 10 grid
+/// assert_eq!(184_756, comb::multiset_permutations(&[10, 10]).count());
+/// ```
+pub fn multiset_permutations(counts: &[usize]) -> Permutations<usize> {
+	let items: Vec<usize> = counts.iter()
+		.enumerate()
+		.flat_map(|(i, &c)| std::iter::repeat_n(i, c))
+		.collect();
+	Permutations::new(&items)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn combinations_in_lexicographic_order() {
+		let triples: Vec<Vec<u32>> = Combinations::new(&[1, 2, 3, 4, 5], 3).collect();
+		assert_eq!(10, triples.len());
+		let mut sorted = triples.clone();
+		sorted.sort();
+		assert_eq!(sorted, triples);
+		assert_eq!(vec![3, 4, 5], triples[9]);
+	}
+
+	#[test]
+	fn combinations_edge_cases() {
+		let items = [7, 8, 9];
+		assert_eq!(vec![Vec::<i32>::new()], Combinations::new(&items, 0).collect::<Vec<_>>());
+		assert_eq!(vec![vec![7, 8, 9]], Combinations::new(&items, 3).collect::<Vec<_>>());
+		assert_eq!(0, Combinations::new(&items, 4).count());
+		assert_eq!(1, Combinations::<u8>::new(&[], 0).count());
+	}
+
+	#[test]
+	fn permutations_with_repeats() {
+		let perms: Vec<Vec<char>> = Permutations::new(&['b', 'a', 'b', 'a']).collect();
+		// 4! / (2! 2!)
+		assert_eq!(6, perms.len());
+		assert_eq!(vec!['a', 'a', 'b', 'b'], perms[0]);
+		assert_eq!(vec!['b', 'b', 'a', 'a'], perms[5]);
+		assert!(perms.windows(2).all(|w| w[0] < w[1]));
+		assert_eq!(vec![Vec::<u8>::new()], Permutations::<u8>::new(&[]).collect::<Vec<_>>());
+	}
+
+	#[test]
+	fn nth_permutation_matches_iterator() {
+		for items in [vec![3, 1, 2, 4], vec![1, 1, 2, 2, 2], vec![5, 5, 5]] {
+			for (n, perm) in Permutations::new(&items).enumerate() {
+				assert_eq!(Some(perm), nth_permutation(&items, n as u64));
+			}
+			let count = Permutations::new(&items).count() as u64;
+			assert_eq!(None, nth_permutation(&items, count));
+		}
+	}
+
+	#[test]
+	fn nth_permutation_of_many_items() {
+		// 40! does not fit in a u128, so the leading items never move
+		let items: Vec<u32> = (0..40).collect();
+		let last = nth_permutation(&items, u64::MAX).unwrap();
+		assert_eq!(&items[..19], &last[..19]);
+		let first = nth_permutation(&items, 0).unwrap();
+		assert_eq!(items, first);
+		let second = nth_permutation(&items, 1).unwrap();
+		assert_eq!((38, 39), (second[39], second[38]));
+	}
+
+	#[test]
+	fn next_and_prev_permutation_are_inverse() {
+		let mut items = [2, 1, 2, 3, 1];
+		let mut forward: Vec<Vec<u32>> = vec![];
+		items.sort();
+		loop {
+			forward.push(items.to_vec());
+			if !next_permutation(&mut items) {
+				break;
+			}
+		}
+		assert_eq!(Permutations::new(&items).collect::<Vec<_>>(), forward);
+
+		// Wrapping around from the first permutation gives the last
+		assert!(!prev_permutation(&mut items));
+		for expected in forward.iter().rev().skip(1) {
+			assert!(prev_permutation(&mut items));
+			assert_eq!(expected, &items[..]);
+		}
+		let mut empty: [u8; 0] = [];
+		assert!(!next_permutation(&mut empty));
+		assert!(!prev_permutation(&mut [7]));
+	}
+
+	#[test]
+	fn gray_codes_change_one_bit() {
+		let codes: Vec<(u64, Option<u32>)> = gray_codes(5).collect();
+		assert_eq!(32, codes.len());
+		for pair in codes.windows(2) {
+			let ((prev, _), (code, changed)) = (pair[0], pair[1]);
+			assert_eq!(Some(prev ^ code), changed.map(|i| 1 << i));
+		}
+		let mut seen: Vec<u64> = codes.iter().map(|&(code, _)| code).collect();
+		seen.sort();
+		assert_eq!((0..32).collect::<Vec<_>>(), seen);
+
+		assert_eq!(vec![(0, None)], gray_codes(0).collect::<Vec<_>>());
+		let mut wide = gray_codes(64);
+		assert_eq!(Some((1, Some(0))), wide.nth(1));
+	}
+
+	#[test]
+	fn partitions_are_distinct_and_decreasing() {
+		for n in 0..20 {
+			let all: Vec<Vec<u64>> = partitions(n).collect();
+			assert_eq!(crate::BigInt::from(all.len() as u64), crate::numt::partition_count(n));
+			assert!(all.windows(2).all(|w| w[0] > w[1]));
+			for p in &all {
+				assert_eq!(n, p.iter().sum::<u64>());
+				assert!(p.windows(2).all(|w| w[0] >= w[1]));
+			}
+		}
+		assert_eq!(vec![Vec::<u64>::new()], partitions(0).collect::<Vec<_>>());
+	}
+
+	#[test]
+	fn partitions_with_constraints() {
+		// Conjugation pairs partitions into k parts with those whose largest
+		// part is k
+		for n in 1..15 {
+			for k in 1..=n {
+				let largest = partitions(n).filter(|p| p[0] == k).count();
+				assert_eq!(largest, partitions(n).parts(k as usize).count());
+			}
+		}
+		let parts: Vec<Vec<u64>> = partitions(9).parts(3).max_part(4).collect();
+		assert_eq!(vec![vec![4, 4, 1], vec![4, 3, 2], vec![3, 3, 3]], parts);
+		assert_eq!(0, partitions(5).parts(6).count());
+		assert_eq!(0, partitions(5).max_part(0).count());
+		assert_eq!(1, partitions(0).parts(0).count());
+	}
+
+	#[test]
+	fn multiset_permutations_without_duplicates() {
+		let perms: Vec<Vec<usize>> = multiset_permutations(&[2, 0, 1, 3]).collect();
+		// 6! / (2! 0! 1! 3!)
+		assert_eq!(60, perms.len());
+		assert!(perms.windows(2).all(|w| w[0] < w[1]));
+		for p in &perms {
+			assert_eq!(2, p.iter().filter(|&&i| i == 0).count());
+			assert_eq!(3, p.iter().filter(|&&i| i == 3).count());
+		}
+		assert_eq!(Some(vec![0, 0, 2, 3, 3, 3]), perms.first().cloned());
+		assert_eq!(vec![Vec::<usize>::new()], multiset_permutations(&[]).collect::<Vec<_>>());
+	}
+}
@@ -1,6 +1,145 @@
+use std::collections::VecDeque;
+use std::iter::{FusedIterator, Zip};
+use std::ops::RangeFrom;
+
+use crate::BigInt;
+use crate::numt;
+
+/// The operations a numeric type needs to support to be used as the terms
+/// of the sequences in this module.
+pub trait SequenceTerm: Clone + PartialEq {
+	fn zero() -> Self;
+
+	fn one() -> Self;
+
+	/// Adds `self` and `other`, returning None on overflow.
+	fn checked_add(&self, other: &Self) -> Option<Self>;
+
+	/// Multiplies `self` and `other`, returning None on overflow.
+	fn checked_mul(&self, other: &Self) -> Option<Self>;
+
+	/// Adds `self` and `other`, returning the largest value on overflow.
+	fn saturating_add(&self, other: &Self) -> Self;
+
+	/// Adds `self` and `other`, wrapping around on overflow.
+	fn wrapping_add(&self, other: &Self) -> Self;
+
+	/// Multiplies `self` and `other`, wrapping around on overflow.
+	fn wrapping_mul(&self, other: &Self) -> Self;
+}
+
+impl SequenceTerm for u64 {
+	fn zero() -> Self {
+		0
+	}
+
+	fn one() -> Self {
+		1
+	}
+
+	fn checked_add(&self, other: &Self) -> Option<Self> {
+		u64::checked_add(*self, *other)
+	}
+
+	fn checked_mul(&self, other: &Self) -> Option<Self> {
+		u64::checked_mul(*self, *other)
+	}
+
+	fn saturating_add(&self, other: &Self) -> Self {
+		u64::saturating_add(*self, *other)
+	}
+
+	fn wrapping_add(&self, other: &Self) -> Self {
+		u64::wrapping_add(*self, *other)
+	}
+
+	fn wrapping_mul(&self, other: &Self) -> Self {
+		u64::wrapping_mul(*self, *other)
+	}
+}
+
+impl SequenceTerm for u128 {
+	fn zero() -> Self {
+		0
+	}
+
+	fn one() -> Self {
+		1
+	}
+
+	fn checked_add(&self, other: &Self) -> Option<Self> {
+		u128::checked_add(*self, *other)
+	}
+
+	fn checked_mul(&self, other: &Self) -> Option<Self> {
+		u128::checked_mul(*self, *other)
+	}
+
+	fn saturating_add(&self, other: &Self) -> Self {
+		u128::saturating_add(*self, *other)
+	}
+
+	fn wrapping_add(&self, other: &Self) -> Self {
+		u128::wrapping_add(*self, *other)
+	}
+
+	fn wrapping_mul(&self, other: &Self) -> Self {
+		u128::wrapping_mul(*self, *other)
+	}
+}
+
+impl SequenceTerm for BigInt {
+	fn zero() -> Self {
+		BigInt::from_int(0)
+	}
+
+	fn one() -> Self {
+		BigInt::from_int(1)
+	}
+
+	// A BigInt never overflows
+	fn checked_add(&self, other: &Self) -> Option<Self> {
+		Some(self.add(other))
+	}
+
+	fn checked_mul(&self, other: &Self) -> Option<Self> {
+		Some(self.multiply(other))
+	}
+
+	fn saturating_add(&self, other: &Self) -> Self {
+		self.add(other)
+	}
+
+	fn wrapping_add(&self, other: &Self) -> Self {
+		self.add(other)
+	}
+
+	fn wrapping_mul(&self, other: &Self) -> Self {
+		self.multiply(other)
+	}
+}
+
+/// What a sequence iterator does when the next term does not fit in its term
+/// type. To switch to [`BigInt`] terms instead, see
+/// [`FibonacciIterator::promote_on_overflow`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+	/// End the iteration before the first term that overflows.
+	#[default]
+	Stop,
+	/// Replace every term that overflows with the largest value of the type.
+	Saturate,
+	/// Keep only the low bits of every term, as with `wrapping_add`.
+	Wrap,
+}
+
 /// An iterator that produces the terms of the Fibonacci sequence, starting
 /// at 0. Returns None on overflow.
 ///
+/// The terms are `u64` by default, which overflows after the 94th term. Any
+/// [`SequenceTerm`] can be used instead, such as `u128` or [`BigInt`], which
+/// never overflows.
+///
 /// # Examples
 ///
 /// ```
@@ -10,38 +149,1856 @@
 /// 	.take(expected_terms.len())
 /// 	.collect();
 /// assert_eq!(expected_terms, produced_terms);
+///
+/// assert_eq!(94, FibonacciIterator::<u64>::new().count());
+/// assert_eq!(187, FibonacciIterator::<u128>::new().count());
+///
+/// // nth() jumps ahead without producing the terms in between
+/// let f93: Option<u64> = FibonacciIterator::new().nth(93);
+/// assert_eq!(Some(12_200_160_415_121_876_738), f93);
+/// ```
+///
+/// Stopping on overflow is the default, but an [`OverflowPolicy`] can be used
+/// to pick another behavior.
+///
+/// ```
+/// use segtrs::FibonacciIterator;
+/// use segtrs::fib::OverflowPolicy;
+/// let f100: Option<u64> = FibonacciIterator::new()
+/// 	.overflow_policy(OverflowPolicy::Wrap)
+/// 	.nth(100);
+/// assert_eq!(Some(3_736_710_778_780_434_371), f100);
 /// ```
-pub struct FibonacciIterator {
+#[derive(Clone)]
+pub struct FibonacciIterator<T = u64> {
 	// This is always the next term to be returned, if any.
-	f0: Option<u64>,
+	f0: Option<T>,
 	// The value following f0, if any.
-	f1: Option<u64>,
+	f1: Option<T>,
+	// The index of f0 in the sequence
+	index: u64,
+	policy: OverflowPolicy,
+	// The last two terms that fit in T, kept once f1 has overflown so that
+	// promote_on_overflow can carry on from them
+	last_pair: Option<(T, T)>,
 }
 
-impl FibonacciIterator {
+/// An endless iterator over the Fibonacci sequence with [`BigInt`] terms.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::fib::BigFibonacciIterator;
+/// // Project Euler 25: the first term with 1000 digits is F(4782)
+/// let index = BigFibonacciIterator::new()
+/// 	.position(|f| f.digits().len() == 1000);
+/// assert_eq!(Some(4782), index);
+/// ```
+pub type BigFibonacciIterator = FibonacciIterator<BigInt>;
+
+impl<T: SequenceTerm> FibonacciIterator<T> {
 	pub fn new() -> Self {
+		Self::with_seeds(T::zero(), T::one())
+	}
+
+	/// Create an iterator for the Fibonacci-like sequence whose first two
+	/// terms are `a` and `b`, and where every later term is the sum of the
+	/// two before it.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::FibonacciIterator;
+	/// let terms: Vec<u64> = FibonacciIterator::with_seeds(1, 1).take(6).collect();
+	/// assert_eq!(vec![1, 1, 2, 3, 5, 8], terms);
+	///
+	/// let terms: Vec<u64> = FibonacciIterator::with_seeds(5, 0).take(6).collect();
+	/// assert_eq!(vec![5, 0, 5, 5, 10, 15], terms);
+	/// ```
+	pub fn with_seeds(a: T, b: T) -> Self {
 		Self {
-			f0: Some(0),
-			f1: Some(1),
+			f0: Some(a),
+			f1: Some(b),
+			index: 0,
+			policy: OverflowPolicy::Stop,
+			last_pair: None,
+		}
+	}
+
+	/// Fast-forward so that the next term produced is the one at index `n`,
+	/// where the first seed is at index 0, so that $F(0) = 0$ for
+	/// [`FibonacciIterator::new`]. Uses fast doubling rather than producing
+	/// the terms in between. Panics if the term at index `n` was already
+	/// produced.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::FibonacciIterator;
+	/// let mut terms = FibonacciIterator::<u64>::new().skip_to(90);
+	/// assert_eq!(Some(2_880_067_194_370_816_120), terms.next());
+	/// ```
+	pub fn skip_to(mut self, n: u64) -> Self {
+		if n < self.index {
+			panic!("cannot skip back to an earlier term");
+		}
+		self.advance(n - self.index);
+		self
+	}
+
+	/// Produce `(n, term)` pairs, where `n` is the index of the term in the
+	/// sequence as described in [`FibonacciIterator::skip_to`].
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::FibonacciIterator;
+	/// let pairs: Vec<(u64, u64)> = FibonacciIterator::new().enumerated().take(4).collect();
+	/// assert_eq!(vec![(0, 0), (1, 1), (2, 1), (3, 2)], pairs);
+	///
+	/// let first_even_after_50 = FibonacciIterator::<u64>::new()
+	/// 	.skip_to(50)
+	/// 	.enumerated()
+	/// 	.find(|(_, f)| f % 2 == 0);
+	/// assert_eq!(Some((51, 20_365_011_074)), first_even_after_50);
+	/// ```
+	pub fn enumerated(self) -> Zip<RangeFrom<u64>, Self> {
+		(self.index..).zip(self)
+	}
+
+	/// Skip the next `n` terms, using fast doubling when possible.
+	fn advance(&mut self, n: u64) {
+		if n == 0 {
+			return;
+		}
+
+		if let (Some(a), Some(b)) = (&self.f0, &self.f1) {
+			if *a == T::zero() && *b == T::zero() {
+				self.index = self.index.saturating_add(n);
+				return;
+			}
+			let forwarded = match self.policy {
+				// Wrapping arithmetic never fails, so neither does this
+				OverflowPolicy::Wrap => fast_forward(a, b, n,
+					|x, y| Some(x.wrapping_add(y)),
+					|x, y| Some(x.wrapping_mul(y))),
+				_ => fast_forward(a, b, n, T::checked_add, T::checked_mul),
+			};
+			if let Some((term, next)) = forwarded {
+				self.f0 = Some(term);
+				self.f1 = Some(next);
+				self.index = self.index.saturating_add(n);
+				return;
+			}
+		}
+
+		// Fast-forwarding overflowed, so the sequence is close to its end, or
+		// close to saturating.
+		for skipped in 1..=n {
+			if self.next().is_none() {
+				return;
+			}
+			if let (Some(a), Some(b)) = (&self.f0, &self.f1)
+				&& a == b && a.saturating_add(b) == *a {
+				// Every remaining term is the largest value
+				self.index = self.index.saturating_add(n - skipped);
+				return;
+			}
+		}
+	}
+
+	/// Set what happens when a term overflows. The default is
+	/// [`OverflowPolicy::Stop`].
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::FibonacciIterator;
+	/// use segtrs::fib::OverflowPolicy;
+	/// let mut terms = FibonacciIterator::<u64>::new()
+	/// 	.overflow_policy(OverflowPolicy::Saturate)
+	/// 	.skip(93);
+	/// assert_eq!(Some(12_200_160_415_121_876_738), terms.next());
+	/// assert_eq!(Some(u64::MAX), terms.next());
+	/// assert_eq!(Some(u64::MAX), terms.next());
+	/// ```
+	pub fn overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+		self.policy = policy;
+		self
+	}
+
+	/// Turn this iterator into one that produces [`BigInt`] terms and, rather
+	/// than overflowing, carries on with [`BigInt`] arithmetic once the terms
+	/// no longer fit in `T`. The overflow policy is ignored, and an iterator
+	/// that already stopped on overflow carries on where it stopped.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::BigInt;
+	/// use segtrs::FibonacciIterator;
+	/// let f100 = FibonacciIterator::<u64>::new().promote_on_overflow().nth(100);
+	/// assert_eq!(Some(BigInt::from(354_224_848_179_261_915_075u128)), f100);
+	///
+	/// let mut terms = FibonacciIterator::<u64>::new().skip_to(93);
+	/// assert_eq!(Some(12_200_160_415_121_876_738), terms.next());
+	/// assert_eq!(None, terms.next());
+	/// let f94 = terms.promote_on_overflow().next();
+	/// assert_eq!(Some(BigInt::from(19_740_274_219_868_223_167u128)), f94);
+	/// ```
+	pub fn promote_on_overflow(mut self) -> PromotingFibonacciIterator<T>
+		where T: Into<BigInt> {
+
+		// Past an overflow, only the last pair that fit can tell what follows
+		let big = self.last_pair.take().map(|(a, b)| {
+			let mut big = FibonacciIterator::with_seeds(a.into(), b.into());
+			big.advance(if self.f0.is_some() { 1 } else { 2 });
+			big
+		});
+		PromotingFibonacciIterator {
+			small: self.overflow_policy(OverflowPolicy::Stop),
+			big,
 		}
 	}
 }
 
-impl Iterator for FibonacciIterator {
-	type Item = u64;
+impl<T: SequenceTerm> Default for FibonacciIterator<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T: SequenceTerm> Iterator for FibonacciIterator<T> {
+	type Item = T;
 
 	fn next(&mut self) -> Option<Self::Item> {
 		// Stop if f0 has overflown
-		let result = self.f0?;
+		let result = self.f0.take()?;
 
-		let next = match (self.f0, self.f1) {
-			(Some(f0_val), Some(f1_val)) => f0_val.checked_add(f1_val),
-			_ => None,
+		let next = match &self.f1 {
+			Some(f1_val) => match self.policy {
+				OverflowPolicy::Stop => {
+					let next = result.checked_add(f1_val);
+					if next.is_none() {
+						self.last_pair = Some((result.clone(), f1_val.clone()));
+					}
+					next
+				},
+				OverflowPolicy::Saturate => Some(result.saturating_add(f1_val)),
+				OverflowPolicy::Wrap => Some(result.wrapping_add(f1_val)),
+			},
+			None => None,
 		};
 
-		self.f0 = self.f1;
+		self.f0 = self.f1.take();
 		self.f1 = next;
+		self.index = self.index.saturating_add(1);
 
 		Some(result)
 	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		if self.policy != OverflowPolicy::Stop {
+			return (usize::MAX, None);
+		}
+
+		// How many terms remain before overflow depends on T, so only the
+		// terms already computed are known for certain.
+		match (&self.f0, &self.f1) {
+			(None, _) => (0, Some(0)),
+			(Some(_), None) => (1, Some(1)),
+			(Some(_), Some(_)) => (2, None),
+		}
+	}
+
+	fn nth(&mut self, n: usize) -> Option<Self::Item> {
+		self.advance(n as u64);
+		self.next()
+	}
+}
+
+impl<T: SequenceTerm> FusedIterator for FibonacciIterator<T> {}
+
+/// An endless iterator over a Fibonacci-like sequence that starts out with
+/// terms of type `T` and switches to [`BigInt`] arithmetic just before they
+/// would overflow. Created by [`FibonacciIterator::promote_on_overflow`].
+#[derive(Clone)]
+pub struct PromotingFibonacciIterator<T = u64> {
+	small: FibonacciIterator<T>,
+	// Takes over once the terms outgrow T
+	big: Option<BigFibonacciIterator>,
+}
+
+impl<T: SequenceTerm + Into<BigInt>> Iterator for PromotingFibonacciIterator<T> {
+	type Item = BigInt;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if let Some(big) = &mut self.big {
+			return big.next();
+		}
+
+		if let (Some(a), Some(b)) = (&self.small.f0, &self.small.f1)
+			&& a.checked_add(b).is_none() {
+			let mut big = FibonacciIterator::with_seeds(a.clone().into(), b.clone().into());
+			let result = big.next();
+			self.big = Some(big);
+			return result;
+		}
+
+		self.small.next().map(Into::into)
+	}
+}
+
+/// Computes terms n and n + 1 of the Fibonacci-like sequence with
+/// seeds `a` and `b`, or None if `add` or `mul` fails along the way. Uses the
+/// identity $G(n) = aF(n - 1) + bF(n)$, where the Fibonacci numbers are found
+/// by fast doubling.
+fn fast_forward<T: SequenceTerm>(a: &T, b: &T, n: u64,
+	add: impl Fn(&T, &T) -> Option<T>, mul: impl Fn(&T, &T) -> Option<T>)
+	-> Option<(T, T)> {
+
+	// Holds F(k - 1), F(k) and F(k + 1), starting from k = 0
+	let (mut prev, mut curr, mut next) = (T::one(), T::zero(), T::one());
+	for bit in (0..u64::BITS - n.leading_zeros()).rev() {
+		// F(2k - 1) = F(k)^2 + F(k - 1)^2
+		// F(2k) = F(k)(F(k - 1) + F(k + 1))
+		// F(2k + 1) = F(k + 1)^2 + F(k)^2
+		let curr_squared = mul(&curr, &curr)?;
+		let odd_before = add(&curr_squared, &mul(&prev, &prev)?)?;
+		let even = mul(&curr, &add(&prev, &next)?)?;
+		let odd_after = add(&mul(&next, &next)?, &curr_squared)?;
+
+		(prev, curr, next) = if (n >> bit) & 1 == 1 {
+			let after = add(&even, &odd_after)?;
+			(even, odd_after, after)
+		} else {
+			(odd_before, even, odd_after)
+		};
+	}
+
+	let term = add(&mul(a, &prev)?, &mul(b, &curr)?)?;
+	let following = add(&mul(a, &curr)?, &mul(b, &next)?)?;
+	Some((term, following))
+}
+
+/// Computes the $n$th Fibonacci number, with $F(0) = 0$, or None if it does
+/// not fit in a `u64`. Uses the fast-doubling identities
+/// $F(2k) = F(k)(2F(k+1) - F(k))$ and $F(2k+1) = F(k)^2 + F(k+1)^2$, so it
+/// takes $O(\log n)$ steps.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::fib;
+/// assert_eq!(Some(55), fib::nth(10));
+/// assert_eq!(Some(12_200_160_415_121_876_738), fib::nth(93));
+/// assert_eq!(None, fib::nth(94));
+/// ```
+pub fn nth(n: u64) -> Option<u64> {
+	// F(93) is the largest Fibonacci number that fits in a u64. Below that,
+	// the pair (F(k), F(k + 1)) always fits in a u128.
+	if n > 93 {
+		return None;
+	}
+
+	Some(fibonacci_pair(n).0 as u64)
+}
+
+/// Computes $(F(n), F(n + 1))$ by fast doubling. Overflows if $n > 184$.
+fn fibonacci_pair(n: u64) -> (u128, u128) {
+	let (mut a, mut b): (u128, u128) = (0, 1);
+	for bit in (0..(u64::BITS - n.leading_zeros())).rev() {
+		let (c, d) = (a * (2 * b - a), a * a + b * b);
+		(a, b) = if (n >> bit) & 1 == 1 { (d, c + d) } else { (c, d) };
+	}
+
+	(a, b)
+}
+
+/// Computes the $n$th Fibonacci number as a BigInt, using the same
+/// fast-doubling identities as [`nth`].
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::BigInt;
+/// use segtrs::fib;
+/// assert_eq!(BigInt::from_int(55), fib::nth_big(10));
+/// assert_eq!(209, fib::nth_big(1000).digits().len());
+/// ```
+pub fn nth_big(n: u64) -> BigInt {
+	let (mut a, mut b) = (BigInt::from_int(0), BigInt::from_int(1));
+	for bit in (0..(u64::BITS - n.leading_zeros())).rev() {
+		let two_b = b.add(&b);
+		let c = a.multiply(&two_b.subtract(&a));
+		let d = a.multiply(&a).add(&b.multiply(&b));
+		(a, b) = if (n >> bit) & 1 == 1 {
+			let sum = c.add(&d);
+			(d, sum)
+		} else {
+			(c, d)
+		};
+	}
+
+	a
+}
+
+/// An iterator that produces the Lucas numbers $2, 1, 3, 4, 7, 11, \ldots$,
+/// which follow the same recurrence as the Fibonacci numbers but start from
+/// $L(0) = 2$ and $L(1) = 1$. Returns None on overflow. Like
+/// [`FibonacciIterator`], the terms may be of any [`SequenceTerm`] type.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::fib::LucasIterator;
+/// let terms: Vec<u64> = LucasIterator::new().take(8).collect();
+/// assert_eq!(vec![2, 1, 3, 4, 7, 11, 18, 29], terms);
+/// ```
+pub struct LucasIterator<T = u64> {
+	terms: FibonacciIterator<T>,
+}
+
+impl<T: SequenceTerm> LucasIterator<T> {
+	pub fn new() -> Self {
+		let one = T::one();
+		let two = one.checked_add(&one).unwrap();
+		Self {
+			terms: FibonacciIterator::with_seeds(two, one),
+		}
+	}
+}
+
+impl<T: SequenceTerm> Default for LucasIterator<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T: SequenceTerm> Iterator for LucasIterator<T> {
+	type Item = T;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.terms.next()
+	}
+}
+
+/// Computes the $n$th Lucas number, with $L(0) = 2$, or None if it does not
+/// fit in a `u64`. Uses the identity $L(n) = 2F(n + 1) - F(n)$ together with
+/// fast doubling.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::fib;
+/// assert_eq!(Some(123), fib::lucas_nth(10));
+/// assert_eq!(None, fib::lucas_nth(94));
+/// ```
+pub fn lucas_nth(n: u64) -> Option<u64> {
+	if n > 93 {
+		return None;
+	}
+
+	let (f0, f1) = fibonacci_pair(n);
+	u64::try_from(2 * f1 - f0).ok()
+}
+
+/// An iterator over the k-bonacci numbers, where every term is the sum of
+/// the `k` terms before it. The sequence starts with $k - 1$ zeros followed
+/// by a one, so `k = 2` gives the Fibonacci numbers, `k = 3` the tribonacci
+/// numbers, and so on. Only a window of the last `k` terms is kept. Returns
+/// None on overflow.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::fib::KBonacciIterator;
+/// let tribonacci: Vec<u64> = KBonacciIterator::new(3).take(10).collect();
+/// assert_eq!(vec![0, 0, 1, 1, 2, 4, 7, 13, 24, 44], tribonacci);
+/// ```
+pub struct KBonacciIterator<T = u64> {
+	// The next k terms to be returned, unless the sequence has overflown
+	window: VecDeque<T>,
+	overflowed: bool,
+}
+
+impl<T: SequenceTerm> KBonacciIterator<T> {
+	/// Panics if `k` is 0.
+	pub fn new(k: usize) -> Self {
+		if k == 0 {
+			panic!("k must be positive");
+		}
+
+		let mut window: VecDeque<T> = std::iter::repeat_n(T::zero(), k - 1)
+			.collect();
+		window.push_back(T::one());
+		Self {
+			window,
+			overflowed: false,
+		}
+	}
+}
+
+impl<T: SequenceTerm> Iterator for KBonacciIterator<T> {
+	type Item = T;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if !self.overflowed {
+			let sum = self.window
+				.iter()
+				.try_fold(T::zero(), |acc, term| acc.checked_add(term));
+			match sum {
+				Some(term) => self.window.push_back(term),
+				// The terms already in the window can still be returned
+				None => self.overflowed = true,
+			}
+		}
+
+		self.window.pop_front()
+	}
+}
+
+/// Computes the $n$th term of the Fibonacci sequence extended to negative
+/// indices, or None if it does not fit in an `i64`. Running the recurrence
+/// backwards gives the negafibonacci numbers, $F(-n) = (-1)^{n+1} F(n)$.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::fib;
+/// let terms: Vec<i64> = (-6..=6).map(|n| fib::nth_signed(n).unwrap()).collect();
+/// assert_eq!(vec![-8, 5, -3, 2, -1, 1, 0, 1, 1, 2, 3, 5, 8], terms);
+/// ```
+pub fn nth_signed(n: i64) -> Option<i64> {
+	let magnitude = i64::try_from(nth(n.unsigned_abs())?).ok()?;
+	if n < 0 && n % 2 == 0 {
+		Some(-magnitude)
+	} else {
+		Some(magnitude)
+	}
+}
+
+/// Computes the Zeckendorf representation of `n`: the unique way to write
+/// `n` as a sum of Fibonacci numbers, no two of them consecutive in the
+/// sequence. The terms are produced in decreasing order, and the
+/// representation of 0 is empty.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::fib;
+/// assert_eq!(vec![89, 8, 3, 1], fib::zeckendorf(101));
+/// assert!(fib::zeckendorf(0).is_empty());
+/// ```
+pub fn zeckendorf(n: u64) -> Vec<u64> {
+	// Fibonacci numbers starting at F(2) = 1, so that 1 appears once
+	let fibs: Vec<u64> = FibonacciIterator::new()
+		.skip(2)
+		.take_while(|&f| f <= n)
+		.collect();
+
+	// Greedily taking the largest term that fits never picks two
+	// consecutive Fibonacci numbers.
+	let mut terms = vec![];
+	let mut rest = n;
+	for &f in fibs.iter().rev() {
+		if f <= rest {
+			terms.push(f);
+			rest -= f;
+		}
+	}
+
+	terms
+}
+
+/// Encodes the Zeckendorf representation of `n` as a string of bits, most
+/// significant first, where the last bit stands for $F(2) = 1$, the one
+/// before it for $F(3) = 2$, and so on. No two adjacent bits are both 1.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::fib;
+/// // 101 = 89 + 8 + 3 + 1 = F(11) + F(6) + F(4) + F(2)
+/// assert_eq!("1000010101", fib::zeckendorf_bits(101));
+/// assert_eq!("0", fib::zeckendorf_bits(0));
+/// ```
+pub fn zeckendorf_bits(n: u64) -> String {
+	let terms = zeckendorf(n);
+	let Some(&largest) = terms.first() else {
+		return String::from("0");
+	};
+
+	let fibs: Vec<u64> = FibonacciIterator::new()
+		.skip(2)
+		.take_while(|&f| f <= largest)
+		.collect();
+	fibs.iter()
+		.rev()
+		.map(|f| if terms.contains(f) { '1' } else { '0' })
+		.collect()
+}
+
+/// Determines whether `n` is a Fibonacci number, using the fact that this
+/// holds exactly when $5n^2 + 4$ or $5n^2 - 4$ is a perfect square.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::fib;
+/// assert!(fib::is_fibonacci(144));
+/// assert!(!fib::is_fibonacci(145));
+/// ```
+pub fn is_fibonacci(n: u64) -> bool {
+	let Some(five_n_squared) = (n as u128 * n as u128).checked_mul(5) else {
+		// Only the few largest u64 values get here, so compare directly.
+		return FibonacciIterator::<u64>::new().any(|f| f == n);
+	};
+	let is_square = |m: u128| m.isqrt() * m.isqrt() == m;
+	is_square(five_n_squared + 4) || (n > 0 && is_square(five_n_squared - 4))
+}
+
+/// Finds the index of `n` in the Fibonacci sequence, so that
+/// $F(\text{index}) = n$, or None if `n` is not a Fibonacci number. Since
+/// $F(1) = F(2) = 1$, the index returned for 1 is 1.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::fib;
+/// assert_eq!(Some(12), fib::index_of(144));
+/// assert_eq!(None, fib::index_of(145));
+/// ```
+pub fn index_of(n: u64) -> Option<u64> {
+	if !is_fibonacci(n) {
+		return None;
+	}
+
+	FibonacciIterator::<u64>::new()
+		.position(|f| f == n)
+		.map(|i| i as u64)
+}
+
+/// Computes the nth term, modulo `modulus`, of the linear recurrence
+/// $a(n) = c_1a(n - 1) + c_2a(n - 2) + \cdots + c_ka(n - k)$, where `coeffs`
+/// holds $c_1, \ldots, c_k$ and `init` holds the first k terms
+/// $a(0), \ldots, a(k - 1)$.
+///
+/// Uses Kitamasa's method, which takes $O(k^2 \log n)$ time, so indices far
+/// too large to iterate to are fine. Panics if `coeffs` is empty, if `coeffs`
+/// and `init` have different lengths, or if `modulus` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::fib;
+/// // The Fibonacci numbers
+/// assert_eq!(6765, fib::linear_recurrence_nth(&[1, 1], &[0, 1], 20, u64::MAX));
+///
+/// // The tribonacci numbers, far past the range of u64
+/// let n = 1_000_000_000_000_000_000;
+/// let t = fib::linear_recurrence_nth(&[1, 1, 1], &[0, 0, 1], n, 1_000_000_007);
+/// assert_eq!(913_728_402, t);
+/// ```
+pub fn linear_recurrence_nth(coeffs: &[u64], init: &[u64], n: u64, modulus: u64)
+	-> u64 {
+
+	let k = coeffs.len();
+	if k == 0 {
+		panic!("recurrence must have at least one coefficient");
+	}
+	if init.len() != k {
+		panic!("expected one initial term per coefficient");
+	}
+	if modulus == 0 {
+		panic!("modulus must be positive");
+	}
+
+	let m = modulus as u128;
+	let coeffs: Vec<u128> = coeffs.iter().map(|&c| c as u128 % m).collect();
+
+	// Multiplies two polynomials of degree less than k, then reduces the
+	// product using x^k = c_1 x^(k - 1) + ... + c_k.
+	let mul_reduce = |p: &[u128], q: &[u128]| -> Vec<u128> {
+		let mut product = vec![0; 2 * k - 1];
+		for (i, a) in p.iter().enumerate() {
+			for (j, b) in q.iter().enumerate() {
+				product[i + j] = (product[i + j] + a * b) % m;
+			}
+		}
+		for i in (k..product.len()).rev() {
+			let top = product[i];
+			for (j, c) in coeffs.iter().enumerate() {
+				product[i - j - 1] = (product[i - j - 1] + top * c) % m;
+			}
+		}
+		product.truncate(k);
+		product
+	};
+
+	// Find x^n modulo the characteristic polynomial by repeated squaring.
+	let mut result = vec![0; k];
+	result[0] = 1 % m;
+	let mut base = vec![0; k];
+	if k == 1 {
+		base[0] = coeffs[0];
+	} else {
+		base[1] = 1;
+	}
+	let mut exponent = n;
+	while exponent > 0 {
+		if exponent & 1 == 1 {
+			result = mul_reduce(&result, &base);
+		}
+		base = mul_reduce(&base, &base);
+		exponent >>= 1;
+	}
+
+	// a(n) is the same combination of the initial terms.
+	result.iter()
+		.zip(init)
+		.fold(0, |acc, (r, &a)| (acc + r * (a as u128 % m)) % m) as u64
+}
+
+/// An iterator that produces the Pell numbers $0, 1, 2, 5, 12, 29, \ldots$,
+/// where $P(n) = 2P(n - 1) + P(n - 2)$. Returns None on overflow.
+///
+/// The fractions $(P(n - 1) + P(n)) / P(n)$ are the convergents of
+/// $\sqrt{2}$.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::fib::PellIterator;
+/// let terms: Vec<u64> = PellIterator::new().take(8).collect();
+/// assert_eq!(vec![0, 1, 2, 5, 12, 29, 70, 169], terms);
+///
+/// // 99/70 approximates the square root of 2
+/// let pell: Vec<u64> = PellIterator::new().take(8).collect();
+/// assert_eq!((99, 70), (pell[5] + pell[6], pell[6]));
+/// assert_eq!(52, PellIterator::<u64>::new().count());
+/// ```
+#[derive(Clone)]
+pub struct PellIterator<T = u64> {
+	p0: Option<T>,
+	p1: Option<T>,
+}
+
+impl<T: SequenceTerm> PellIterator<T> {
+	pub fn new() -> Self {
+		Self {
+			p0: Some(T::zero()),
+			p1: Some(T::one()),
+		}
+	}
+}
+
+impl<T: SequenceTerm> Default for PellIterator<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T: SequenceTerm> Iterator for PellIterator<T> {
+	type Item = T;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let result = self.p0.take()?;
+
+		let next = match &self.p1 {
+			Some(p1) => p1.checked_add(p1).and_then(|double| double.checked_add(&result)),
+			None => None,
+		};
+
+		self.p0 = self.p1.take();
+		self.p1 = next;
+
+		Some(result)
+	}
+}
+
+/// Computes the $n$th Pell number, with $P(0) = 0$, or None if it does not
+/// fit in a `u64`.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::fib;
+/// assert_eq!(Some(2378), fib::pell_nth(10));
+/// assert_eq!(None, fib::pell_nth(52));
+/// ```
+pub fn pell_nth(n: u64) -> Option<u64> {
+	// Only 52 terms fit in a u64, so stepping is as fast as anything else.
+	PellIterator::new().nth(usize::try_from(n).ok()?)
+}
+
+/// An iterator that produces the Jacobsthal numbers $0, 1, 1, 3, 5, 11,
+/// \ldots$, where $J(n) = J(n - 1) + 2J(n - 2)$. Returns None on overflow.
+///
+/// $J(n)$ counts the ways to tile a $2 \times (n - 1)$ board with $1 \times 2$
+/// dominoes and $2 \times 2$ squares.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::fib::JacobsthalIterator;
+/// let terms: Vec<u64> = JacobsthalIterator::new().take(8).collect();
+/// assert_eq!(vec![0, 1, 1, 3, 5, 11, 21, 43], terms);
+/// assert_eq!(66, JacobsthalIterator::<u64>::new().count());
+/// ```
+#[derive(Clone)]
+pub struct JacobsthalIterator<T = u64> {
+	j0: Option<T>,
+	j1: Option<T>,
+}
+
+impl<T: SequenceTerm> JacobsthalIterator<T> {
+	pub fn new() -> Self {
+		Self {
+			j0: Some(T::zero()),
+			j1: Some(T::one()),
+		}
+	}
+}
+
+impl<T: SequenceTerm> Default for JacobsthalIterator<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T: SequenceTerm> Iterator for JacobsthalIterator<T> {
+	type Item = T;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let result = self.j0.take()?;
+
+		let next = match &self.j1 {
+			Some(j1) => result.checked_add(&result).and_then(|double| double.checked_add(j1)),
+			None => None,
+		};
+
+		self.j0 = self.j1.take();
+		self.j1 = next;
+
+		Some(result)
+	}
+}
+
+/// Computes the $n$th Jacobsthal number, with $J(0) = 0$, or None if it does
+/// not fit in a `u64`. Uses the closed form $J(n) = (2^n - (-1)^n) / 3$.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::fib;
+/// assert_eq!(Some(341), fib::jacobsthal_nth(10));
+/// assert_eq!(Some(12_297_829_382_473_034_411), fib::jacobsthal_nth(65));
+/// assert_eq!(None, fib::jacobsthal_nth(66));
+/// ```
+pub fn jacobsthal_nth(n: u64) -> Option<u64> {
+	if n > 65 {
+		return None;
+	}
+
+	let power = 1u128 << n;
+	let numerator = if n.is_multiple_of(2) { power - 1 } else { power + 1 };
+	u64::try_from(numerator / 3).ok()
+}
+
+/// A sequence of non-negative integers, indexed from 0, that can be used
+/// without knowing which sequence it is.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::fib::{FibonacciSequence, PrimeSequence, Sequence, TriangularSequence};
+/// // Counts the terms of any sequence that lie below a bound
+/// fn count_below(sequence: &dyn Sequence, bound: u64) -> usize {
+/// 	sequence.iter().take_while(|&t| t < bound).count()
+/// }
+///
+/// assert_eq!(12, count_below(&FibonacciSequence, 100));
+/// assert_eq!(14, count_below(&TriangularSequence, 100));
+/// assert_eq!(25, count_below(&PrimeSequence, 100));
+/// ```
+pub trait Sequence {
+	/// The term at index `n`, or None if it does not fit in a `u64`.
+	fn nth(&self, n: u64) -> Option<u64>;
+
+	/// An iterator over the terms of the sequence, starting at index 0.
+	fn iter(&self) -> Box<dyn Iterator<Item = u64>>;
+
+	/// Whether `value` is a term of the sequence. The default implementation
+	/// assumes the sequence is non-decreasing and searches through the terms
+	/// up to `value`.
+	fn contains(&self, value: u64) -> bool {
+		self.iter().take_while(|&t| t <= value).any(|t| t == value)
+	}
+}
+
+/// The Fibonacci numbers as a [`Sequence`].
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::fib::{FibonacciSequence, Sequence};
+/// assert_eq!(Some(55), FibonacciSequence.nth(10));
+/// assert!(FibonacciSequence.contains(144));
+/// ```
+pub struct FibonacciSequence;
+
+impl Sequence for FibonacciSequence {
+	fn nth(&self, n: u64) -> Option<u64> {
+		nth(n)
+	}
+
+	fn iter(&self) -> Box<dyn Iterator<Item = u64>> {
+		Box::new(FibonacciIterator::new())
+	}
+
+	fn contains(&self, value: u64) -> bool {
+		is_fibonacci(value)
+	}
+}
+
+/// The Lucas numbers as a [`Sequence`].
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::fib::{LucasSequence, Sequence};
+/// assert_eq!(Some(123), LucasSequence.nth(10));
+/// assert!(LucasSequence.contains(1));
+/// assert!(!LucasSequence.contains(5));
+/// ```
+pub struct LucasSequence;
+
+impl Sequence for LucasSequence {
+	fn nth(&self, n: u64) -> Option<u64> {
+		lucas_nth(n)
+	}
+
+	fn iter(&self) -> Box<dyn Iterator<Item = u64>> {
+		Box::new(LucasIterator::new())
+	}
+
+	fn contains(&self, value: u64) -> bool {
+		// L(1) = 1 is the only term smaller than the one before it
+		value == 1 || self.iter().take_while(|&t| t <= value).any(|t| t == value)
+	}
+}
+
+/// The triangular numbers $0, 1, 3, 6, 10, \ldots$ as a [`Sequence`].
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::fib::{Sequence, TriangularSequence};
+/// assert_eq!(Some(55), TriangularSequence.nth(10));
+/// assert!(TriangularSequence.contains(5050));
+/// ```
+pub struct TriangularSequence;
+
+impl Sequence for TriangularSequence {
+	fn nth(&self, n: u64) -> Option<u64> {
+		numt::triangular_number(n).ok()
+	}
+
+	fn iter(&self) -> Box<dyn Iterator<Item = u64>> {
+		Box::new((0..).map_while(|n| numt::triangular_number(n).ok()))
+	}
+
+	fn contains(&self, value: u64) -> bool {
+		// value is triangular exactly when 8 * value + 1 is a perfect square
+		let m = 8 * value as u128 + 1;
+		m.isqrt() * m.isqrt() == m
+	}
+}
+
+/// The prime numbers $2, 3, 5, 7, \ldots$ as a [`Sequence`].
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::fib::{PrimeSequence, Sequence};
+/// assert_eq!(Some(31), PrimeSequence.nth(10));
+/// assert!(PrimeSequence.contains(1_000_000_007));
+/// ```
+pub struct PrimeSequence;
+
+impl Sequence for PrimeSequence {
+	fn nth(&self, n: u64) -> Option<u64> {
+		numt::Primes::new().nth(usize::try_from(n).ok()?)
+	}
+
+	fn iter(&self) -> Box<dyn Iterator<Item = u64>> {
+		Box::new(numt::Primes::new())
+	}
+
+	fn contains(&self, value: u64) -> bool {
+		numt::is_prime(value)
+	}
+}
+
+/// An iterator over the s-gonal numbers $P(s, n) = ((s - 2)n^2 - (s - 4)n) / 2$
+/// for $n = 0, 1, 2, \ldots$, which count the dots in a regular polygon with
+/// `s` sides. Returns None on overflow. Panics if there are fewer than 3
+/// sides.
+///
+/// The term at index n of [`PolygonalNumbers::triangular`] is
+/// [`crate::numt::triangular_number`] of n.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::fib::PolygonalNumbers;
+/// let pentagonal: Vec<u64> = PolygonalNumbers::pentagonal().take(6).collect();
+/// assert_eq!(vec![0, 1, 5, 12, 22, 35], pentagonal);
+///
+/// let octagonal: Vec<u64> = PolygonalNumbers::new(8).take(5).collect();
+/// assert_eq!(vec![0, 1, 8, 21, 40], octagonal);
+///
+/// // Project Euler 45: numbers that are both hexagonal and pentagonal
+/// let mut pentagonal = PolygonalNumbers::pentagonal().peekable();
+/// let both: Vec<u64> = PolygonalNumbers::hexagonal()
+/// 	.filter(|&h| {
+/// 		while pentagonal.next_if(|&p| p < h).is_some() {}
+/// 		pentagonal.peek() == Some(&h)
+/// 	})
+/// 	.take(3)
+/// 	.collect();
+/// assert_eq!(vec![0, 1, 40755], both);
+/// ```
+#[derive(Clone)]
+pub struct PolygonalNumbers {
+	sides: u64,
+	// Index and value of the next term, unless it has overflown
+	n: u64,
+	next_term: Option<u64>,
+}
+
+impl PolygonalNumbers {
+	pub fn new(sides: u64) -> Self {
+		if sides < 3 {
+			panic!("a polygon needs at least 3 sides");
+		}
+
+		Self {
+			sides,
+			n: 0,
+			next_term: Some(0),
+		}
+	}
+
+	pub fn triangular() -> Self {
+		Self::new(3)
+	}
+
+	pub fn square() -> Self {
+		Self::new(4)
+	}
+
+	pub fn pentagonal() -> Self {
+		Self::new(5)
+	}
+
+	pub fn hexagonal() -> Self {
+		Self::new(6)
+	}
+}
+
+impl Iterator for PolygonalNumbers {
+	type Item = u64;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let result = self.next_term?;
+
+		// P(s, n + 1) = P(s, n) + (s - 2)n + 1
+		self.next_term = (self.sides - 2).checked_mul(self.n)
+			.and_then(|step| step.checked_add(1))
+			.and_then(|step| result.checked_add(step));
+		self.n += 1;
+
+		Some(result)
+	}
+}
+
+/// An endless iterator over the look-and-say sequence $1, 11, 21, 1211,
+/// 111221, \ldots$, where each term describes the runs of digits in the one
+/// before it. Terms are produced as digit vectors, most-significant digit
+/// first.
+///
+/// Terms grow by about 30% at each step, so every term is built in a single
+/// pass over the runs of the previous one, with its final capacity reserved
+/// up front.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::fib::LookAndSay;
+/// let terms: Vec<Vec<u8>> = LookAndSay::new().take(5).collect();
+/// assert_eq!(vec![1, 2, 1, 1], terms[3]);
+///
+/// let as_string: String = terms[4].iter().map(|d| (b'0' + d) as char).collect();
+/// assert_eq!("111221", as_string);
+///
+/// assert_eq!(Some(5808), LookAndSay::new().nth(30).map(|t| t.len()));
+/// ```
+#[derive(Clone)]
+pub struct LookAndSay {
+	current: Vec<u8>,
+}
+
+impl LookAndSay {
+	pub fn new() -> Self {
+		Self {
+			current: vec![1],
+		}
+	}
+
+	fn describe(term: &[u8]) -> Vec<u8> {
+		let mut description = Vec::with_capacity(term.len() + term.len() / 3 + 2);
+		for run in term.chunk_by(|a, b| a == b) {
+			// Runs longer than 9 only occur for unusual starting terms
+			let count_start = description.len();
+			let mut count = run.len();
+			while count > 0 {
+				description.push((count % 10) as u8);
+				count /= 10;
+			}
+			description[count_start..].reverse();
+			description.push(run[0]);
+		}
+		description
+	}
+}
+
+impl Default for LookAndSay {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Iterator for LookAndSay {
+	type Item = Vec<u8>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let next = Self::describe(&self.current);
+		Some(std::mem::replace(&mut self.current, next))
+	}
+}
+
+/// An endless iterator over the Catalan numbers $1, 1, 2, 5, 14, 42, \ldots$,
+/// where $C(n) = \binom{2n}{n} / (n + 1)$. Each term is found from the one
+/// before it using $C(n + 1) = 2(2n + 1)C(n) / (n + 2)$, so computing many
+/// terms in order is cheaper than finding each one from scratch.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::BigInt;
+/// use segtrs::fib::CatalanIterator;
+/// let terms: Vec<BigInt> = CatalanIterator::new().take(7).collect();
+/// assert_eq!(BigInt::from_int(132), terms[6]);
+///
+/// // The number of ways to triangulate a polygon with 32 sides is C(30)
+/// let c30 = CatalanIterator::new().nth(30).unwrap();
+/// assert_eq!(BigInt::from_int(3_814_986_502_092_304), c30);
+/// ```
+#[derive(Clone)]
+pub struct CatalanIterator {
+	n: u64,
+	current: BigInt,
+}
+
+impl CatalanIterator {
+	pub fn new() -> Self {
+		Self {
+			n: 0,
+			current: BigInt::from_int(1),
+		}
+	}
+}
+
+impl Default for CatalanIterator {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Iterator for CatalanIterator {
+	type Item = BigInt;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let n = self.n;
+		let product = self.current.multiply(&BigInt::from_int(2 * (2 * n + 1)));
+		// The division is always exact
+		let (next, _) = product.divide_int(n + 2);
+		self.n += 1;
+
+		Some(std::mem::replace(&mut self.current, next))
+	}
+}
+
+/// An endless iterator over the convergents $F(n + 1) / F(n)$ of the golden
+/// ratio $\varphi = (1 + \sqrt{5}) / 2$, starting at $n = 1$, as
+/// `(numerator, denominator)` pairs. These are the convergents of the
+/// continued fraction $[1; 1, 1, \ldots]$, so each one is the best rational
+/// approximation of $\varphi$ for its denominator, and the error shrinks like
+/// $1 / F(n)^2$.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::BigInt;
+/// use segtrs::fib::GoldenRatioConvergents;
+/// let (h, k) = GoldenRatioConvergents::new().nth(5).unwrap();
+/// assert_eq!((BigInt::from_int(13), BigInt::from_int(8)), (h, k));
+/// ```
+#[derive(Clone)]
+pub struct GoldenRatioConvergents {
+	terms: BigFibonacciIterator,
+	// The most recent Fibonacci number taken from terms
+	previous: BigInt,
+}
+
+impl GoldenRatioConvergents {
+	pub fn new() -> Self {
+		let mut terms = BigFibonacciIterator::new();
+		terms.next();
+		let previous = terms.next().unwrap();
+		Self {
+			terms,
+			previous,
+		}
+	}
+}
+
+impl Default for GoldenRatioConvergents {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Iterator for GoldenRatioConvergents {
+	type Item = (BigInt, BigInt);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let numerator = self.terms.next()?;
+		let denominator = std::mem::replace(&mut self.previous, numerator.clone());
+		Some((numerator, denominator))
+	}
+}
+
+/// An iterator that produces the Padovan numbers $1, 1, 1, 2, 2, 3, 4, 5,
+/// \ldots$, where $P(n) = P(n - 2) + P(n - 3)$. Returns None on overflow. Like
+/// [`FibonacciIterator`], the terms may be of any [`SequenceTerm`] type, and
+/// other starting terms can be given with [`PadovanIterator::with_seeds`].
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::fib::PadovanIterator;
+/// let terms: Vec<u64> = PadovanIterator::new().take(10).collect();
+/// assert_eq!(vec![1, 1, 1, 2, 2, 3, 4, 5, 7, 9], terms);
+/// assert_eq!(159, PadovanIterator::<u64>::new().count());
+/// ```
+#[derive(Clone)]
+pub struct PadovanIterator<T = u64> {
+	// The next three terms to be returned, if any
+	p0: Option<T>,
+	p1: Option<T>,
+	p2: Option<T>,
+}
+
+impl<T: SequenceTerm> PadovanIterator<T> {
+	pub fn new() -> Self {
+		Self::with_seeds(T::one(), T::one(), T::one())
+	}
+
+	/// Create an iterator for the sequence whose first three terms are `a`,
+	/// `b` and `c`, and where every later term is the sum of the second and
+	/// third terms before it.
+	pub fn with_seeds(a: T, b: T, c: T) -> Self {
+		Self {
+			p0: Some(a),
+			p1: Some(b),
+			p2: Some(c),
+		}
+	}
+}
+
+impl<T: SequenceTerm> Default for PadovanIterator<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T: SequenceTerm> Iterator for PadovanIterator<T> {
+	type Item = T;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let result = self.p0.take()?;
+
+		let next = match &self.p1 {
+			Some(p1) => result.checked_add(p1),
+			None => None,
+		};
+
+		self.p0 = self.p1.take();
+		self.p1 = self.p2.take();
+		self.p2 = next;
+
+		Some(result)
+	}
+}
+
+/// Computes the $n$th Padovan number, with $P(0) = 1$, or None if it does not
+/// fit in a `u64`.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::fib;
+/// assert_eq!(Some(200), fib::padovan_nth(20));
+/// assert_eq!(None, fib::padovan_nth(159));
+/// ```
+pub fn padovan_nth(n: u64) -> Option<u64> {
+	// Only 159 terms fit in a u64, so stepping is as fast as anything else.
+	PadovanIterator::new().nth(usize::try_from(n).ok()?)
+}
+
+/// An iterator that produces the Perrin numbers $3, 0, 2, 3, 2, 5, 5, 7,
+/// \ldots$, which follow the same recurrence as the Padovan numbers but start
+/// from 3, 0 and 2. Returns None on overflow.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::fib::PerrinIterator;
+/// let terms: Vec<u64> = PerrinIterator::new().take(10).collect();
+/// assert_eq!(vec![3, 0, 2, 3, 2, 5, 5, 7, 10, 12], terms);
+/// assert_eq!(158, PerrinIterator::<u64>::new().count());
+/// ```
+#[derive(Clone)]
+pub struct PerrinIterator<T = u64> {
+	terms: PadovanIterator<T>,
+}
+
+impl<T: SequenceTerm> PerrinIterator<T> {
+	pub fn new() -> Self {
+		let one = T::one();
+		let two = one.checked_add(&one).unwrap();
+		let three = two.checked_add(&one).unwrap();
+		Self {
+			terms: PadovanIterator::with_seeds(three, T::zero(), two),
+		}
+	}
+}
+
+impl<T: SequenceTerm> Default for PerrinIterator<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T: SequenceTerm> Iterator for PerrinIterator<T> {
+	type Item = T;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.terms.next()
+	}
+}
+
+/// Computes the $n$th Perrin number, with $P(0) = 3$, or None if it does not
+/// fit in a `u64`.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::fib;
+/// assert_eq!(Some(277), fib::perrin_nth(20));
+/// assert_eq!(None, fib::perrin_nth(158));
+/// ```
+pub fn perrin_nth(n: u64) -> Option<u64> {
+	PerrinIterator::new().nth(usize::try_from(n).ok()?)
+}
+
+/// Determine whether `n` passes the Perrin test, that is, whether $n > 1$
+/// divides the $n$th Perrin number. Every prime passes, but so do a few
+/// composites, the Perrin pseudoprimes, the smallest of which is
+/// $271441 = 521^2$.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::fib;
+/// assert!(fib::is_perrin_probable_prime(1_000_000_007));
+/// assert!(!fib::is_perrin_probable_prime(1_000_000_005));
+/// assert!(fib::is_perrin_probable_prime(271_441));
+/// ```
+pub fn is_perrin_probable_prime(n: u64) -> bool {
+	n > 1 && linear_recurrence_nth(&[0, 1, 1], &[3, 0, 2], n, n) == 0
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn fibonacci_u64_last_term() {
+		let last = FibonacciIterator::<u64>::new().last();
+		assert_eq!(Some(12_200_160_415_121_876_738), last);
+	}
+
+	#[test]
+	fn fibonacci_bigint_past_u128() {
+		let term = FibonacciIterator::<BigInt>::new().nth(200).unwrap();
+		// F(200) = 280571172992510140037611932413038677189525
+		let digits = "280571172992510140037611932413038677189525";
+		let expected = BigInt::new(digits.bytes().rev().map(|b| b - b'0'));
+		assert_eq!(expected, term);
+	}
+
+	#[test]
+	fn big_fibonacci_does_not_stop() {
+		let mut fib = BigFibonacciIterator::new();
+		assert_eq!(Some(BigInt::from_int(0)), fib.next());
+		assert!(fib.nth(1000).is_some());
+	}
+
+	#[test]
+	fn fibonacci_types_agree() {
+		let small: Vec<u128> = FibonacciIterator::<u64>::new()
+			.map(|f| f as u128)
+			.collect();
+		let wide: Vec<u128> = FibonacciIterator::<u128>::new()
+			.take(small.len())
+			.collect();
+		assert_eq!(small, wide);
+	}
+
+	#[test]
+	fn nth_matches_iterator() {
+		for (n, f) in FibonacciIterator::<u64>::new().enumerate() {
+			assert_eq!(Some(f), nth(n as u64));
+		}
+		assert_eq!(None, nth(u64::MAX));
+	}
+
+	#[test]
+	fn nth_big_matches_iterator() {
+		for (n, f) in BigFibonacciIterator::new().take(300).enumerate() {
+			assert_eq!(f, nth_big(n as u64));
+		}
+	}
+
+	#[test]
+	fn lucas_nth_matches_iterator() {
+		let terms: Vec<u64> = LucasIterator::new().collect();
+		assert_eq!(93, terms.len());
+		for (n, &l) in terms.iter().enumerate() {
+			assert_eq!(Some(l), lucas_nth(n as u64));
+		}
+		assert_eq!(None, lucas_nth(93));
+	}
+
+	#[test]
+	fn lucas_fibonacci_identity() {
+		// L(n) = F(n - 1) + F(n + 1)
+		let fib: Vec<u128> = FibonacciIterator::<u128>::new().take(102).collect();
+		let lucas: Vec<u128> = LucasIterator::<u128>::new().take(101).collect();
+		for n in 1..=100 {
+			assert_eq!(fib[n - 1] + fib[n + 1], lucas[n]);
+		}
+	}
+
+	#[test]
+	fn kbonacci_two_is_fibonacci() {
+		let kbonacci: Vec<u64> = KBonacciIterator::new(2).collect();
+		let fibonacci: Vec<u64> = FibonacciIterator::new().collect();
+		assert_eq!(fibonacci, kbonacci);
+	}
+
+	#[test]
+	fn kbonacci_small_orders() {
+		let ones: Vec<u64> = KBonacciIterator::new(1).take(4).collect();
+		assert_eq!(vec![1, 1, 1, 1], ones);
+		let tetranacci: Vec<u64> = KBonacciIterator::new(4).take(10).collect();
+		assert_eq!(vec![0, 0, 0, 1, 1, 2, 4, 8, 15, 29], tetranacci);
+	}
+
+	#[test]
+	fn kbonacci_big_terms() {
+		// With large k, the early terms are powers of 2
+		let term = KBonacciIterator::<BigInt>::new(100).nth(99 + 70).unwrap();
+		assert_eq!(BigInt::from_int(1 << 63).multiply(&BigInt::from_int(1 << 6)), term);
+	}
+
+	#[test]
+	#[should_panic(expected = "k must be positive")]
+	fn kbonacci_zero() {
+		KBonacciIterator::<u64>::new(0);
+	}
+
+	#[test]
+	fn with_seeds_matches_shifted_fibonacci() {
+		let shifted: Vec<u64> = FibonacciIterator::new().skip(10).collect();
+		let seeded: Vec<u64> = FibonacciIterator::with_seeds(55, 89).collect();
+		assert_eq!(shifted, seeded);
+
+		let lucas: Vec<u64> = LucasIterator::new().collect();
+		let seeded: Vec<u64> = FibonacciIterator::with_seeds(2, 1).collect();
+		assert_eq!(lucas, seeded);
+	}
+
+	#[test]
+	fn with_seeds_overflowing_immediately() {
+		let terms: Vec<u64> = FibonacciIterator::with_seeds(u64::MAX, 1).collect();
+		assert_eq!(vec![u64::MAX, 1], terms);
+	}
+
+	#[test]
+	fn nth_signed_satisfies_recurrence() {
+		for n in -90..=90 {
+			let (a, b, c) = (nth_signed(n), nth_signed(n + 1), nth_signed(n + 2));
+			assert_eq!(c.unwrap(), a.unwrap() + b.unwrap());
+		}
+	}
+
+	#[test]
+	fn nth_signed_limits() {
+		assert_eq!(Some(7_540_113_804_746_346_429), nth_signed(92));
+		assert_eq!(Some(-7_540_113_804_746_346_429), nth_signed(-92));
+		assert_eq!(None, nth_signed(93));
+		assert_eq!(None, nth_signed(i64::MIN));
+	}
+
+	#[test]
+	fn zeckendorf_terms_are_non_consecutive() {
+		let fibs: Vec<u64> = FibonacciIterator::new().skip(2).collect();
+		for n in 0..5000 {
+			let terms = zeckendorf(n);
+			assert_eq!(n, terms.iter().sum::<u64>());
+			let indices: Vec<usize> = terms
+				.iter()
+				.map(|t| fibs.iter().position(|f| f == t).unwrap())
+				.collect();
+			assert!(indices.windows(2).all(|w| w[0] > w[1] + 1));
+		}
+	}
+
+	#[test]
+	fn zeckendorf_bits_small_values() {
+		let bits: Vec<String> = (1..=8).map(zeckendorf_bits).collect();
+		assert_eq!(
+			vec!["1", "10", "100", "101", "1000", "1001", "1010", "10000"],
+			bits,
+		);
+		assert!(!zeckendorf_bits(u64::MAX).contains("11"));
+	}
+
+	#[test]
+	fn is_fibonacci_matches_iterator() {
+		let fibs: Vec<u64> = FibonacciIterator::new().collect();
+		for n in 0..10_000 {
+			assert_eq!(fibs.contains(&n), is_fibonacci(n));
+		}
+		for (i, &f) in fibs.iter().enumerate().skip(3) {
+			assert!(is_fibonacci(f));
+			assert_eq!(Some(i as u64), index_of(f));
+		}
+	}
+
+	#[test]
+	fn index_of_small_and_large_values() {
+		assert_eq!(Some(0), index_of(0));
+		assert_eq!(Some(1), index_of(1));
+		assert_eq!(Some(93), index_of(12_200_160_415_121_876_738));
+		assert_eq!(None, index_of(u64::MAX));
+	}
+
+	#[test]
+	fn nth_method_matches_stepping() {
+		for seeds in [(0, 1), (2, 1), (5, 0), (7, 3)] {
+			for n in 0..100 {
+				let mut stepped = FibonacciIterator::<u64>::with_seeds(seeds.0, seeds.1);
+				let mut jumped = stepped.clone();
+				for _ in 0..n {
+					stepped.next();
+				}
+				assert_eq!(stepped.next(), jumped.nth(n));
+				assert_eq!(stepped.collect::<Vec<_>>(), jumped.collect::<Vec<_>>());
+			}
+		}
+	}
+
+	#[test]
+	fn nth_method_big_and_past_overflow() {
+		let expected = nth_big(1000);
+		assert_eq!(Some(expected), BigFibonacciIterator::new().nth(1000));
+
+		let mut it = FibonacciIterator::<u64>::new();
+		assert_eq!(None, it.nth(1_000_000));
+		assert_eq!(None, it.next());
+		assert_eq!(Some(0), FibonacciIterator::<u64>::with_seeds(0, 0).nth(usize::MAX));
+	}
+
+	#[test]
+	fn size_hint_bounds() {
+		let mut it = FibonacciIterator::<u64>::new();
+		assert_eq!((2, None), it.size_hint());
+		it.nth(92);
+		assert_eq!((1, Some(1)), it.size_hint());
+		it.next();
+		assert_eq!((0, Some(0)), it.size_hint());
+	}
+
+	#[test]
+	fn linear_recurrence_matches_fibonacci() {
+		for n in 0..94 {
+			assert_eq!(nth(n), Some(linear_recurrence_nth(&[1, 1], &[0, 1], n, u64::MAX)));
+		}
+		// F(10^18) mod 10^9 + 7, a well-known value
+		let n = 1_000_000_000_000_000_000;
+		assert_eq!(209_783_453, linear_recurrence_nth(&[1, 1], &[0, 1], n, 1_000_000_007));
+	}
+
+	#[test]
+	fn linear_recurrence_small_orders() {
+		// a(n) = 3a(n - 1) gives powers of 3
+		assert_eq!(3u64.pow(20) % 1000, linear_recurrence_nth(&[3], &[1], 20, 1000));
+		let expected: Vec<u64> = KBonacciIterator::new(3).take(40).collect();
+		for (n, t) in expected.into_iter().enumerate() {
+			assert_eq!(t, linear_recurrence_nth(&[1, 1, 1], &[0, 0, 1], n as u64, u64::MAX));
+		}
+		assert_eq!(0, linear_recurrence_nth(&[1, 1], &[5, 8], 100, 1));
+	}
+
+	#[test]
+	#[should_panic(expected = "one initial term")]
+	fn linear_recurrence_mismatched_lengths() {
+		linear_recurrence_nth(&[1, 1], &[1], 10, 7);
+	}
+
+	#[test]
+	fn pell_nth_matches_iterator() {
+		for (n, p) in PellIterator::new().enumerate() {
+			assert_eq!(Some(p), pell_nth(n as u64));
+		}
+		let big: Vec<BigInt> = PellIterator::new().skip(50).take(5).collect();
+		assert_eq!(BigInt::from_int(pell_nth(51).unwrap()), big[1]);
+		assert_eq!(5, big.len());
+	}
+
+	#[test]
+	fn jacobsthal_nth_matches_iterator() {
+		for (n, j) in JacobsthalIterator::new().enumerate() {
+			assert_eq!(Some(j), jacobsthal_nth(n as u64));
+		}
+		assert_eq!(None, jacobsthal_nth(u64::MAX));
+	}
+
+	#[test]
+	fn sequences_agree_with_their_iterators() {
+		let sequences: [&dyn Sequence; 4] = [
+			&FibonacciSequence, &LucasSequence, &TriangularSequence, &PrimeSequence,
+		];
+		for sequence in sequences {
+			for (n, t) in sequence.iter().take(50).enumerate() {
+				assert_eq!(Some(t), sequence.nth(n as u64));
+				assert!(sequence.contains(t));
+			}
+		}
+	}
+
+	#[test]
+	fn sequence_contains_rejects_non_terms() {
+		assert!(!FibonacciSequence.contains(4));
+		assert!(!LucasSequence.contains(0));
+		assert!(!TriangularSequence.contains(u64::MAX));
+		assert!(!PrimeSequence.contains(1));
+		assert_eq!(None, TriangularSequence.nth(u64::MAX));
+	}
+
+	#[test]
+	fn triangular_sequence_near_overflow() {
+		// T(6074000999) is the largest triangular number that fits in a u64
+		let largest = TriangularSequence.nth(6_074_000_999);
+		assert_eq!(Some(18_446_744_070_963_499_500), largest);
+		assert!(TriangularSequence.contains(largest.unwrap()));
+		// (n + 1) * n alone would overflow here
+		assert_eq!(Some((1 << 63) + (1 << 31)), TriangularSequence.nth(1 << 32));
+		assert_eq!(None, TriangularSequence.nth(6_074_001_000));
+	}
+
+	#[test]
+	fn polygonal_numbers_match_formulas() {
+		let triangular: Vec<u64> = PolygonalNumbers::triangular().take(100).collect();
+		let expected: Vec<u64> = (0..100).map(|n| numt::triangular_number(n).unwrap()).collect();
+		assert_eq!(expected, triangular);
+
+		for (n, s) in PolygonalNumbers::square().take(100).enumerate() {
+			assert_eq!((n * n) as u64, s);
+		}
+		for (n, h) in PolygonalNumbers::hexagonal().take(100).enumerate() {
+			assert_eq!((2 * n * n - n) as u64, h);
+		}
+	}
+
+	#[test]
+	fn polygonal_numbers_stop_on_overflow() {
+		let terms: Vec<u64> = PolygonalNumbers::new(u64::MAX).collect();
+		assert_eq!(vec![0, 1, u64::MAX], terms);
+	}
+
+	#[test]
+	#[should_panic(expected = "at least 3 sides")]
+	fn polygonal_numbers_too_few_sides() {
+		PolygonalNumbers::new(2);
+	}
+
+	#[test]
+	fn look_and_say_first_terms() {
+		let terms: Vec<Vec<u8>> = LookAndSay::new().take(8).collect();
+		assert_eq!(vec![1, 3, 1, 1, 2, 2, 2, 1], terms[6]);
+		assert_eq!(vec![1, 1, 1, 3, 2, 1, 3, 2, 1, 1], terms[7]);
+	}
+
+	#[test]
+	fn look_and_say_long_runs() {
+		assert_eq!(vec![1, 2, 7], LookAndSay::describe(&[7; 12]));
+	}
+
+	#[test]
+	fn catalan_matches_binomial_formula() {
+		for (n, c) in CatalanIterator::new().take(60).enumerate() {
+			let n = n as u64;
+			let (expected, remainder) = numt::binomial_big(2 * n, n).divide_int(n + 1);
+			assert_eq!(0, remainder);
+			assert_eq!(expected, c);
+		}
+	}
+
+	#[test]
+	fn golden_ratio_convergents_match_continued_fraction() {
+		let expected: Vec<(BigInt, BigInt)> = numt::Convergents::new(std::iter::repeat(1))
+			.take(200)
+			.collect();
+		let produced: Vec<(BigInt, BigInt)> = GoldenRatioConvergents::new().take(200).collect();
+		assert_eq!(expected, produced);
+	}
+
+	#[test]
+	fn golden_ratio_convergents_alternate_around_phi() {
+		// h/k > phi exactly when h^2 - hk - k^2 > 0, which alternates with n
+		for (n, (h, k)) in GoldenRatioConvergents::new().take(50).enumerate() {
+			let hk_plus_k2 = h.multiply(&k).add(&k.multiply(&k));
+			assert_eq!(n % 2 == 0, h.multiply(&h) < hk_plus_k2);
+		}
+	}
+
+	#[test]
+	fn wrapping_policy_matches_modular_arithmetic() {
+		let wrapped: Vec<u64> = FibonacciIterator::new()
+			.overflow_policy(OverflowPolicy::Wrap)
+			.take(300)
+			.collect();
+		let modulus = u64::MAX as u128 + 1;
+		for (n, f) in wrapped.iter().enumerate() {
+			if n < 94 {
+				assert_eq!(nth(n as u64), Some(*f));
+			}
+			let jumped = FibonacciIterator::<u64>::new()
+				.overflow_policy(OverflowPolicy::Wrap)
+				.nth(n);
+			assert_eq!(Some(*f), jumped);
+		}
+		// F(n) mod 2^64 also follows from the u128 fast doubling up to F(186)
+		assert_eq!((fibonacci_pair(150).0 % modulus) as u64, wrapped[150]);
+	}
+
+	#[test]
+	fn saturating_policy_stays_at_max() {
+		let mut it = FibonacciIterator::<u64>::new().overflow_policy(OverflowPolicy::Saturate);
+		assert_eq!(Some(u64::MAX), it.clone().nth(1_000_000));
+		assert_eq!(Some(u64::MAX), it.nth(94));
+		assert_eq!(Some(u64::MAX), it.next());
+		assert_eq!((usize::MAX, None), it.size_hint());
+	}
+
+	#[test]
+	fn promoting_matches_big_iterator() {
+		let promoted: Vec<BigInt> = FibonacciIterator::<u64>::with_seeds(2, 1)
+			.promote_on_overflow()
+			.take(300)
+			.collect();
+		let big: Vec<BigInt> = LucasIterator::new().take(300).collect();
+		assert_eq!(big, promoted);
+	}
+
+	#[test]
+	fn promoting_after_overflow() {
+		let expected: Vec<BigInt> = BigFibonacciIterator::new().skip(92).take(5).collect();
+		for skipped in 92..=94 {
+			let mut it = FibonacciIterator::<u64>::new().skip_to(92);
+			for _ in 92..skipped {
+				it.next();
+			}
+			let promoted: Vec<BigInt> = it.promote_on_overflow().take(97 - skipped).collect();
+			assert_eq!(expected[skipped - 92..], promoted[..]);
+		}
+	}
+
+	#[test]
+	fn enumerated_indices_follow_skips() {
+		let mut it = FibonacciIterator::<u64>::new();
+		it.nth(9);
+		let pairs: Vec<(u64, u64)> = it.skip_to(20).enumerated().take(3).collect();
+		assert_eq!(vec![(20, 6765), (21, 10946), (22, 17711)], pairs);
+
+		let last = FibonacciIterator::<u64>::new().enumerated().last();
+		assert_eq!(Some((93, nth(93).unwrap())), last);
+	}
+
+	#[test]
+	fn skip_to_seeded_and_saturated() {
+		let mut lucas = FibonacciIterator::<u64>::with_seeds(2, 1).skip_to(10);
+		assert_eq!(lucas_nth(10), lucas.next());
+
+		let saturated = FibonacciIterator::<u64>::new()
+			.overflow_policy(OverflowPolicy::Saturate)
+			.skip_to(1_000)
+			.enumerated()
+			.next();
+		assert_eq!(Some((1_000, u64::MAX)), saturated);
+	}
+
+	#[test]
+	#[should_panic(expected = "cannot skip back")]
+	fn skip_to_earlier_term() {
+		FibonacciIterator::<u64>::new().skip_to(5).skip_to(4);
+	}
+
+	#[test]
+	fn padovan_and_perrin_nth_match_iterators() {
+		for (n, p) in PadovanIterator::new().enumerate() {
+			assert_eq!(Some(p), padovan_nth(n as u64));
+		}
+		for (n, p) in PerrinIterator::new().enumerate() {
+			assert_eq!(Some(p), perrin_nth(n as u64));
+		}
+		let big: Vec<BigInt> = PerrinIterator::new().take(200).collect();
+		assert_eq!(BigInt::from_int(perrin_nth(157).unwrap()), big[157]);
+	}
+
+	#[test]
+	fn perrin_test_agrees_with_primality() {
+		for n in 0..5000 {
+			assert_eq!(numt::is_prime(n), is_perrin_probable_prime(n));
+		}
+		assert!(is_perrin_probable_prime(271_441));
+		assert!(!is_perrin_probable_prime(271_443));
+	}
 }
@@ -0,0 +1,280 @@
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+
+use crate::numt;
+
+/// A Gaussian integer $a + bi$, where $a$ and $b$ are integers.
+///
+/// Both parts are `i64`s; there is no [`BigInt`](crate::BigInt) version.
+/// The norm is a `u128`, so it never overflows, but the arithmetic
+/// operators overflow like `i64` arithmetic does, panicking in debug builds,
+/// and [`GaussianInt::div_rem`] panics if its results do not fit.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::gaussian::GaussianInt;
+/// let z = GaussianInt::new(2, 1);
+/// let w = z * z.conjugate();
+/// assert_eq!(GaussianInt::from(5), w);
+/// assert_eq!(5, z.norm());
+/// assert_eq!("2-1i", z.conjugate().to_string());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GaussianInt {
+	re: i64,
+	im: i64,
+}
+
+impl GaussianInt {
+	pub fn new(re: i64, im: i64) -> Self {
+		Self { re, im }
+	}
+
+	/// The real part.
+	pub fn re(&self) -> i64 {
+		self.re
+	}
+
+	/// The imaginary part.
+	pub fn im(&self) -> i64 {
+		self.im
+	}
+
+	pub fn conjugate(&self) -> Self {
+		Self::new(self.re, -self.im)
+	}
+
+	/// The norm $a^2 + b^2$, which is multiplicative: $N(zw) = N(z)N(w)$.
+	pub fn norm(&self) -> u128 {
+		let (a, b) = (self.re.unsigned_abs() as u128, self.im.unsigned_abs() as u128);
+		a * a + b * b
+	}
+
+	/// Whether `self` is one of the units 1, -1, i and -i.
+	pub fn is_unit(&self) -> bool {
+		self.norm() == 1
+	}
+
+	/// Divide `self` by `other`, giving a quotient $q$ and remainder $r$ with
+	/// $\text{self} = q \cdot \text{other} + r$ and $N(r) < N(\text{other})$.
+	/// The quotient is the exact quotient rounded to the nearest Gaussian
+	/// integer. Panics if `other` is 0, or if the quotient or remainder does
+	/// not fit in a `GaussianInt`, as when dividing by a unit near the limits
+	/// of `i64`.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::gaussian::GaussianInt;
+	/// let a = GaussianInt::new(27, -23);
+	/// let b = GaussianInt::new(8, 1);
+	/// let (q, r) = a.div_rem(&b);
+	/// assert_eq!(a, q * b + r);
+	/// assert!(r.norm() < b.norm());
+	/// ```
+	pub fn div_rem(&self, other: &GaussianInt) -> (GaussianInt, GaussianInt) {
+		let n = other.norm();
+		if n == 0 {
+			panic!("division by zero");
+		}
+		// self / other = self * conj(other) / N(other). The numerator and the
+		// norm can reach 2^127, so they are kept as u128 magnitudes.
+		let (a, b) = (self.re as i128, self.im as i128);
+		let (c, d) = (other.re as i128, other.im as i128);
+		let round = |terms: [i128; 2]| {
+			let (positive, negative) = terms.iter().fold((0u128, 0u128), |(p, m), &t| {
+				if t >= 0 { (p + t as u128, m) } else { (p, m + t.unsigned_abs()) }
+			});
+			if positive >= negative {
+				let x = positive - negative;
+				let (q, r) = (x / n, x % n);
+				i64::try_from(q + (r >= n - r) as u128).ok()
+			} else {
+				let x = negative - positive;
+				let (q, r) = (x / n, x % n);
+				let q = i128::try_from(q + (r > n - r) as u128).ok()?;
+				i64::try_from(-q).ok()
+			}
+		};
+		let q = round([a * c, b * d]).zip(round([b * c, -(a * d)]));
+		// The remainder is computed in i128, since q * other may not fit
+		let r = q.and_then(|(qa, qb)| {
+			let (qa, qb) = (qa as i128, qb as i128);
+			let re = a.checked_sub((qa * c).checked_sub(qb * d)?)?;
+			let im = b.checked_sub((qa * d).checked_add(qb * c)?)?;
+			let (re, im) = (i64::try_from(re).ok()?, i64::try_from(im).ok()?);
+			Some(GaussianInt::new(re, im))
+		});
+		let (Some((qa, qb)), Some(r)) = (q, r) else {
+			panic!("quotient of {self} by {other} does not fit in a GaussianInt");
+		};
+		(GaussianInt::new(qa, qb), r)
+	}
+
+	/// A greatest common divisor, which is unique up to multiplication by a
+	/// unit.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::gaussian::GaussianInt;
+	/// let g = GaussianInt::new(3, 4).gcd(&GaussianInt::new(1, 3));
+	/// // 3 + 4i = (2 + i)^2 and 1 + 3i = (1 + i)(2 + i)
+	/// assert_eq!(5, g.norm());
+	/// ```
+	pub fn gcd(&self, other: &GaussianInt) -> GaussianInt {
+		let (mut p, mut q) = (*self, *other);
+		while q != GaussianInt::from(0) {
+			let (_, r) = p.div_rem(&q);
+			p = q;
+			q = r;
+		}
+		p
+	}
+
+	/// Determines whether `self` is a Gaussian prime: either its norm is a
+	/// prime, or it is a unit times a prime $p \equiv 3 \pmod 4$. Panics if
+	/// the norm does not fit in a `u64`.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::gaussian::GaussianInt;
+	/// assert!(GaussianInt::new(1, 1).is_prime());
+	/// assert!(GaussianInt::new(0, -3).is_prime());
+	/// // 5 = (2 + i)(2 - i)
+	/// assert!(!GaussianInt::new(5, 0).is_prime());
+	/// ```
+	pub fn is_prime(&self) -> bool {
+		if self.re == 0 || self.im == 0 {
+			let p = (self.re + self.im).unsigned_abs();
+			return p % 4 == 3 && numt::is_prime(p);
+		}
+		let Ok(norm) = u64::try_from(self.norm()) else {
+			panic!("norm of {self} is too large to test for primality");
+		};
+		numt::is_prime(norm)
+	}
+}
+
+impl From<i64> for GaussianInt {
+	fn from(n: i64) -> Self {
+		GaussianInt::new(n, 0)
+	}
+}
+
+impl Add for GaussianInt {
+	type Output = GaussianInt;
+
+	fn add(self, other: GaussianInt) -> GaussianInt {
+		GaussianInt::new(self.re + other.re, self.im + other.im)
+	}
+}
+
+impl Sub for GaussianInt {
+	type Output = GaussianInt;
+
+	fn sub(self, other: GaussianInt) -> GaussianInt {
+		GaussianInt::new(self.re - other.re, self.im - other.im)
+	}
+}
+
+impl Mul for GaussianInt {
+	type Output = GaussianInt;
+
+	fn mul(self, other: GaussianInt) -> GaussianInt {
+		GaussianInt::new(
+			self.re * other.re - self.im * other.im,
+			self.re * other.im + self.im * other.re,
+		)
+	}
+}
+
+impl Neg for GaussianInt {
+	type Output = GaussianInt;
+
+	fn neg(self) -> GaussianInt {
+		GaussianInt::new(-self.re, -self.im)
+	}
+}
+
+impl fmt::Display for GaussianInt {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}{:+}i", self.re, self.im)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn gaussian_arithmetic() {
+		let z = GaussianInt::new(3, -2);
+		let w = GaussianInt::new(-1, 4);
+		assert_eq!(GaussianInt::new(2, 2), z + w);
+		assert_eq!(GaussianInt::new(4, -6), z - w);
+		assert_eq!(GaussianInt::new(5, 14), z * w);
+		assert_eq!(z.norm() * w.norm(), (z * w).norm());
+		assert!(GaussianInt::new(0, -1).is_unit());
+	}
+
+	#[test]
+	fn gaussian_div_rem() {
+		for re in -6..=6 {
+			for im in -6..=6 {
+				let a = GaussianInt::new(re * 7 + 3, im * 5 - 1);
+				let b = GaussianInt::new(re, im);
+				if b == GaussianInt::from(0) {
+					continue;
+				}
+				let (q, r) = a.div_rem(&b);
+				assert_eq!(a, q * b + r);
+				assert!(2 * r.norm() <= b.norm());
+			}
+		}
+	}
+
+	#[test]
+	fn gaussian_primes() {
+		// Rational primes that stay prime are those congruent to 3 modulo 4
+		let inert: Vec<i64> = (2..30).filter(|&p| GaussianInt::from(p).is_prime()).collect();
+		assert_eq!(vec![3, 7, 11, 19, 23], inert);
+		assert!(GaussianInt::new(2, 3).is_prime());
+		assert!(!GaussianInt::new(3, 1).is_prime());
+		assert!(!GaussianInt::new(1, 0).is_prime());
+
+		let g = GaussianInt::new(11, 3).gcd(&GaussianInt::new(1, 8));
+		assert_eq!(5, g.norm());
+	}
+
+	#[test]
+	fn gaussian_extreme_values() {
+		let min = GaussianInt::new(i64::MIN, i64::MIN);
+		let max = GaussianInt::new(i64::MAX, i64::MAX);
+		assert_eq!(1 << 127, min.norm());
+		assert_eq!(2 * (i64::MAX as u128).pow(2), max.norm());
+
+		assert_eq!((GaussianInt::from(1), GaussianInt::from(0)), min.div_rem(&min));
+		let (q, r) = max.div_rem(&GaussianInt::new(1, 1));
+		assert_eq!((GaussianInt::from(i64::MAX), GaussianInt::from(0)), (q, r));
+		// Check a = q * b + r in i128, since q * b may not fit in an i64
+		let wide = |z: GaussianInt| (z.re as i128, z.im as i128);
+		let a = GaussianInt::new(i64::MAX, i64::MIN);
+		for b in [GaussianInt::new(3, 4), max, min, GaussianInt::new(-7, i64::MAX)] {
+			let (q, r) = a.div_rem(&b);
+			let ((qa, qb), (c, d), (ra, rb)) = (wide(q), wide(b), wide(r));
+			assert_eq!(wide(a), (qa * c - qb * d + ra, qa * d + qb * c + rb));
+			assert!(2 * r.norm() <= b.norm());
+		}
+	}
+
+	#[test]
+	#[should_panic(expected = "does not fit")]
+	fn gaussian_quotient_overflow() {
+		// The quotient would be i64::MIN - i64::MIN i, whose imaginary part
+		// is out of range
+		GaussianInt::new(i64::MIN, i64::MIN).div_rem(&GaussianInt::new(0, 1));
+	}
+}
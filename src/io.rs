@@ -1,25 +1,2736 @@
-use std::error::Error;
+use std::fmt;
 use std::fs::File;
 use std::io::BufReader;
 // BufReader implements the BufRead trait for its lines() method.
 // To use lies(), BufRead must be in scope.
-use std::io::BufRead;
+use std::io::{BufRead, BufWriter, Lines, Read, Write};
+use std::marker::PhantomData;
+use std::ops::{Index, IndexMut};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
-pub fn load_number_grid(filepath: &str)
-	-> Result<Vec<Vec<u64>>, Box<dyn Error>> {
+use crate::BigInt;
+use crate::matrix::Matrix;
 
-	let file = File::open(filepath)?;
-	let reader = BufReader::new(file);
+/// The ways in which loading data from a file can fail.
+#[derive(Debug)]
+pub enum Error {
+	/// The file could not be opened or read.
+	Io(std::io::Error),
+	/// A token could not be parsed. Lines and columns are counted from 1,
+	/// and the column is that of the first character of the token.
+	Parse {
+		line: usize,
+		column: usize,
+		token: String,
+		message: String,
+	},
+	/// A record has no field at the given index, counting from 0.
+	MissingField {
+		line: usize,
+		field: usize,
+	},
+	/// A row does not have the number of values its position requires.
+	RowLength {
+		line: usize,
+		expected: usize,
+		found: usize,
+	},
+	/// A file is not in the expected format. The message describes what is
+	/// wrong.
+	InvalidFormat(&'static str),
+	/// A JSON file is malformed or does not have the expected structure.
+	#[cfg(feature = "json")]
+	Json(serde_json::Error),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Error::Io(err) => write!(f, "{err}"),
+			Error::Parse { line, column, token, message } => {
+				write!(f, "line {line}, column {column}: cannot parse {token:?}: {message}")
+			},
+			Error::MissingField { line, field } => {
+				write!(f, "line {line}: missing field {field}")
+			},
+			Error::RowLength { line, expected, found } => {
+				write!(f, "line {line}: expected {expected} values, found {found}")
+			},
+			Error::InvalidFormat(message) => write!(f, "invalid format: {message}"),
+			#[cfg(feature = "json")]
+			Error::Json(err) => write!(f, "{err}"),
+		}
+	}
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Error::Io(err) => Some(err),
+			#[cfg(feature = "json")]
+			Error::Json(err) => Some(err),
+			_ => None,
+		}
+	}
+}
+
+impl From<std::io::Error> for Error {
+	fn from(err: std::io::Error) -> Self {
+		Error::Io(err)
+	}
+}
+
+/// How the values on each line of a grid are separated.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Delimiter {
+	/// Any run of whitespace.
+	#[default]
+	Whitespace,
+	Comma,
+	Tab,
+	/// Any other separator, which may be longer than one character.
+	Custom(String),
+}
+
+impl Delimiter {
+	/// The text written between values, and expected between them unless
+	/// splitting on whitespace.
+	fn separator(&self) -> &str {
+		match self {
+			Delimiter::Whitespace => " ",
+			Delimiter::Comma => ",",
+			Delimiter::Tab => "\t",
+			Delimiter::Custom(separator) => separator,
+		}
+	}
+}
+
+/// Options that control how [`load_grid_with`] and
+/// [`load_grid_from_reader_with`] split lines into values.
+///
+/// With any delimiter other than [`Delimiter::Whitespace`], the whitespace
+/// around each value is ignored. Blank lines always become empty rows.
+///
+/// By default rows may have different lengths. With
+/// [`GridParseOptions::require_rectangular`], a row whose length differs from
+/// that of the first row is reported as an [`Error::RowLength`].
+///
+/// Parsing stops at the first malformed token unless a more lenient
+/// [`Leniency`] is chosen, in which case the problems are collected as
+/// warnings by [`load_grid_from_reader_with_warnings`].
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::io::{self, Delimiter, GridParseOptions};
+/// let options = GridParseOptions::new().delimiter(Delimiter::Comma);
+/// let grid: Vec<Vec<u64>> = io::load_grid_from_reader_with("1, 2,3\n4,5,6".as_bytes(), &options)
+///     .unwrap();
+/// assert_eq!(vec![vec![1, 2, 3], vec![4, 5, 6]], grid);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct GridParseOptions {
+	delimiter: Delimiter,
+	rectangular: bool,
+	leniency: Leniency,
+	column_major: bool,
+}
+
+impl GridParseOptions {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn delimiter(mut self, delimiter: Delimiter) -> Self {
+		self.delimiter = delimiter;
+		self
+	}
+
+	/// Whether every row must have as many values as the first.
+	pub fn require_rectangular(mut self, rectangular: bool) -> Self {
+		self.rectangular = rectangular;
+		self
+	}
+
+	/// How to handle malformed tokens and lines.
+	pub fn leniency(mut self, leniency: Leniency) -> Self {
+		self.leniency = leniency;
+		self
+	}
+
+	/// Whether to return the grid column by column, so that each inner `Vec`
+	/// holds one column of the input. Implies
+	/// [`GridParseOptions::require_rectangular`].
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::io::{self, GridParseOptions};
+	/// let options = GridParseOptions::new().column_major(true);
+	/// let text = "1 2 3\n4 5 6";
+	/// let columns: Vec<Vec<u64>> = io::load_grid_from_reader_with(text.as_bytes(), &options)
+	///     .unwrap();
+	/// assert_eq!(vec![vec![1, 4], vec![2, 5], vec![3, 6]], columns);
+	/// ```
+	pub fn column_major(mut self, column_major: bool) -> Self {
+		self.column_major = column_major;
+		self
+	}
+
+	/// Splits `line` into its tokens, which are slices of `line`.
+	fn split<'a>(&self, line: &'a str) -> Vec<&'a str> {
+		if self.delimiter == Delimiter::Whitespace {
+			return line.split_whitespace().collect();
+		}
+		if line.trim().is_empty() {
+			return vec![];
+		}
+		line.split(self.delimiter.separator()).map(str::trim).collect()
+	}
+}
+
+/// What to do when a grid being parsed contains a malformed token or line.
+///
+/// In the lenient modes, blank lines are skipped, and with
+/// [`GridParseOptions::require_rectangular`] a row of the wrong length is
+/// skipped instead of aborting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Leniency {
+	/// Abort on the first error.
+	#[default]
+	Strict,
+	/// Drop tokens that fail to parse, keeping the rest of their line. A line
+	/// left without any values is dropped.
+	SkipTokens,
+	/// Drop every line that contains a token that fails to parse.
+	SkipLines,
+}
+
+pub fn load_number_grid(filepath: impl AsRef<Path>) -> Result<Vec<Vec<u64>>, Error> {
+	load_grid(filepath)
+}
+
+/// Load a grid of whitespace-separated `u64` values from any buffered reader,
+/// such as an in-memory string or a network stream.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::io;
+/// let grid = io::load_number_grid_from_reader("8 2\n49 49".as_bytes()).unwrap();
+/// assert_eq!(vec![vec![8, 2], vec![49, 49]], grid);
+/// ```
+pub fn load_number_grid_from_reader(reader: impl BufRead)
+	-> Result<Vec<Vec<u64>>, Error> {
+
+	load_grid_from_reader(reader)
+}
+
+/// Where input comes from. Every loader in this module has a variant ending
+/// in `_from_reader`, which accepts [`Source::into_reader`] for any source.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::io::{self, Source};
+/// let source = Source::Text("1 2\n3 4");
+/// let grid = io::load_number_grid_from_reader(source.into_reader().unwrap()).unwrap();
+/// assert_eq!(vec![vec![1, 2], vec![3, 4]], grid);
+/// ```
+pub enum Source<'a> {
+	/// A file on disk.
+	Path(PathBuf),
+	/// The standard input of the process.
+	Stdin,
+	/// Any buffered reader, such as a network stream.
+	Reader(Box<dyn BufRead + 'a>),
+	/// Text held in memory, such as data embedded with `include_str!`.
+	Text(&'a str),
+}
+
+impl<'a> Source<'a> {
+	/// Open the source for reading. Only [`Source::Path`] can fail.
+	pub fn into_reader(self) -> Result<Box<dyn BufRead + 'a>, Error> {
+		Ok(match self {
+			Source::Path(path) => open_file(path)?,
+			Source::Stdin => Box::new(std::io::stdin().lock()),
+			Source::Reader(reader) => reader,
+			Source::Text(text) => Box::new(text.as_bytes()),
+		})
+	}
+}
+
+/// Load a grid of whitespace-separated `u64` values piped into the standard
+/// input of the process.
+///
+/// # Examples
+///
+/// ```no_run
+/// use segtrs::io;
+/// // Run as `my-binary < grid.txt`
+/// let grid = io::load_number_grid_from_stdin().unwrap();
+/// ```
+pub fn load_number_grid_from_stdin() -> Result<Vec<Vec<u64>>, Error> {
+	load_number_grid_from_reader(Source::Stdin.into_reader()?)
+}
+
+/// Load a grid of whitespace-separated values of any type that can be parsed
+/// from a string, such as `i64`, `f64` or [`crate::BigInt`]. Each line of the
+/// file becomes a row of the grid.
+///
+/// # Examples
+///
+/// ```no_run
+/// use segtrs::io;
+/// let grid: Vec<Vec<f64>> = io::load_grid("data/grid.txt").unwrap();
+/// ```
+pub fn load_grid<T>(filepath: impl AsRef<Path>) -> Result<Vec<Vec<T>>, Error>
+	where T: FromStr, T::Err: fmt::Display {
+
+	load_grid_with(filepath, &GridParseOptions::new())
+}
+
+/// An iterator that parses the rows of a grid one line at a time, so that
+/// only the current row is held in memory. Produces an error at most once,
+/// after which it ends.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::io;
+/// let row_sums: Vec<u64> = io::grid_rows_from_reader::<u64, _>("1 2\n3 4\n".as_bytes())
+///     .map(|row| row.map(|row| row.iter().sum()))
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+/// assert_eq!(vec![3, 7], row_sums);
+/// ```
+pub struct GridRows<R, T> {
+	lines: Lines<R>,
+	// The index of the next line to be read
+	index: usize,
+	failed: bool,
+	values: PhantomData<T>,
+}
+
+impl<R: BufRead, T> Iterator for GridRows<R, T>
+	where T: FromStr, T::Err: fmt::Display {
+
+	type Item = Result<Vec<T>, Error>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.failed {
+			return None;
+		}
+		let line = match self.lines.next()? {
+			Ok(line) => line,
+			Err(err) => {
+				self.failed = true;
+				return Some(Err(err.into()));
+			},
+		};
+		let index = self.index;
+		self.index += 1;
+
+		let row = parse_row(&line, index);
+		self.failed = row.is_err();
+		Some(row)
+	}
+}
+
+pub fn number_grid_rows(filepath: impl AsRef<Path>)
+	-> Result<GridRows<Box<dyn BufRead>, u64>, Error> {
+
+	grid_rows(filepath)
+}
+
+/// Open a file of whitespace-separated values for reading one row at a
+/// time. Unlike [`load_grid`], the rows are parsed lazily, so files larger
+/// than the available memory can be processed.
+///
+/// # Examples
+///
+/// ```no_run
+/// use segtrs::io;
+/// let mut largest = 0;
+/// for row in io::number_grid_rows("data/huge.txt").unwrap() {
+///     largest = row.unwrap().into_iter().fold(largest, u64::max);
+/// }
+/// ```
+pub fn grid_rows<T>(filepath: impl AsRef<Path>) -> Result<GridRows<Box<dyn BufRead>, T>, Error>
+	where T: FromStr, T::Err: fmt::Display {
+
+	Ok(grid_rows_from_reader(open_file(filepath)?))
+}
+
+/// Like [`grid_rows`], but reads from any buffered reader instead of a file.
+pub fn grid_rows_from_reader<T, R: BufRead>(reader: R) -> GridRows<R, T>
+	where T: FromStr, T::Err: fmt::Display {
+
+	GridRows {
+		lines: reader.lines(),
+		index: 0,
+		failed: false,
+		values: PhantomData,
+	}
+}
+
+pub fn load_number_list(filepath: impl AsRef<Path>) -> Result<Vec<u64>, Error> {
+	load_list(filepath)
+}
+
+/// Load every whitespace-separated value in a file into a single list,
+/// regardless of how the values are split across lines.
+///
+/// # Examples
+///
+/// ```no_run
+/// use segtrs::io;
+/// let values: Vec<i64> = io::load_list("data/values.txt").unwrap();
+/// ```
+pub fn load_list<T>(filepath: impl AsRef<Path>) -> Result<Vec<T>, Error>
+	where T: FromStr, T::Err: fmt::Display {
+
+	load_list_from_reader(open_file(filepath)?)
+}
+
+/// Like [`load_list`], but reads from any buffered reader instead of a file.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::io;
+/// let values: Vec<u64> = io::load_list_from_reader("1 2\n\n3\n 4 5".as_bytes()).unwrap();
+/// assert_eq!(vec![1, 2, 3, 4, 5], values);
+/// ```
+pub fn load_list_from_reader<T>(reader: impl BufRead) -> Result<Vec<T>, Error>
+	where T: FromStr, T::Err: fmt::Display {
+
+	let mut list = vec![];
+	for (index, line) in reader.lines().enumerate() {
+		let line = line?;
+		for s in line.split_whitespace() {
+			list.push(parse_token(&line, index, s)?);
+		}
+	}
+	Ok(list)
+}
+
+/// Load a file with one decimal number per line, of any length, as
+/// [`BigInt`] values. Blank lines are skipped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use segtrs::BigInt;
+/// use segtrs::io;
+/// // Project Euler 13: the first ten digits of the sum of 100 numbers
+/// let numbers = io::load_bigints("data/numbers.txt").unwrap();
+/// let sum = numbers.iter().fold(BigInt::from_int(0), |acc, n| acc.add(n));
+/// ```
+pub fn load_bigints(filepath: impl AsRef<Path>) -> Result<Vec<BigInt>, Error> {
+	load_bigints_from_reader(open_file(filepath)?)
+}
+
+/// Like [`load_bigints`], but reads from any buffered reader instead of a
+/// file.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::BigInt;
+/// use segtrs::io;
+/// let text = "37107287533902102798797998220837590246510135740250\n";
+/// let numbers = io::load_bigints_from_reader(text.as_bytes()).unwrap();
+/// assert_eq!(50, numbers[0].digits().len());
+/// ```
+pub fn load_bigints_from_reader(reader: impl BufRead) -> Result<Vec<BigInt>, Error> {
+	let mut numbers = vec![];
+	for (index, line) in reader.lines().enumerate() {
+		let line = line?;
+		let token = line.trim();
+		if !token.is_empty() {
+			numbers.push(parse_token(&line, index, token)?);
+		}
+	}
+	Ok(numbers)
+}
+
+/// Load a file holding a single string of decimal digits, which may be
+/// wrapped across lines and broken up by whitespace, as a `Vec` with one
+/// digit per element, most-significant first.
+///
+/// # Examples
+///
+/// ```no_run
+/// use segtrs::io;
+/// // Project Euler 8: the greatest product of 13 adjacent digits
+/// let digits = io::load_digit_string("data/number.txt").unwrap();
+/// let max = digits.windows(13)
+///     .map(|w| w.iter().map(|&d| d as u64).product::<u64>())
+///     .max();
+/// ```
+pub fn load_digit_string(filepath: impl AsRef<Path>) -> Result<Vec<u8>, Error> {
+	load_digit_string_from_reader(open_file(filepath)?)
+}
+
+/// Like [`load_digit_string`], but reads from any buffered reader instead
+/// of a file.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::io;
+/// let digits = io::load_digit_string_from_reader("731 67\n1765\n".as_bytes()).unwrap();
+/// assert_eq!(vec![7, 3, 1, 6, 7, 1, 7, 6, 5], digits);
+/// ```
+pub fn load_digit_string_from_reader(reader: impl BufRead) -> Result<Vec<u8>, Error> {
+	let mut digits = vec![];
+	for (index, line) in reader.lines().enumerate() {
+		let line = line?;
+		for (column, c) in line.chars().enumerate() {
+			match c.to_digit(10) {
+				Some(d) => digits.push(d as u8),
+				None if c.is_whitespace() => {},
+				None => return Err(Error::Parse {
+					line: index + 1,
+					column: column + 1,
+					token: c.to_string(),
+					message: "not a decimal digit".to_string(),
+				}),
+			}
+		}
+	}
+	Ok(digits)
+}
+
+/// Like [`load_digit_string`], but produces the number the digits represent.
+/// Fails with [`Error::InvalidFormat`] if the file has no digits.
+///
+/// # Examples
+///
+/// ```no_run
+/// use segtrs::io;
+/// let n = io::load_digit_bigint("data/number.txt").unwrap();
+/// println!("{} digits", n.digits().len());
+/// ```
+pub fn load_digit_bigint(filepath: impl AsRef<Path>) -> Result<BigInt, Error> {
+	load_digit_bigint_from_reader(open_file(filepath)?)
+}
 
-	// Read squared grid of itnegers
+/// Like [`load_digit_bigint`], but reads from any buffered reader instead
+/// of a file.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::BigInt;
+/// use segtrs::io;
+/// let n = io::load_digit_bigint_from_reader("12\n34\n".as_bytes()).unwrap();
+/// assert_eq!(BigInt::from_int(1234), n);
+/// ```
+pub fn load_digit_bigint_from_reader(reader: impl BufRead) -> Result<BigInt, Error> {
+	let digits = load_digit_string_from_reader(reader)?;
+	if digits.is_empty() {
+		return Err(Error::InvalidFormat("no digits found"));
+	}
+	Ok(BigInt::new(digits.into_iter().rev()))
+}
+
+/// Like [`load_grid`], but fails with [`Error::RowLength`] at the first row
+/// whose length differs from that of the first row, so that every row of
+/// the grid can be indexed the same way.
+///
+/// # Examples
+///
+/// ```no_run
+/// use segtrs::io;
+/// let grid: Vec<Vec<u64>> = io::load_rectangular_grid("data/grid.txt").unwrap();
+/// let width = grid.first().map_or(0, |row| row.len());
+/// assert!(grid.iter().all(|row| row.len() == width));
+/// ```
+pub fn load_rectangular_grid<T>(filepath: impl AsRef<Path>) -> Result<Vec<Vec<T>>, Error>
+	where T: FromStr, T::Err: fmt::Display {
+
+	load_grid_with(filepath, &GridParseOptions::new().require_rectangular(true))
+}
+
+/// Load a grid whose values are packed into fields of `field_width`
+/// characters each, without separators, such as a long number given one
+/// digit per cell. The last field of a line may be shorter, and spaces
+/// padding a field are ignored. Panics if `field_width` is 0.
+///
+/// # Examples
+///
+/// ```no_run
+/// use segtrs::io;
+/// let digits: Vec<Vec<u8>> = io::load_fixed_width_grid("data/digits.txt", 1).unwrap();
+/// ```
+pub fn load_fixed_width_grid<T>(filepath: impl AsRef<Path>, field_width: usize)
+	-> Result<Vec<Vec<T>>, Error>
+	where T: FromStr, T::Err: fmt::Display {
+
+	load_fixed_width_grid_from_reader(open_file(filepath)?, field_width)
+}
+
+/// Like [`load_fixed_width_grid`], but reads from any buffered reader instead
+/// of a file.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::io;
+/// let grid: Vec<Vec<u32>> = io::load_fixed_width_grid_from_reader("0812 7\n4903".as_bytes(), 2)
+///     .unwrap();
+/// assert_eq!(vec![vec![8, 12, 7], vec![49, 3]], grid);
+/// ```
+pub fn load_fixed_width_grid_from_reader<T>(reader: impl BufRead, field_width: usize)
+	-> Result<Vec<Vec<T>>, Error>
+	where T: FromStr, T::Err: fmt::Display {
+
+	if field_width == 0 {
+		panic!("field width must be positive");
+	}
 	let mut grid = vec![];
-	for line in reader.lines() {
+	for (index, line) in reader.lines().enumerate() {
+		let line = line?;
+		let content = line.trim_end();
+		let mut row = vec![];
+		let mut starts = content.char_indices().map(|(i, _)| i).step_by(field_width).peekable();
+		while let Some(start) = starts.next() {
+			let end = starts.peek().copied().unwrap_or(content.len());
+			row.push(parse_token(&line, index, content[start..end].trim())?);
+		}
+		grid.push(row);
+	}
+	Ok(grid)
+}
+
+/// Load a grid of whitespace-separated `u64` values as a [`Matrix`]. Fails
+/// with [`Error::RowLength`] at the first row whose length differs from that
+/// of the first row.
+///
+/// # Examples
+///
+/// ```no_run
+/// use segtrs::io;
+/// let m = io::load_matrix("data/matrix.txt").unwrap();
+/// println!("{} x {}", m.rows(), m.cols());
+/// ```
+pub fn load_matrix(filepath: impl AsRef<Path>) -> Result<Matrix<u64>, Error> {
+	Ok(Matrix::new(load_rectangular_grid(filepath)?))
+}
+
+/// Like [`load_matrix`], but reads from any buffered reader instead of a
+/// file.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::io;
+/// let m = io::load_matrix_from_reader("1 2\n3 4\n".as_bytes()).unwrap();
+/// assert_eq!(4, m[(1, 1)]);
+/// ```
+pub fn load_matrix_from_reader(reader: impl BufRead) -> Result<Matrix<u64>, Error> {
+	let options = GridParseOptions::new().require_rectangular(true);
+	Ok(Matrix::new(load_grid_from_reader_with(reader, &options)?))
+}
+
+/// Like [`load_grid`], but splits lines as described by `options`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use segtrs::io::{self, Delimiter, GridParseOptions};
+/// let options = GridParseOptions::new().delimiter(Delimiter::Tab);
+/// let grid: Vec<Vec<u64>> = io::load_grid_with("data/grid.tsv", &options).unwrap();
+/// ```
+pub fn load_grid_with<T>(filepath: impl AsRef<Path>, options: &GridParseOptions)
+	-> Result<Vec<Vec<T>>, Error>
+	where T: FromStr, T::Err: fmt::Display {
+
+	load_grid_from_reader_with(open_file(filepath)?, options)
+}
+
+/// Like [`load_grid`], but reads from any buffered reader instead of a file.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::io;
+/// let grid: Vec<Vec<i64>> = io::load_grid_from_reader("-1 2\n3 -4".as_bytes()).unwrap();
+/// assert_eq!(vec![vec![-1, 2], vec![3, -4]], grid);
+/// ```
+pub fn load_grid_from_reader<T>(reader: impl BufRead) -> Result<Vec<Vec<T>>, Error>
+	where T: FromStr, T::Err: fmt::Display {
+
+	load_grid_from_reader_with(reader, &GridParseOptions::new())
+}
+
+/// Like [`load_grid_from_reader`], but splits lines as described by
+/// `options`.
+///
+/// Any warnings collected under a lenient [`Leniency`] are discarded; use
+/// [`load_grid_from_reader_with_warnings`] to keep them.
+pub fn load_grid_from_reader_with<T>(reader: impl BufRead, options: &GridParseOptions)
+	-> Result<Vec<Vec<T>>, Error>
+	where T: FromStr, T::Err: fmt::Display {
+
+	Ok(load_grid_from_reader_with_warnings(reader, options)?.0)
+}
+
+/// Like [`load_grid_with`], but also returns the errors that were skipped
+/// under a lenient [`Leniency`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use segtrs::io::{self, GridParseOptions, Leniency};
+/// let options = GridParseOptions::new().leniency(Leniency::SkipLines);
+/// let (grid, warnings) = io::load_grid_with_warnings::<u64>("data/grid.txt", &options)
+///     .unwrap();
+/// for warning in warnings {
+///     eprintln!("skipped: {}", warning);
+/// }
+/// ```
+pub fn load_grid_with_warnings<T>(filepath: impl AsRef<Path>, options: &GridParseOptions)
+	-> Result<(Vec<Vec<T>>, Vec<Error>), Error>
+	where T: FromStr, T::Err: fmt::Display {
+
+	load_grid_from_reader_with_warnings(open_file(filepath)?, options)
+}
+
+/// Like [`load_grid_from_reader_with`], but also returns the errors that were
+/// skipped under a lenient [`Leniency`]. Read errors always abort.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::io::{self, GridParseOptions, Leniency};
+/// let options = GridParseOptions::new().leniency(Leniency::SkipLines);
+/// let text = "a b\n1 2\n\n3 x\n4 5\n";
+/// let (grid, warnings) = io::load_grid_from_reader_with_warnings::<u64>(text.as_bytes(), &options)
+///     .unwrap();
+/// assert_eq!(vec![vec![1, 2], vec![4, 5]], grid);
+/// assert_eq!(2, warnings.len());
+/// ```
+pub fn load_grid_from_reader_with_warnings<T>(reader: impl BufRead, options: &GridParseOptions)
+	-> Result<(Vec<Vec<T>>, Vec<Error>), Error>
+	where T: FromStr, T::Err: fmt::Display {
+
+	let lenient = options.leniency != Leniency::Strict;
+	let mut grid: Vec<Vec<T>> = vec![];
+	let mut warnings = vec![];
+	'lines: for (index, line) in reader.lines().enumerate() {
+		let line = line?;
 		let mut row = vec![];
-		for s in line?.split_whitespace() {
-			let n: u64 = s.parse()?;
-			row.push(n);
+		for s in options.split(&line) {
+			match parse_token(&line, index, s) {
+				Ok(value) => row.push(value),
+				Err(e) if !lenient => return Err(e),
+				Err(e) => {
+					warnings.push(e);
+					if options.leniency == Leniency::SkipLines {
+						continue 'lines;
+					}
+				}
+			}
+		}
+		// Blank lines, and lines whose every token was skipped
+		if lenient && row.is_empty() {
+			continue;
+		}
+		if (options.rectangular || options.column_major) && let Some(first) = grid.first()
+			&& row.len() != first.len() {
+			let e = Error::RowLength {
+				line: index + 1,
+				expected: first.len(),
+				found: row.len(),
+			};
+			if !lenient {
+				return Err(e);
+			}
+			warnings.push(e);
+			continue;
+		}
+		grid.push(row);
+	}
+	if options.column_major {
+		grid = transpose_rows(grid);
+	}
+	Ok((grid, warnings))
+}
+
+/// Like [`load_grid_with`], but calls `progress` with the number of bytes
+/// read so far as the file is parsed, for example to drive a progress bar.
+/// For compressed files, the count is of decompressed bytes.
+///
+/// # Examples
+///
+/// ```no_run
+/// use segtrs::io::{self, GridParseOptions};
+/// let total = std::fs::metadata("data/grid.txt").unwrap().len();
+/// let grid: Vec<Vec<u64>> = io::load_grid_with_progress("data/grid.txt", &GridParseOptions::new(),
+///     |bytes| eprint!("\r{:3}%", 100 * bytes / total.max(1))).unwrap();
+/// ```
+pub fn load_grid_with_progress<T>(filepath: impl AsRef<Path>, options: &GridParseOptions,
+	progress: impl FnMut(u64)) -> Result<Vec<Vec<T>>, Error>
+	where T: FromStr, T::Err: fmt::Display {
+
+	load_grid_from_reader_with(ProgressReader::new(open_file(filepath)?, progress), options)
+}
+
+/// A reader that reports the total number of bytes read through it to a
+/// callback each time it makes progress. Wrap a reader in it to follow the
+/// progress of any loader in this module that accepts a reader.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::io::{self, ProgressReader};
+/// let mut reported = vec![];
+/// let reader = ProgressReader::new("1 2\n3 4\n".as_bytes(), |bytes| reported.push(bytes));
+/// let grid: Vec<Vec<u64>> = io::load_grid_from_reader(reader).unwrap();
+/// assert_eq!(2, grid.len());
+/// assert_eq!(Some(&8), reported.last());
+/// ```
+pub struct ProgressReader<R, F> {
+	inner: R,
+	bytes: u64,
+	callback: F,
+}
+
+impl<R, F: FnMut(u64)> ProgressReader<R, F> {
+	pub fn new(inner: R, callback: F) -> Self {
+		Self {
+			inner,
+			bytes: 0,
+			callback,
+		}
+	}
+
+	/// The number of bytes read so far.
+	pub fn bytes_read(&self) -> u64 {
+		self.bytes
+	}
+
+	pub fn into_inner(self) -> R {
+		self.inner
+	}
+
+	fn advance(&mut self, amount: usize) {
+		if amount > 0 {
+			self.bytes += amount as u64;
+			(self.callback)(self.bytes);
+		}
+	}
+}
+
+impl<R: Read, F: FnMut(u64)> Read for ProgressReader<R, F> {
+	fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+		let n = self.inner.read(buf)?;
+		self.advance(n);
+		Ok(n)
+	}
+}
+
+impl<R: BufRead, F: FnMut(u64)> BufRead for ProgressReader<R, F> {
+	fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+		self.inner.fill_buf()
+	}
+
+	fn consume(&mut self, amount: usize) {
+		self.inner.consume(amount);
+		self.advance(amount);
+	}
+}
+
+/// Turns rectangular rows into columns, moving every value.
+fn transpose_rows<T>(rows: Vec<Vec<T>>) -> Vec<Vec<T>> {
+	let cols = rows.first().map_or(0, Vec::len);
+	let mut rows: Vec<_> = rows.into_iter().map(Vec::into_iter).collect();
+	(0..cols)
+		.map(|_| rows.iter_mut().map(|row| row.next().unwrap()).collect())
+		.collect()
+}
+
+/// Load a grid stored as a JSON array of arrays, such as `[[1, 2], [3, 4]]`.
+/// The values may be of any type that can be deserialized.
+///
+/// Only available with the `json` feature.
+///
+/// # Examples
+///
+/// ```no_run
+/// use segtrs::io;
+/// let grid: Vec<Vec<f64>> = io::load_grid_json("data/grid.json").unwrap();
+/// ```
+#[cfg(feature = "json")]
+pub fn load_grid_json<T>(filepath: impl AsRef<Path>) -> Result<Vec<Vec<T>>, Error>
+	where T: serde::de::DeserializeOwned {
+
+	load_grid_json_from_reader(open_file(filepath)?)
+}
+
+/// Like [`load_grid_json`], but reads from any reader instead of a file.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::io;
+/// let grid: Vec<Vec<i64>> = io::load_grid_json_from_reader("[[1, -2], [3]]".as_bytes()).unwrap();
+/// assert_eq!(vec![vec![1, -2], vec![3]], grid);
+/// ```
+#[cfg(feature = "json")]
+pub fn load_grid_json_from_reader<T>(reader: impl Read) -> Result<Vec<Vec<T>>, Error>
+	where T: serde::de::DeserializeOwned {
+
+	serde_json::from_reader(reader).map_err(Error::Json)
+}
+
+// Identifies files written by save_grid_binary
+const BINARY_GRID_MAGIC: &[u8; 4] = b"SGRD";
+
+/// Save a rectangular grid in a compact binary format that
+/// [`load_grid_binary`] reads back much faster than text. The file starts
+/// with a header holding the number of rows and columns and the width in
+/// bytes of every value, which is the smallest of 1, 2, 4 or 8 that fits the
+/// largest value. The values follow in row order, in little-endian byte
+/// order. Fails with [`Error::RowLength`] if the grid is not rectangular,
+/// and with [`Error::InvalidFormat`] if it has rows but no columns.
+///
+/// # Examples
+///
+/// ```no_run
+/// use segtrs::io;
+/// let grid = vec![vec![1, 2], vec![300, 4]];
+/// io::save_grid_binary("data/grid.bin", &grid).unwrap();
+/// assert_eq!(grid, io::load_grid_binary("data/grid.bin").unwrap());
+/// ```
+pub fn save_grid_binary(filepath: impl AsRef<Path>, grid: &[Vec<u64>]) -> Result<(), Error> {
+	let mut writer = BufWriter::new(File::create(filepath)?);
+	save_grid_binary_to_writer(&mut writer, grid)?;
+	writer.flush()?;
+	Ok(())
+}
+
+/// Like [`save_grid_binary`], but writes to any writer instead of a file.
+pub fn save_grid_binary_to_writer(mut writer: impl Write, grid: &[Vec<u64>])
+	-> Result<(), Error> {
+
+	let cols = grid.first().map_or(0, Vec::len);
+	if let Some(i) = grid.iter().position(|row| row.len() != cols) {
+		return Err(Error::RowLength { line: i + 1, expected: cols, found: grid[i].len() });
+	}
+	if cols == 0 && !grid.is_empty() {
+		return Err(Error::InvalidFormat("grid has rows but no columns"));
+	}
+
+	let largest = grid.iter().flatten().copied().max().unwrap_or(0);
+	let width: u8 = match largest {
+		0..=0xff => 1,
+		0x100..=0xffff => 2,
+		0x1_0000..=0xffff_ffff => 4,
+		_ => 8,
+	};
+
+	writer.write_all(BINARY_GRID_MAGIC)?;
+	writer.write_all(&(grid.len() as u64).to_le_bytes())?;
+	writer.write_all(&(cols as u64).to_le_bytes())?;
+	writer.write_all(&[width])?;
+	for value in grid.iter().flatten() {
+		writer.write_all(&value.to_le_bytes()[..width as usize])?;
+	}
+	Ok(())
+}
+
+/// Load a grid saved by [`save_grid_binary`].
+pub fn load_grid_binary(filepath: impl AsRef<Path>) -> Result<Vec<Vec<u64>>, Error> {
+	load_grid_binary_from_reader(open_file(filepath)?)
+}
+
+/// Like [`load_grid_binary`], but reads from any reader instead of a file.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::io;
+/// let grid = vec![vec![1, 70_000], vec![3, 4]];
+/// let mut bytes = vec![];
+/// io::save_grid_binary_to_writer(&mut bytes, &grid).unwrap();
+/// assert_eq!(4 + 8 + 8 + 1 + 4 * 4, bytes.len());
+/// assert_eq!(grid, io::load_grid_binary_from_reader(&bytes[..]).unwrap());
+/// ```
+pub fn load_grid_binary_from_reader(mut reader: impl Read) -> Result<Vec<Vec<u64>>, Error> {
+	let mut magic = [0; 4];
+	reader.read_exact(&mut magic)?;
+	if &magic != BINARY_GRID_MAGIC {
+		return Err(Error::InvalidFormat("not a binary grid"));
+	}
+
+	let mut word = [0; 8];
+	reader.read_exact(&mut word)?;
+	let rows = u64::from_le_bytes(word);
+	reader.read_exact(&mut word)?;
+	let cols = u64::from_le_bytes(word);
+	let mut width = [0; 1];
+	reader.read_exact(&mut width)?;
+	let width = width[0] as usize;
+	if ![1, 2, 4, 8].contains(&width) {
+		return Err(Error::InvalidFormat("value width must be 1, 2, 4 or 8 bytes"));
+	}
+	let (Ok(rows), Ok(cols)) = (usize::try_from(rows), usize::try_from(cols)) else {
+		return Err(Error::InvalidFormat("grid is too large"));
+	};
+	// Empty rows take no space in the file, so their number cannot be checked
+	// against its length below
+	if cols == 0 && rows > 0 {
+		return Err(Error::InvalidFormat("grid has rows but no columns"));
+	}
+	let Some(len) = rows.checked_mul(cols).and_then(|n| n.checked_mul(width)) else {
+		return Err(Error::InvalidFormat("grid is too large"));
+	};
+
+	// Read through take() so a corrupt header cannot cause a huge allocation
+	let mut bytes = vec![];
+	reader.take(len as u64).read_to_end(&mut bytes)?;
+	if bytes.len() < len {
+		return Err(Error::InvalidFormat("file ends before the last value"));
+	}
+
+	let values: Vec<u64> = bytes.chunks_exact(width).map(|chunk| {
+		word = [0; 8];
+		word[..width].copy_from_slice(chunk);
+		u64::from_le_bytes(word)
+	}).collect();
+	if cols == 0 {
+		return Ok(vec![]);
+	}
+	Ok(values.chunks(cols).map(<[u64]>::to_vec).collect())
+}
+
+/// Parse a grid of whitespace-separated `u64` values directly from bytes,
+/// with the same results as [`load_number_grid_from_reader`]. No `String` is
+/// allocated for each line or value, which makes this much faster on very
+/// large inputs.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::io;
+/// let grid = io::parse_number_grid_bytes(b"1 2\n3 4\n").unwrap();
+/// assert_eq!(vec![vec![1, 2], vec![3, 4]], grid);
+/// ```
+pub fn parse_number_grid_bytes(bytes: &[u8]) -> Result<Vec<Vec<u64>>, Error> {
+	let mut grid = vec![];
+	let mut row = vec![];
+	let (mut line_index, mut line_start) = (0, 0);
+	let mut i = 0;
+	while i < bytes.len() {
+		let b = bytes[i];
+		if b == b'\n' {
+			grid.push(std::mem::take(&mut row));
+			line_index += 1;
+			line_start = i + 1;
+			i += 1;
+			continue;
+		}
+		if b.is_ascii_whitespace() {
+			i += 1;
+			continue;
 		}
+
+		let start = i;
+		while i < bytes.len() && !bytes[i].is_ascii_whitespace() {
+			i += 1;
+		}
+		let token = &bytes[start..i];
+		let value = token.iter().try_fold(0u64, |n, &d| {
+			if d.is_ascii_digit() {
+				n.checked_mul(10)?.checked_add((d - b'0') as u64)
+			} else {
+				None
+			}
+		});
+		match value {
+			Some(value) => row.push(value),
+			// Let the standard parser accept or describe anything unusual. It
+			// redoes the whole line, which may also be split on whitespace
+			// outside of ASCII, such as a vertical tab or a no-break space.
+			None => {
+				let line_end = bytes[i..].iter()
+					.position(|&b| b == b'\n')
+					.map_or(bytes.len(), |n| i + n);
+				let line = std::str::from_utf8(&bytes[line_start..line_end])
+					.map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+				row = parse_row(line, line_index)?;
+				i = line_end;
+			},
+		}
+	}
+	if bytes.last().is_some_and(|&b| b != b'\n') {
 		grid.push(row);
 	}
 	Ok(grid)
 }
+
+/// Load a grid of whitespace-separated `u64` values by memory-mapping the
+/// file and parsing it with [`parse_number_grid_bytes`], so that the file is
+/// never copied into a buffer of its own.
+///
+/// Only available with the `mmap` feature. The file must not be modified
+/// while it is being loaded.
+///
+/// # Examples
+///
+/// ```no_run
+/// use segtrs::io;
+/// let grid = io::load_number_grid_mmap("data/huge.txt").unwrap();
+/// ```
+#[cfg(feature = "mmap")]
+pub fn load_number_grid_mmap(filepath: impl AsRef<Path>) -> Result<Vec<Vec<u64>>, Error> {
+	let file = File::open(filepath)?;
+	// SAFETY: the mapping is only read, and only while this function runs.
+	// Callers are told not to modify the file in the meantime.
+	let map = unsafe { memmap2::Mmap::map(&file)? };
+	parse_number_grid_bytes(&map)
+}
+
+/// Load a grid of whitespace-separated `u64` values without blocking the
+/// async runtime.
+///
+/// Only available with the `tokio` feature.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn example() {
+/// use segtrs::io;
+/// let grid = io::load_number_grid_async("data/grid.txt").await.unwrap();
+/// # }
+/// ```
+#[cfg(feature = "tokio")]
+pub async fn load_number_grid_async(filepath: impl AsRef<Path>) -> Result<Vec<Vec<u64>>, Error> {
+	load_grid_async(filepath).await
+}
+
+/// An async version of [`load_grid`]. Like the other loaders, it reads
+/// gzip files when the `gzip` feature is enabled, but decompresses them into
+/// memory as a whole first.
+///
+/// Only available with the `tokio` feature.
+#[cfg(feature = "tokio")]
+pub async fn load_grid_async<T>(filepath: impl AsRef<Path>) -> Result<Vec<Vec<T>>, Error>
+	where T: FromStr, T::Err: fmt::Display {
+
+	load_grid_from_async_reader(open_file_async(filepath).await?).await
+}
+
+/// An async version of [`load_grid_from_reader`].
+///
+/// Only available with the `tokio` feature.
+#[cfg(feature = "tokio")]
+pub async fn load_grid_from_async_reader<T>(reader: impl tokio::io::AsyncBufRead + Unpin)
+	-> Result<Vec<Vec<T>>, Error>
+	where T: FromStr, T::Err: fmt::Display {
+
+	use tokio::io::AsyncBufReadExt;
+
+	let mut lines = reader.lines();
+	let mut grid = vec![];
+	while let Some(line) = lines.next_line().await? {
+		grid.push(parse_row(&line, grid.len())?);
+	}
+	Ok(grid)
+}
+
+/// Open a file of whitespace-separated `u64` values as a stream of rows,
+/// the async counterpart of [`number_grid_rows`].
+///
+/// Only available with the `tokio` feature.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn example() {
+/// use segtrs::io;
+/// use tokio_stream::StreamExt;
+/// let mut rows = io::number_grid_rows_async("data/huge.txt").await.unwrap();
+/// while let Some(row) = rows.next().await {
+///     println!("{:?}", row.unwrap());
+/// }
+/// # }
+/// ```
+#[cfg(feature = "tokio")]
+pub async fn number_grid_rows_async(filepath: impl AsRef<Path>)
+	-> Result<impl tokio_stream::Stream<Item = Result<Vec<u64>, Error>> + Unpin, Error> {
+
+	Ok(grid_rows_stream(open_file_async(filepath).await?))
+}
+
+/// A stream that parses the rows of a grid one line at a time, the async
+/// counterpart of [`GridRows`]. Produces an error at most once, after which
+/// it ends.
+///
+/// Only available with the `tokio` feature.
+#[cfg(feature = "tokio")]
+pub fn grid_rows_stream<T, R>(reader: R)
+	-> impl tokio_stream::Stream<Item = Result<Vec<T>, Error>> + Unpin
+	where R: tokio::io::AsyncBufRead + Unpin, T: FromStr, T::Err: fmt::Display {
+
+	use tokio::io::AsyncBufReadExt;
+	use tokio_stream::StreamExt;
+
+	let mut index = 0;
+	let mut failed = false;
+	tokio_stream::wrappers::LinesStream::new(reader.lines()).map_while(move |line| {
+		if failed {
+			return None;
+		}
+		let row = line.map_err(Error::from).and_then(|line| parse_row(&line, index));
+		index += 1;
+		failed = row.is_err();
+		Some(row)
+	})
+}
+
+/// Opens a file for buffered reading. With the `gzip` feature enabled, files
+/// that start with the gzip magic bytes are decompressed on the fly, so every
+/// loader in this module can read `.gz` files.
+fn open_file(filepath: impl AsRef<Path>) -> Result<Box<dyn BufRead>, Error> {
+	#[cfg_attr(not(feature = "gzip"), allow(unused_mut))]
+	let mut reader = BufReader::new(File::open(filepath)?);
+
+	#[cfg(feature = "gzip")]
+	if reader.fill_buf()?.starts_with(&[0x1f, 0x8b]) {
+		let decoder = flate2::bufread::MultiGzDecoder::new(reader);
+		return Ok(Box::new(BufReader::new(decoder)));
+	}
+
+	Ok(Box::new(reader))
+}
+
+/// The async counterpart of [`open_file`]. Gzip files are decompressed into
+/// memory as a whole, since `flate2` only decompresses synchronously.
+#[cfg(feature = "tokio")]
+async fn open_file_async(filepath: impl AsRef<Path>)
+	-> Result<Box<dyn tokio::io::AsyncBufRead + Unpin + Send>, Error> {
+
+	#[cfg_attr(not(feature = "gzip"), allow(unused_mut))]
+	let mut reader = tokio::io::BufReader::new(tokio::fs::File::open(filepath).await?);
+
+	#[cfg(feature = "gzip")]
+	{
+		use tokio::io::{AsyncBufReadExt, AsyncReadExt};
+
+		if reader.fill_buf().await?.starts_with(&[0x1f, 0x8b]) {
+			let mut compressed = vec![];
+			reader.read_to_end(&mut compressed).await?;
+			let mut decompressed = vec![];
+			flate2::bufread::MultiGzDecoder::new(&compressed[..])
+				.read_to_end(&mut decompressed)?;
+			return Ok(Box::new(std::io::Cursor::new(decompressed)));
+		}
+	}
+
+	Ok(Box::new(reader))
+}
+
+/// Parses the whitespace-separated values of `line`, which is the line at
+/// `index` counting from 0.
+fn parse_row<T>(line: &str, index: usize) -> Result<Vec<T>, Error>
+	where T: FromStr, T::Err: fmt::Display {
+
+	line.split_whitespace()
+		.map(|token| parse_token(line, index, token))
+		.collect()
+}
+
+/// Parses `token`, a slice of `line`, which is the line at `index` counting
+/// from 0. On failure, reports where in the file the token was found.
+fn parse_token<T>(line: &str, index: usize, token: &str) -> Result<T, Error>
+	where T: FromStr, T::Err: fmt::Display {
+
+	token.parse().map_err(|err: T::Err| Error::Parse {
+		line: index + 1,
+		column: token_column(line, token),
+		token: token.to_string(),
+		message: err.to_string(),
+	})
+}
+
+/// The column of `token` within `line`, counting from 1, where `token` is a
+/// slice of `line`.
+fn token_column(line: &str, token: &str) -> usize {
+	let offset = token.as_ptr() as usize - line.as_ptr() as usize;
+	line[..offset].chars().count() + 1
+}
+
+/// The records of a CSV file, with the header row kept apart if there is one.
+///
+/// Fields are kept as strings. Use [`Csv::column`] to convert a column to
+/// another type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Csv {
+	headers: Option<Vec<String>>,
+	records: Vec<Vec<String>>,
+	// The line and column where each field of each record starts
+	positions: Vec<Vec<(usize, usize)>>,
+}
+
+impl Csv {
+	/// The field names from the first line, if the file was loaded with
+	/// `has_header` set.
+	pub fn headers(&self) -> Option<&[String]> {
+		self.headers.as_deref()
+	}
+
+	/// The records that follow the header, if any.
+	pub fn records(&self) -> &[Vec<String>] {
+		&self.records
+	}
+
+	/// The index of the column with the given header.
+	pub fn column_index(&self, name: &str) -> Option<usize> {
+		self.headers.as_ref()?.iter().position(|h| h == name)
+	}
+
+	/// Parse the field at `index` of every record.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::io;
+	/// let csv = io::load_csv_from_reader("name,age\nAda,36\nAlan,41\n".as_bytes(), true)
+	///     .unwrap();
+	/// let ages: Vec<u32> = csv.column(csv.column_index("age").unwrap()).unwrap();
+	/// assert_eq!(vec![36, 41], ages);
+	/// ```
+	pub fn column<T>(&self, index: usize) -> Result<Vec<T>, Error>
+		where T: FromStr, T::Err: fmt::Display {
+
+		self.records.iter().zip(&self.positions).map(|(record, positions)| {
+			let Some(field) = record.get(index) else {
+				return Err(Error::MissingField { line: positions[0].0, field: index });
+			};
+			let (line, column) = positions[index];
+			field.parse().map_err(|err: T::Err| Error::Parse {
+				line,
+				column,
+				token: field.clone(),
+				message: err.to_string(),
+			})
+		}).collect()
+	}
+}
+
+/// Load a comma-separated values file. Fields may be enclosed in double
+/// quotes, in which case they can contain commas, line breaks, and double
+/// quotes written twice. Blank lines are skipped. If `has_header` is set,
+/// the first record holds the column names.
+///
+/// # Examples
+///
+/// ```no_run
+/// use segtrs::io;
+/// let csv = io::load_csv("data/scores.csv", true).unwrap();
+/// println!("{:?}", csv.headers());
+/// ```
+pub fn load_csv(filepath: impl AsRef<Path>, has_header: bool) -> Result<Csv, Error> {
+	load_csv_from_reader(open_file(filepath)?, has_header)
+}
+
+/// Like [`load_csv`], but reads from any reader instead of a file.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::io;
+/// let csv = io::load_csv_from_reader("1,\"a, b\"\n2,\"say \"\"hi\"\"\"\n".as_bytes(), false)
+///     .unwrap();
+/// assert_eq!(vec!["1", "a, b"], csv.records()[0]);
+/// assert_eq!(vec!["2", "say \"hi\""], csv.records()[1]);
+/// ```
+pub fn load_csv_from_reader(mut reader: impl Read, has_header: bool) -> Result<Csv, Error> {
+	let mut input = String::new();
+	reader.read_to_string(&mut input)?;
+
+	let mut records = vec![];
+	let mut positions = vec![];
+	let mut record = vec![];
+	let mut record_positions = vec![];
+	let mut field = String::new();
+	let (mut line, mut column) = (1, 1);
+	let mut field_start = (line, column);
+	let mut in_quotes = false;
+
+	let mut chars = input.chars().peekable();
+	while let Some(c) = chars.next() {
+		let at_field_start = field.is_empty() && field_start == (line, column);
+		(line, column) = if c == '\n' { (line + 1, 1) } else { (line, column + 1) };
+
+		if in_quotes {
+			if c != '"' {
+				field.push(c);
+			} else if chars.next_if_eq(&'"').is_some() {
+				column += 1;
+				field.push('"');
+			} else {
+				in_quotes = false;
+			}
+			continue;
+		}
+
+		match c {
+			'"' if at_field_start => in_quotes = true,
+			',' => {
+				record.push(std::mem::take(&mut field));
+				record_positions.push(field_start);
+				field_start = (line, column);
+			},
+			'\r' if chars.peek() == Some(&'\n') => {},
+			'\n' => {
+				// A blank line is a single empty field
+				if !record.is_empty() || !field.is_empty() {
+					record.push(std::mem::take(&mut field));
+					record_positions.push(field_start);
+					records.push(std::mem::take(&mut record));
+					positions.push(std::mem::take(&mut record_positions));
+				}
+				field_start = (line, column);
+			},
+			_ => field.push(c),
+		}
+	}
+
+	if in_quotes {
+		return Err(Error::Parse {
+			line: field_start.0,
+			column: field_start.1,
+			token: format!("\"{field}"),
+			message: "unterminated quoted field".to_string(),
+		});
+	}
+	if !record.is_empty() || !field.is_empty() {
+		record.push(field);
+		record_positions.push(field_start);
+		records.push(record);
+		positions.push(record_positions);
+	}
+
+	let headers = if has_header && !records.is_empty() {
+		positions.remove(0);
+		Some(records.remove(0))
+	} else {
+		None
+	};
+	Ok(Csv {
+		headers,
+		records,
+		positions,
+	})
+}
+
+/// Load a list of comma-separated words, each of which may be enclosed in
+/// double quotes, as in `"MARY","PATRICIA","LINDA"`. The words may be split
+/// across any number of lines, and empty fields are skipped. If `sorted` is
+/// set, the words are sorted in lexicographic order.
+///
+/// # Examples
+///
+/// ```no_run
+/// use segtrs::io;
+/// // Project Euler 22
+/// let names = io::load_quoted_words("data/names.txt", true).unwrap();
+/// ```
+pub fn load_quoted_words(filepath: impl AsRef<Path>, sorted: bool) -> Result<Vec<String>, Error> {
+	load_quoted_words_from_reader(open_file(filepath)?, sorted)
+}
+
+/// Like [`load_quoted_words`], but reads from any reader instead of a file.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::io;
+/// let input = "\"MARY\",\"PATRICIA\",\"LINDA\"";
+/// let names = io::load_quoted_words_from_reader(input.as_bytes(), true).unwrap();
+/// assert_eq!(vec!["LINDA", "MARY", "PATRICIA"], names);
+/// ```
+pub fn load_quoted_words_from_reader(reader: impl Read, sorted: bool)
+	-> Result<Vec<String>, Error> {
+
+	let csv = load_csv_from_reader(reader, false)?;
+	let mut words: Vec<String> = csv.records.into_iter()
+		.flatten()
+		.filter(|word| !word.is_empty())
+		.collect();
+	if sorted {
+		words.sort();
+	}
+	Ok(words)
+}
+
+/// A triangular array, where the row at index i has i + 1 values.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::io;
+/// let triangle = io::load_triangle_from_reader::<u64>("3\n7 4\n2 4 6\n".as_bytes()).unwrap();
+/// assert_eq!(3, triangle.height());
+/// assert_eq!(Some(&4), triangle.get(2, 1));
+/// assert_eq!(None, triangle.get(1, 2));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Triangle<T> {
+	rows: Vec<Vec<T>>,
+}
+
+impl<T> Triangle<T> {
+	/// Create a triangle from its rows. Panics if the row at index i does not
+	/// have i + 1 values.
+	pub fn new(rows: Vec<Vec<T>>) -> Self {
+		for (i, row) in rows.iter().enumerate() {
+			if row.len() != i + 1 {
+				panic!("row {i} of a triangle must have {} values", i + 1);
+			}
+		}
+		Self {
+			rows,
+		}
+	}
+
+	/// The number of rows.
+	pub fn height(&self) -> usize {
+		self.rows.len()
+	}
+
+	pub fn rows(&self) -> &[Vec<T>] {
+		&self.rows
+	}
+
+	/// The value at position `col` of row `row`, if there is one.
+	pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+		self.rows.get(row)?.get(col)
+	}
+
+	pub fn into_rows(self) -> Vec<Vec<T>> {
+		self.rows
+	}
+}
+
+/// Load a triangle of whitespace-separated values, where the first line has
+/// one value, the second two, and so on. Blank lines are skipped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use segtrs::io;
+/// let triangle = io::load_triangle::<u64>("data/triangle.txt").unwrap();
+/// ```
+pub fn load_triangle<T>(filepath: impl AsRef<Path>) -> Result<Triangle<T>, Error>
+	where T: FromStr, T::Err: fmt::Display {
+
+	load_triangle_from_reader(open_file(filepath)?)
+}
+
+/// Like [`load_triangle`], but reads from any buffered reader instead of a
+/// file.
+pub fn load_triangle_from_reader<T>(reader: impl BufRead) -> Result<Triangle<T>, Error>
+	where T: FromStr, T::Err: fmt::Display {
+
+	let mut rows: Vec<Vec<T>> = vec![];
+	for (index, line) in reader.lines().enumerate() {
+		let line = line?;
+		let tokens: Vec<&str> = line.split_whitespace().collect();
+		if tokens.is_empty() {
+			continue;
+		}
+		if tokens.len() != rows.len() + 1 {
+			return Err(Error::RowLength {
+				line: index + 1,
+				expected: rows.len() + 1,
+				found: tokens.len(),
+			});
+		}
+
+		let row = tokens.into_iter()
+			.map(|token| parse_token(&line, index, token))
+			.collect::<Result<_, _>>()?;
+		rows.push(row);
+	}
+	Ok(Triangle { rows })
+}
+
+/// An edge of a [`Graph`] between vertices `u` and `v`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edge<W> {
+	pub u: usize,
+	pub v: usize,
+	pub weight: W,
+}
+
+/// An undirected graph with weighted edges, whose vertices are numbered from
+/// 0. Edges are kept in the order they were added.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::io::Graph;
+/// let mut graph = Graph::new(3);
+/// graph.add_edge(0, 1, 7);
+/// graph.add_edge(2, 1, 4);
+/// assert_eq!(2, graph.edges().len());
+/// assert_eq!(vec![(0, &7), (2, &4)], graph.neighbors(1).collect::<Vec<_>>());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Graph<W> {
+	vertices: usize,
+	edges: Vec<Edge<W>>,
+}
+
+impl<W> Graph<W> {
+	/// Create a graph with `vertices` vertices and no edges.
+	pub fn new(vertices: usize) -> Self {
+		Self {
+			vertices,
+			edges: vec![],
+		}
+	}
+
+	/// Add an edge between `u` and `v`. Panics if either is not a vertex.
+	pub fn add_edge(&mut self, u: usize, v: usize, weight: W) {
+		if u >= self.vertices || v >= self.vertices {
+			panic!("edge ({u}, {v}) is out of bounds for a graph with {} vertices", self.vertices);
+		}
+		self.edges.push(Edge { u, v, weight });
+	}
+
+	pub fn vertices(&self) -> usize {
+		self.vertices
+	}
+
+	pub fn edges(&self) -> &[Edge<W>] {
+		&self.edges
+	}
+
+	/// The vertices joined to `vertex` by an edge, with the weight of that
+	/// edge, in the order the edges were added.
+	pub fn neighbors(&self, vertex: usize) -> impl Iterator<Item = (usize, &W)> {
+		self.edges.iter().filter_map(move |edge| {
+			if edge.u == vertex {
+				Some((edge.v, &edge.weight))
+			} else if edge.v == vertex {
+				Some((edge.u, &edge.weight))
+			} else {
+				None
+			}
+		})
+	}
+}
+
+/// Load a graph from a file with one edge per line, given as the two
+/// vertices it joins followed by its weight, such as `0 3 16`. The graph has
+/// as many vertices as needed for the largest one mentioned. Blank lines are
+/// skipped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use segtrs::io;
+/// let graph = io::load_edge_list::<u64>("data/network.txt").unwrap();
+/// let total: u64 = graph.edges().iter().map(|edge| edge.weight).sum();
+/// ```
+pub fn load_edge_list<W>(filepath: impl AsRef<Path>) -> Result<Graph<W>, Error>
+	where W: FromStr, W::Err: fmt::Display {
+
+	load_edge_list_from_reader(open_file(filepath)?)
+}
+
+/// Like [`load_edge_list`], but reads from any buffered reader instead of a
+/// file.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::io;
+/// let graph = io::load_edge_list_from_reader::<u64>("0 1 5\n1 3 2\n".as_bytes()).unwrap();
+/// assert_eq!(4, graph.vertices());
+/// assert_eq!(5, graph.edges()[0].weight);
+/// ```
+pub fn load_edge_list_from_reader<W>(reader: impl BufRead) -> Result<Graph<W>, Error>
+	where W: FromStr, W::Err: fmt::Display {
+
+	let mut edges = vec![];
+	for (index, line) in reader.lines().enumerate() {
+		let line = line?;
+		let tokens: Vec<&str> = line.split_whitespace().collect();
+		if tokens.is_empty() {
+			continue;
+		}
+		if tokens.len() > 3 {
+			return Err(Error::RowLength {
+				line: index + 1,
+				expected: 3,
+				found: tokens.len(),
+			});
+		}
+		let field = |i: usize| tokens.get(i).ok_or(Error::MissingField {
+			line: index + 1,
+			field: i,
+		});
+		// The number of vertices is one more than the largest index, so that
+		// must not overflow either
+		let vertex = |i: usize| -> Result<usize, Error> {
+			let token = field(i)?;
+			let v: usize = parse_token(&line, index, token)?;
+			v.checked_add(1).ok_or_else(|| Error::Parse {
+				line: index + 1,
+				column: token_column(&line, token),
+				token: token.to_string(),
+				message: "vertex index is too large".to_string(),
+			})?;
+			Ok(v)
+		};
+		let u = vertex(0)?;
+		let v = vertex(1)?;
+		let weight = parse_token(&line, index, field(2)?)?;
+		edges.push(Edge { u, v, weight });
+	}
+	let vertices = edges.iter().map(|edge| edge.u.max(edge.v) + 1).max().unwrap_or(0);
+	Ok(Graph { vertices, edges })
+}
+
+/// Load a graph from a square adjacency matrix, where the value in row i
+/// and column j is the weight of the edge between vertices i and j, or `-`
+/// if there is none. Values may be separated by commas, whitespace or both.
+/// The matrix must be symmetric; its diagonal is ignored.
+///
+/// # Examples
+///
+/// ```no_run
+/// use segtrs::io;
+/// let graph = io::load_adjacency_matrix::<u64>("data/network.txt").unwrap();
+/// println!("{} vertices, {} edges", graph.vertices(), graph.edges().len());
+/// ```
+pub fn load_adjacency_matrix<W>(filepath: impl AsRef<Path>) -> Result<Graph<W>, Error>
+	where W: FromStr + PartialEq, W::Err: fmt::Display {
+
+	load_adjacency_matrix_from_reader(open_file(filepath)?)
+}
+
+/// Like [`load_adjacency_matrix`], but reads from any buffered reader
+/// instead of a file.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::io;
+/// let text = "-,16,12\n16,-,-\n12,-,-\n";
+/// let graph = io::load_adjacency_matrix_from_reader::<u64>(text.as_bytes()).unwrap();
+/// assert_eq!(3, graph.vertices());
+/// assert_eq!(vec![(1, &16), (2, &12)], graph.neighbors(0).collect::<Vec<_>>());
+/// ```
+pub fn load_adjacency_matrix_from_reader<W>(reader: impl BufRead) -> Result<Graph<W>, Error>
+	where W: FromStr + PartialEq, W::Err: fmt::Display {
+
+	let mut matrix: Vec<Vec<Option<W>>> = vec![];
+	for (index, line) in reader.lines().enumerate() {
+		let line = line?;
+		let tokens: Vec<&str> = line.split(|c: char| c == ',' || c.is_whitespace())
+			.filter(|token| !token.is_empty())
+			.collect();
+		if tokens.is_empty() {
+			continue;
+		}
+		if let Some(first) = matrix.first() && tokens.len() != first.len() {
+			return Err(Error::RowLength {
+				line: index + 1,
+				expected: first.len(),
+				found: tokens.len(),
+			});
+		}
+		let row = tokens.into_iter()
+			.map(|token| match token {
+				"-" => Ok(None),
+				_ => parse_token(&line, index, token).map(Some),
+			})
+			.collect::<Result<_, _>>()?;
+		matrix.push(row);
+	}
+	if matrix.first().is_some_and(|row| row.len() != matrix.len()) {
+		return Err(Error::InvalidFormat("adjacency matrix is not square"));
+	}
+
+	let n = matrix.len();
+	let mut graph = Graph::new(n);
+	for (i, j) in (0..n).flat_map(|i| (i + 1..n).map(move |j| (i, j))) {
+		if matrix[i][j] != matrix[j][i] {
+			return Err(Error::InvalidFormat("adjacency matrix is not symmetric"));
+		}
+		if let Some(weight) = matrix[i][j].take() {
+			graph.add_edge(i, j, weight);
+		}
+	}
+	Ok(graph)
+}
+
+/// How the values in each column of a written grid line up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Alignment {
+	/// Values are written as they are, without padding.
+	#[default]
+	None,
+	/// Values are padded on the right to the width of their column.
+	Left,
+	/// Values are padded on the left to the width of their column.
+	Right,
+}
+
+/// Options that control how [`write_grid`] lays out a grid. By default,
+/// values are separated by single spaces and are not padded.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::io::{self, Alignment, GridWriteOptions};
+/// let options = GridWriteOptions::new().alignment(Alignment::Right);
+/// let mut output = vec![];
+/// io::write_grid_to_writer(&mut output, &[vec![1, 20], vec![300, 4]], &options).unwrap();
+/// assert_eq!("  1 20\n300  4\n", String::from_utf8(output).unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct GridWriteOptions {
+	delimiter: Delimiter,
+	alignment: Alignment,
+}
+
+impl GridWriteOptions {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// The separator written between values. [`Delimiter::Whitespace`] is
+	/// written as a single space.
+	pub fn delimiter(mut self, delimiter: Delimiter) -> Self {
+		self.delimiter = delimiter;
+		self
+	}
+
+	pub fn alignment(mut self, alignment: Alignment) -> Self {
+		self.alignment = alignment;
+		self
+	}
+}
+
+pub fn write_number_grid(filepath: impl AsRef<Path>, grid: &[Vec<u64>], options: &GridWriteOptions)
+	-> Result<(), Error> {
+
+	write_grid(filepath, grid, options)
+}
+
+/// Write a grid to a file, one row per line, in a format that
+/// [`load_grid_with`] can read back using the same delimiter.
+///
+/// # Examples
+///
+/// ```no_run
+/// use segtrs::io::{self, Delimiter, GridParseOptions, GridWriteOptions};
+/// let grid = vec![vec![1, 2], vec![3, 4]];
+/// let options = GridWriteOptions::new().delimiter(Delimiter::Comma);
+/// io::write_grid("data/grid.csv", &grid, &options).unwrap();
+///
+/// let options = GridParseOptions::new().delimiter(Delimiter::Comma);
+/// let loaded: Vec<Vec<u64>> = io::load_grid_with("data/grid.csv", &options).unwrap();
+/// assert_eq!(grid, loaded);
+/// ```
+pub fn write_grid<T: fmt::Display>(filepath: impl AsRef<Path>, grid: &[Vec<T>],
+	options: &GridWriteOptions) -> Result<(), Error> {
+
+	let file = File::create(filepath)?;
+	let mut writer = BufWriter::new(file);
+	write_grid_to_writer(&mut writer, grid, options)?;
+	writer.flush()?;
+	Ok(())
+}
+
+/// Like [`write_grid`], but writes to any writer instead of a file.
+pub fn write_grid_to_writer<T: fmt::Display>(mut writer: impl Write, grid: &[Vec<T>],
+	options: &GridWriteOptions) -> Result<(), Error> {
+
+	let separator = options.delimiter.separator();
+	let rows: Vec<Vec<String>> = grid.iter()
+		.map(|row| row.iter().map(|value| value.to_string()).collect())
+		.collect();
+	let mut widths: Vec<usize> = vec![];
+	for row in &rows {
+		for (j, value) in row.iter().enumerate() {
+			let width = value.chars().count();
+			match widths.get_mut(j) {
+				Some(w) => *w = (*w).max(width),
+				None => widths.push(width),
+			}
+		}
+	}
+
+	for row in &rows {
+		for (j, value) in row.iter().enumerate() {
+			if j > 0 {
+				write!(writer, "{separator}")?;
+			}
+			match options.alignment {
+				Alignment::None => write!(writer, "{value}")?,
+				// Avoid trailing spaces after the last value
+				Alignment::Left if j + 1 == row.len() => write!(writer, "{value}")?,
+				Alignment::Left => write!(writer, "{value:<width$}", width = widths[j])?,
+				Alignment::Right => write!(writer, "{value:>width$}", width = widths[j])?,
+			}
+		}
+		writeln!(writer)?;
+	}
+	Ok(())
+}
+
+/// A rectangular grid of values stored row by row in a single `Vec`, so
+/// every row is guaranteed to have the same length.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::io::Grid;
+/// let grid = Grid::<u64>::load_from_reader("1 2 3\n4 5 6\n".as_bytes()).unwrap();
+/// assert_eq!((2, 3), (grid.rows(), grid.cols()));
+/// assert_eq!(6, grid[(1, 2)]);
+/// assert_eq!(None, grid.get(2, 0));
+/// assert_eq!(vec![&3, &6], grid.column(2).collect::<Vec<_>>());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+	rows: usize,
+	cols: usize,
+	values: Vec<T>,
+}
+
+impl<T> Grid<T> {
+	/// Create a grid from its values in row order. Panics if there are not
+	/// exactly `rows * cols` values.
+	pub fn new(rows: usize, cols: usize, values: Vec<T>) -> Self {
+		if rows.checked_mul(cols) != Some(values.len()) {
+			panic!("expected {rows} * {cols} values, found {}", values.len());
+		}
+		Self {
+			rows,
+			cols,
+			values,
+		}
+	}
+
+	/// Create a grid from nested rows, which must all have the same length.
+	/// The row at index i is reported as line i + 1 if its length differs
+	/// from that of the first row.
+	pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, Error> {
+		let cols = rows.first().map_or(0, Vec::len);
+		if let Some(i) = rows.iter().position(|row| row.len() != cols) {
+			return Err(Error::RowLength {
+				line: i + 1,
+				expected: cols,
+				found: rows[i].len(),
+			});
+		}
+		Ok(Self {
+			rows: rows.len(),
+			cols,
+			values: rows.into_iter().flatten().collect(),
+		})
+	}
+
+	pub fn rows(&self) -> usize {
+		self.rows
+	}
+
+	pub fn cols(&self) -> usize {
+		self.cols
+	}
+
+	/// All values, in row order.
+	pub fn values(&self) -> &[T] {
+		&self.values
+	}
+
+	pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+		if row < self.rows && col < self.cols {
+			Some(&self.values[row * self.cols + col])
+		} else {
+			None
+		}
+	}
+
+	pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+		if row < self.rows && col < self.cols {
+			Some(&mut self.values[row * self.cols + col])
+		} else {
+			None
+		}
+	}
+
+	/// The values of row `row`. Panics if there is no such row.
+	pub fn row(&self, row: usize) -> &[T] {
+		if row >= self.rows {
+			panic!("row {row} is out of bounds for a grid with {} rows", self.rows);
+		}
+		&self.values[row * self.cols..(row + 1) * self.cols]
+	}
+
+	/// The values of column `col`, from top to bottom. Panics if there is no
+	/// such column.
+	pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+		if col >= self.cols {
+			panic!("column {col} is out of bounds for a grid with {} columns", self.cols);
+		}
+		self.values[col..].iter().step_by(self.cols)
+	}
+
+	/// An iterator over the rows, from top to bottom.
+	pub fn iter_rows(&self) -> impl Iterator<Item = &[T]> {
+		(0..self.rows).map(|row| self.row(row))
+	}
+
+	/// An iterator over the columns, from left to right, each of which is an
+	/// iterator over its values.
+	pub fn iter_columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+		(0..self.cols).map(|col| self.column(col))
+	}
+
+	/// The grid whose rows are the columns of this one, so that columns can
+	/// afterwards be read as contiguous slices with [`Grid::row`].
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::io::Grid;
+	/// let grid = Grid::new(2, 3, vec![1, 2, 3, 4, 5, 6]);
+	/// let t = grid.transpose();
+	/// assert_eq!((3, 2), (t.rows(), t.cols()));
+	/// assert_eq!(&[1, 4, 2, 5, 3, 6], t.values());
+	/// ```
+	pub fn transpose(&self) -> Grid<T>
+		where T: Clone {
+
+		Grid {
+			rows: self.cols,
+			cols: self.rows,
+			values: self.iter_columns().flatten().cloned().collect(),
+		}
+	}
+}
+
+/// A direction in which a run of values in a [`Grid`] can extend from its
+/// first cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+	Right,
+	Down,
+	/// Down and to the right.
+	DownRight,
+	/// Down and to the left.
+	DownLeft,
+}
+
+impl Direction {
+	/// All directions, which between them cover every straight line of cells
+	/// exactly once.
+	pub const ALL: [Direction; 4] = [
+		Direction::Right, Direction::Down, Direction::DownRight, Direction::DownLeft,
+	];
+
+	fn offsets(self) -> (isize, isize) {
+		match self {
+			Direction::Right => (0, 1),
+			Direction::Down => (1, 0),
+			Direction::DownRight => (1, 1),
+			Direction::DownLeft => (1, -1),
+		}
+	}
+}
+
+impl<T> Grid<T> {
+	/// The position `steps` moves of `(dr, dc)` away from `(row, col)`, if it
+	/// lies in the grid.
+	fn offset(&self, (row, col): (usize, usize), (dr, dc): (isize, isize), steps: usize)
+		-> Option<(usize, usize)> {
+
+		let row = row.checked_add_signed(dr.checked_mul(steps as isize)?)?;
+		let col = col.checked_add_signed(dc.checked_mul(steps as isize)?)?;
+		(row < self.rows && col < self.cols).then_some((row, col))
+	}
+
+	fn neighbors(&self, row: usize, col: usize, offsets: &'static [(isize, isize)])
+		-> impl Iterator<Item = ((usize, usize), &T)> {
+
+		offsets.iter()
+			.filter_map(move |&offset| self.offset((row, col), offset, 1))
+			.map(|(r, c)| ((r, c), &self[(r, c)]))
+	}
+
+	/// The cells above, below, left and right of `(row, col)` that lie in
+	/// the grid, with their positions.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::io::Grid;
+	/// let grid = Grid::new(2, 2, vec![1, 2, 3, 4]);
+	/// let neighbors: Vec<_> = grid.neighbors4(0, 0).collect();
+	/// assert_eq!(vec![((1, 0), &3), ((0, 1), &2)], neighbors);
+	/// ```
+	pub fn neighbors4(&self, row: usize, col: usize)
+		-> impl Iterator<Item = ((usize, usize), &T)> {
+
+		self.neighbors(row, col, &[(-1, 0), (1, 0), (0, -1), (0, 1)])
+	}
+
+	/// Like [`Grid::neighbors4`], but also includes the diagonal neighbors.
+	pub fn neighbors8(&self, row: usize, col: usize)
+		-> impl Iterator<Item = ((usize, usize), &T)> {
+
+		self.neighbors(row, col, &[
+			(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1),
+		])
+	}
+
+	/// Every run of `len` consecutive values that lies in the grid and
+	/// extends in `direction` from its first cell. Panics if `len` is 0.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::io::{Direction, Grid};
+	/// let grid = Grid::new(2, 3, vec![1, 2, 3, 4, 5, 6]);
+	/// let runs: Vec<Vec<&u64>> = grid.runs(2, Direction::DownLeft).collect();
+	/// assert_eq!(vec![vec![&2, &4], vec![&3, &5]], runs);
+	/// ```
+	pub fn runs(&self, len: usize, direction: Direction) -> impl Iterator<Item = Vec<&T>> {
+		if len == 0 {
+			panic!("runs must have at least one value");
+		}
+		let offsets = direction.offsets();
+		(0..self.rows)
+			.flat_map(move |row| (0..self.cols).map(move |col| (row, col)))
+			.filter(move |&start| self.offset(start, offsets, len - 1).is_some())
+			.map(move |start| {
+				(0..len).map(|i| {
+					let position = self.offset(start, offsets, i).unwrap();
+					&self[position]
+				}).collect()
+			})
+	}
+
+	/// Every run of `len` consecutive values in any of the directions in
+	/// [`Direction::ALL`].
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::io::Grid;
+	/// let grid = Grid::new(3, 3, vec![1, 2, 3, 4, 5, 6, 7, 8, 9]);
+	/// // Greatest product of two adjacent numbers in any direction
+	/// let greatest = grid.all_runs(2).map(|run| run.into_iter().product::<u64>()).max();
+	/// assert_eq!(Some(72), greatest);
+	/// ```
+	pub fn all_runs(&self, len: usize) -> impl Iterator<Item = Vec<&T>> {
+		Direction::ALL.into_iter().flat_map(move |direction| self.runs(len, direction))
+	}
+}
+
+impl<T> Grid<T> where T: FromStr, T::Err: fmt::Display {
+	/// Load a grid of whitespace-separated values as with
+	/// [`load_rectangular_grid`].
+	pub fn load(filepath: impl AsRef<Path>) -> Result<Self, Error> {
+		Self::from_rows(load_rectangular_grid(filepath)?)
+	}
+
+	/// Like [`Grid::load`], but reads from any buffered reader instead of a
+	/// file.
+	pub fn load_from_reader(reader: impl BufRead) -> Result<Self, Error> {
+		let options = GridParseOptions::new().require_rectangular(true);
+		Self::from_rows(load_grid_from_reader_with(reader, &options)?)
+	}
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+	type Output = T;
+
+	fn index(&self, (row, col): (usize, usize)) -> &T {
+		match self.get(row, col) {
+			Some(value) => value,
+			None => panic!("({row}, {col}) is out of bounds for a {}x{} grid",
+				self.rows, self.cols),
+		}
+	}
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+	fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+		let (rows, cols) = (self.rows, self.cols);
+		match self.get_mut(row, col) {
+			Some(value) => value,
+			None => panic!("({row}, {col}) is out of bounds for a {rows}x{cols} grid"),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::fs;
+
+	#[test]
+	fn load_grid_from_file() {
+		let path = std::env::temp_dir().join("segtrs_load_grid_from_file.txt");
+		fs::write(&path, "-1 2\n30 -40\n").unwrap();
+
+		// Any kind of path works
+		let grid: Vec<Vec<i64>> = load_grid(&path).unwrap();
+		assert_eq!(vec![vec![-1, 2], vec![30, -40]], grid);
+		assert!(load_number_grid(path.as_path()).is_err());
+		assert!(load_number_grid(&path).is_err());
+		fs::remove_file(path).unwrap();
+	}
+
+	#[test]
+	fn load_grid_of_other_types() {
+		let grid: Vec<Vec<f64>> = load_grid_from_reader("-1 2\n30 -40\n".as_bytes()).unwrap();
+		assert_eq!(-40.0, grid[1][1]);
+
+		let input = "123456789012345678901234567890 7\n";
+		let grid: Vec<Vec<BigInt>> = load_grid_from_reader(input.as_bytes()).unwrap();
+		assert_eq!(30, grid[0][0].digits().len());
+	}
+
+	#[test]
+	fn parse_error_reports_position() {
+		let result = load_number_grid_from_reader("1 2 3\n4  é5 6\n".as_bytes());
+		match result {
+			Err(Error::Parse { line, column, token, .. }) => {
+				assert_eq!((2, 4), (line, column));
+				assert_eq!("é5", token);
+			},
+			other => panic!("expected a parse error, got {other:?}"),
+		}
+	}
+
+	#[test]
+	fn missing_file_is_io_error() {
+		let result = load_number_grid("/nonexistent/segtrs/grid.txt");
+		assert!(matches!(result, Err(Error::Io(_))));
+	}
+
+	#[test]
+	fn custom_delimiters() {
+		let options = GridParseOptions::new().delimiter(Delimiter::Tab);
+		let grid: Vec<Vec<i32>> = load_grid_from_reader_with("1\t-2\n\n3\t 4".as_bytes(), &options)
+			.unwrap();
+		assert_eq!(vec![vec![1, -2], vec![], vec![3, 4]], grid);
+
+		let options = GridParseOptions::new().delimiter(Delimiter::Custom("::".to_string()));
+		let grid: Vec<Vec<u64>> = load_grid_from_reader_with("7::8::9".as_bytes(), &options)
+			.unwrap();
+		assert_eq!(vec![vec![7, 8, 9]], grid);
+	}
+
+	#[test]
+	fn empty_field_is_parse_error() {
+		let options = GridParseOptions::new().delimiter(Delimiter::Comma);
+		let result = load_grid_from_reader_with::<u64>("1,2\n3,,4".as_bytes(), &options);
+		assert!(matches!(result, Err(Error::Parse { line: 2, column: 3, .. })));
+	}
+
+	#[test]
+	fn csv_quoted_fields_and_positions() {
+		let input = "id,note\r\n1,\"multi\nline\"\n\n2,\"x\"\"y\"\n3,oops\n";
+		let csv = load_csv_from_reader(input.as_bytes(), true).unwrap();
+		assert_eq!(Some(&["id".to_string(), "note".to_string()][..]), csv.headers());
+		assert_eq!(vec!["1", "multi\nline"], csv.records()[0]);
+		assert_eq!(vec!["2", "x\"y"], csv.records()[1]);
+
+		let ids: Vec<u8> = csv.column(0).unwrap();
+		assert_eq!(vec![1, 2, 3], ids);
+		assert!(matches!(csv.column::<u8>(1), Err(Error::Parse { line: 2, column: 3, .. })));
+	}
+
+	#[test]
+	fn csv_errors() {
+		let csv = load_csv_from_reader("1,2\n3\n".as_bytes(), false).unwrap();
+		assert!(matches!(csv.column::<u8>(1), Err(Error::MissingField { line: 2, field: 1 })));
+		assert_eq!(None, csv.column_index("a"));
+
+		let result = load_csv_from_reader("a,\"b\nc".as_bytes(), false);
+		assert!(matches!(result, Err(Error::Parse { line: 1, column: 3, .. })));
+	}
+
+	#[test]
+	fn triangle_shape_is_validated() {
+		let triangle: Triangle<i8> = load_triangle_from_reader("\n-1\n2 3\n\n".as_bytes()).unwrap();
+		assert_eq!(vec![vec![-1], vec![2, 3]], triangle.into_rows());
+
+		let result = load_triangle_from_reader::<u64>("1\n2 3\n4 5\n".as_bytes());
+		assert!(matches!(result, Err(Error::RowLength { line: 3, expected: 3, found: 2 })));
+	}
+
+	#[test]
+	#[should_panic(expected = "must have 2 values")]
+	fn triangle_new_with_wrong_shape() {
+		Triangle::new(vec![vec![1], vec![2, 3, 4]]);
+	}
+
+	#[test]
+	fn rectangular_grids() {
+		let options = GridParseOptions::new().require_rectangular(true);
+		let grid: Vec<Vec<u8>> = load_grid_from_reader_with("1 2\n3 4\n".as_bytes(), &options)
+			.unwrap();
+		assert_eq!(vec![vec![1, 2], vec![3, 4]], grid);
+
+		let result = load_grid_from_reader_with::<u8>("1 2\n3 4\n5\n6 7 8".as_bytes(), &options);
+		assert!(matches!(result, Err(Error::RowLength { line: 3, expected: 2, found: 1 })));
+		let ragged: Vec<Vec<u8>> = load_grid_from_reader("1 2\n5\n".as_bytes()).unwrap();
+		assert_eq!(2, ragged.len());
+	}
+
+	#[test]
+	fn write_grid_round_trip() {
+		let grid = vec![vec![10, -2, 3], vec![4, 500, -6]];
+		let custom = Delimiter::Custom("; ".to_string());
+		for delimiter in [Delimiter::Whitespace, Delimiter::Tab, custom] {
+			for alignment in [Alignment::None, Alignment::Left, Alignment::Right] {
+				let options = GridWriteOptions::new()
+					.delimiter(delimiter.clone())
+					.alignment(alignment);
+				let mut output = vec![];
+				write_grid_to_writer(&mut output, &grid, &options).unwrap();
+
+				let options = GridParseOptions::new().delimiter(delimiter.clone());
+				let loaded: Vec<Vec<i32>> = load_grid_from_reader_with(&output[..], &options)
+					.unwrap();
+				assert_eq!(grid, loaded);
+			}
+		}
+	}
+
+	#[test]
+	fn write_grid_left_aligned() {
+		let options = GridWriteOptions::new()
+			.delimiter(Delimiter::Custom(" | ".to_string()))
+			.alignment(Alignment::Left);
+		let mut output = vec![];
+		write_grid_to_writer(&mut output, &[vec![1, 22], vec![333, 4], vec![5]], &options).unwrap();
+		assert_eq!("1   | 22\n333 | 4\n5\n", String::from_utf8(output).unwrap());
+	}
+
+	#[test]
+	fn write_number_grid_to_file() {
+		let path = std::env::temp_dir().join("segtrs_write_number_grid_to_file.txt");
+		let grid = vec![vec![8, 2, 22], vec![49, 49, 99]];
+		write_number_grid(&path, &grid, &GridWriteOptions::new()).unwrap();
+		assert_eq!(grid, load_number_grid(&path).unwrap());
+		fs::remove_file(path).unwrap();
+	}
+
+	#[test]
+	fn load_list_across_lines() {
+		let values: Vec<i32> = load_list_from_reader("-1\n2 3\n\n4".as_bytes()).unwrap();
+		assert_eq!(vec![-1, 2, 3, 4], values);
+
+		let result = load_list_from_reader::<u64>("1\n2 x".as_bytes());
+		assert!(matches!(result, Err(Error::Parse { line: 2, column: 3, .. })));
+	}
+
+	#[test]
+	fn load_bigints_one_per_line() {
+		let input = "  99999999999999999999999\n\n1\n";
+		let numbers = load_bigints_from_reader(input.as_bytes()).unwrap();
+		assert_eq!(2, numbers.len());
+		let sum = numbers[0].add(&numbers[1]);
+		assert_eq!("100000000000000000000000".parse::<BigInt>().unwrap(), sum);
+
+		let result = load_bigints_from_reader("12 34\n".as_bytes());
+		assert!(matches!(result, Err(Error::Parse { line: 1, column: 1, .. })));
+	}
+
+	#[test]
+	fn grid_rows_stop_after_error() {
+		let mut rows = grid_rows_from_reader::<u8, _>("1 2\n3 x\n5 6\n".as_bytes());
+		assert_eq!(Some(vec![1, 2]), rows.next().map(Result::unwrap));
+		assert!(matches!(rows.next(), Some(Err(Error::Parse { line: 2, column: 3, .. }))));
+		assert!(rows.next().is_none());
+	}
+
+	#[test]
+	fn grid_rows_match_loaded_grid() {
+		let input = "8 2 22\n\n49 49 99 40\n";
+		let rows: Vec<Vec<u64>> = grid_rows_from_reader(input.as_bytes())
+			.collect::<Result<_, _>>()
+			.unwrap();
+		assert_eq!(load_number_grid_from_reader(input.as_bytes()).unwrap(), rows);
+	}
+
+	#[test]
+	fn grid_rows_and_columns() {
+		let mut grid = Grid::new(2, 3, vec![1, 2, 3, 4, 5, 6]);
+		grid[(0, 1)] = 20;
+		let rows: Vec<&[i32]> = grid.iter_rows().collect();
+		assert_eq!(vec![&[1, 20, 3][..], &[4, 5, 6][..]], rows);
+		let column_sums: Vec<i32> = grid.iter_columns().map(|col| col.sum()).collect();
+		assert_eq!(vec![5, 25, 9], column_sums);
+		assert_eq!(None, grid.get(0, 3));
+	}
+
+	#[test]
+	fn grid_from_ragged_rows() {
+		let result = Grid::from_rows(vec![vec![1, 2], vec![3, 4], vec![5]]);
+		assert!(matches!(result, Err(Error::RowLength { line: 3, expected: 2, found: 1 })));
+		let empty: Grid<u8> = Grid::from_rows(vec![]).unwrap();
+		assert_eq!((0, 0), (empty.rows(), empty.cols()));
+	}
+
+	#[test]
+	#[should_panic(expected = "out of bounds")]
+	fn grid_index_out_of_bounds() {
+		let grid = Grid::new(2, 2, vec![1, 2, 3, 4]);
+		let _ = grid[(2, 0)];
+	}
+
+	#[test]
+	fn grid_neighbors_at_edges() {
+		let grid = Grid::new(3, 3, (1..=9).collect());
+		assert_eq!(4, grid.neighbors4(1, 1).count());
+		assert_eq!(8, grid.neighbors8(1, 1).count());
+		let corner: Vec<u32> = grid.neighbors8(2, 2).map(|(_, &v)| v).collect();
+		assert_eq!(vec![5, 6, 8], corner);
+		assert_eq!(2, grid.neighbors4(0, 2).count());
+	}
+
+	#[test]
+	fn grid_run_counts() {
+		let grid = Grid::new(4, 5, vec![0u8; 20]);
+		assert_eq!(4 * 2, grid.runs(4, Direction::Right).count());
+		assert_eq!(5, grid.runs(4, Direction::Down).count());
+		assert_eq!(2, grid.runs(4, Direction::DownRight).count());
+		assert_eq!(2, grid.runs(4, Direction::DownLeft).count());
+		assert_eq!(0, grid.all_runs(6).count());
+		assert_eq!(20 * 4, grid.all_runs(1).count());
+	}
+
+	#[test]
+	fn quoted_words_across_lines() {
+		let input = "\"B\",\"A, C\",\n\"\",D\n";
+		let words = load_quoted_words_from_reader(input.as_bytes(), false).unwrap();
+		assert_eq!(vec!["B", "A, C", "D"], words);
+		let words = load_quoted_words_from_reader(input.as_bytes(), true).unwrap();
+		assert_eq!(vec!["A, C", "B", "D"], words);
+	}
+
+	#[test]
+	fn load_matrix_checks_shape() {
+		let m = load_matrix_from_reader("1 2 3\n4 5 6\n".as_bytes()).unwrap();
+		assert_eq!((2, 3), (m.rows(), m.cols()));
+		let result = load_matrix_from_reader("1 2 3\n4 5\n".as_bytes());
+		assert!(matches!(result, Err(Error::RowLength { line: 2, expected: 3, found: 2 })));
+	}
+
+	#[test]
+	fn sources_open_as_readers() {
+		let path = std::env::temp_dir().join("segtrs_sources_open_as_readers.txt");
+		fs::write(&path, "5 6\n").unwrap();
+		let reader = Source::Path(path.clone()).into_reader().unwrap();
+		let from_path = load_number_grid_from_reader(reader);
+		fs::remove_file(&path).unwrap();
+		assert_eq!(vec![vec![5, 6]], from_path.unwrap());
+
+		let reader = Source::Reader(Box::new("7 8".as_bytes())).into_reader().unwrap();
+		assert_eq!(vec![vec![7, 8]], load_number_grid_from_reader(reader).unwrap());
+		assert!(Source::Path(path).into_reader().is_err());
+	}
+
+	#[cfg(feature = "gzip")]
+	#[test]
+	fn gzip_files_are_decompressed() {
+		use flate2::Compression;
+		use flate2::write::GzEncoder;
+
+		let path = std::env::temp_dir().join("segtrs_gzip_files_are_decompressed.txt.gz");
+		let mut encoder = GzEncoder::new(File::create(&path).unwrap(), Compression::default());
+		encoder.write_all(b"1 2\n3 4\n").unwrap();
+		encoder.finish().unwrap();
+
+		let grid = load_number_grid(&path);
+		fs::remove_file(&path).unwrap();
+		assert_eq!(vec![vec![1, 2], vec![3, 4]], grid.unwrap());
+	}
+
+	#[cfg(feature = "json")]
+	#[test]
+	fn json_grid_errors() {
+		let result = load_grid_json_from_reader::<u64>("[[1, 2], [3, -4]]".as_bytes());
+		assert!(matches!(result, Err(Error::Json(_))));
+		let result = load_grid_json_from_reader::<u64>("[1, 2]".as_bytes());
+		assert!(matches!(result, Err(Error::Json(_))));
+	}
+
+	#[test]
+	fn binary_grid_round_trip() {
+		for grid in [vec![], vec![vec![0, 255]], vec![vec![u64::MAX, 1], vec![2, 3]]] {
+			let mut bytes = vec![];
+			save_grid_binary_to_writer(&mut bytes, &grid).unwrap();
+			assert_eq!(grid, load_grid_binary_from_reader(&bytes[..]).unwrap());
+		}
+	}
+
+	#[test]
+	fn binary_grid_errors() {
+		let mut bytes = vec![];
+		save_grid_binary_to_writer(&mut bytes, &[vec![1, 2], vec![3, 4]]).unwrap();
+		assert!(matches!(load_grid_binary_from_reader(&bytes[..bytes.len() - 1]),
+			Err(Error::InvalidFormat(_))));
+		bytes[0] = b'X';
+		assert!(matches!(load_grid_binary_from_reader(&bytes[..]), Err(Error::InvalidFormat(_))));
+		assert!(matches!(save_grid_binary_to_writer(vec![], &[vec![1], vec![]]),
+			Err(Error::RowLength { line: 2, .. })));
+		assert!(matches!(save_grid_binary_to_writer(vec![], &[vec![], vec![]]),
+			Err(Error::InvalidFormat(_))));
+
+		// A header claiming 2^40 empty rows must not be trusted
+		let mut header = b"SGRD".to_vec();
+		header.extend((1u64 << 40).to_le_bytes());
+		header.extend(0u64.to_le_bytes());
+		header.push(1);
+		assert!(matches!(load_grid_binary_from_reader(&header[..]), Err(Error::InvalidFormat(_))));
+	}
+
+	#[test]
+	fn byte_parser_matches_reader_loader() {
+		let inputs = [
+			"", "\n", "1 2\n\n 3\t4 \r\n5", "+7 18446744073709551615\n",
+			"1\u{0B}2\n3\u{A0}4\n", "5 6\u{2003}7",
+		];
+		for input in inputs {
+			let expected = load_number_grid_from_reader(input.as_bytes()).unwrap();
+			assert_eq!(expected, parse_number_grid_bytes(input.as_bytes()).unwrap());
+		}
+
+		assert!(matches!(parse_number_grid_bytes(b"1 \xff"), Err(Error::Io(_))));
+		for input in ["1 2\n3 x4 5", "é 18446744073709551616", "-1"] {
+			let expected = load_number_grid_from_reader(input.as_bytes()).unwrap_err();
+			let found = parse_number_grid_bytes(input.as_bytes()).unwrap_err();
+			assert_eq!(expected.to_string(), found.to_string());
+		}
+	}
+
+	#[cfg(feature = "mmap")]
+	#[test]
+	fn mmap_loader_reads_file() {
+		let path = std::env::temp_dir().join("segtrs_mmap_loader_reads_file.txt");
+		fs::write(&path, "1 2\n3 4\n").unwrap();
+		let grid = load_number_grid_mmap(&path);
+		fs::remove_file(&path).unwrap();
+		assert_eq!(vec![vec![1, 2], vec![3, 4]], grid.unwrap());
+	}
+
+	/// Runs a future to completion on a single-threaded runtime.
+	#[cfg(feature = "tokio")]
+	fn block_on<F: std::future::Future>(future: F) -> F::Output {
+		tokio::runtime::Builder::new_current_thread()
+			.build()
+			.unwrap()
+			.block_on(future)
+	}
+
+	#[cfg(feature = "tokio")]
+	#[test]
+	fn async_loaders_match_sync_loaders() {
+		use tokio_stream::StreamExt;
+
+		let input = "1 2\n\n3 4 5\n";
+		let grid: Vec<Vec<u64>> = block_on(load_grid_from_async_reader(input.as_bytes())).unwrap();
+		assert_eq!(load_number_grid_from_reader(input.as_bytes()).unwrap(), grid);
+
+		let rows: Vec<Result<Vec<u8>, Error>> =
+			block_on(grid_rows_stream("1\nx\n2\n".as_bytes()).collect());
+		assert_eq!(2, rows.len());
+		assert!(matches!(rows[1], Err(Error::Parse { line: 2, .. })));
+
+		let path = std::env::temp_dir().join("segtrs_async_loaders_match_sync_loaders.txt");
+		fs::write(&path, input).unwrap();
+		let loaded = block_on(load_number_grid_async(&path));
+		fs::remove_file(&path).unwrap();
+		assert_eq!(grid, loaded.unwrap());
+	}
+
+	#[cfg(all(feature = "tokio", feature = "gzip"))]
+	#[test]
+	fn async_gzip_files_are_decompressed() {
+		use flate2::Compression;
+		use flate2::write::GzEncoder;
+		use tokio_stream::StreamExt;
+
+		let path = std::env::temp_dir().join("segtrs_async_gzip_files_are_decompressed.txt.gz");
+		let mut encoder = GzEncoder::new(File::create(&path).unwrap(), Compression::default());
+		encoder.write_all(b"1 2\n3 4\n").unwrap();
+		encoder.finish().unwrap();
+
+		let grid = block_on(load_number_grid_async(&path));
+		let rows: Vec<Result<Vec<u64>, Error>> = block_on(async {
+			number_grid_rows_async(&path).await.unwrap().collect().await
+		});
+		fs::remove_file(&path).unwrap();
+		assert_eq!(vec![vec![1, 2], vec![3, 4]], grid.unwrap());
+		let rows: Vec<Vec<u64>> = rows.into_iter().collect::<Result<_, _>>().unwrap();
+		assert_eq!(vec![vec![1, 2], vec![3, 4]], rows);
+	}
+
+	#[test]
+	fn lenient_skip_tokens_keeps_rest_of_line() {
+		let options = GridParseOptions::new().leniency(Leniency::SkipTokens);
+		let text = "1 x 3\n\n4 5 6\n";
+		let (grid, warnings) = load_grid_from_reader_with_warnings::<u64>(text.as_bytes(), &options)
+			.unwrap();
+		assert_eq!(vec![vec![1, 3], vec![4, 5, 6]], grid);
+		assert_eq!(1, warnings.len());
+		assert!(matches!(warnings[0], Error::Parse { line: 1, column: 3, .. }));
+
+		let text = "a b\n1 2\n";
+		let (grid, warnings) = load_grid_from_reader_with_warnings::<u64>(text.as_bytes(), &options)
+			.unwrap();
+		assert_eq!(vec![vec![1, 2]], grid);
+		assert_eq!(2, warnings.len());
+	}
+
+	#[test]
+	fn lenient_skips_rows_of_wrong_length() {
+		let options = GridParseOptions::new()
+			.require_rectangular(true)
+			.leniency(Leniency::SkipLines);
+		let text = "year value\n1 2\n3 4 5\n6 7\n";
+		let (grid, warnings) = load_grid_from_reader_with_warnings::<u64>(text.as_bytes(), &options)
+			.unwrap();
+		assert_eq!(vec![vec![1, 2], vec![6, 7]], grid);
+		assert!(matches!(warnings[0], Error::Parse { line: 1, .. }));
+		assert!(matches!(warnings[1], Error::RowLength { line: 3, expected: 2, found: 3 }));
+
+		let strict = GridParseOptions::new();
+		assert!(load_grid_from_reader_with_warnings::<u64>(text.as_bytes(), &strict).is_err());
+	}
+
+	#[test]
+	fn load_column_major() {
+		let options = GridParseOptions::new().column_major(true);
+		let text = "1 2\n3 4\n5 6\n";
+		let columns: Vec<Vec<u64>> = load_grid_from_reader_with(text.as_bytes(), &options)
+			.unwrap();
+		assert_eq!(vec![vec![1, 3, 5], vec![2, 4, 6]], columns);
+		assert!(matches!(
+			load_grid_from_reader_with::<u64>("1 2\n3\n".as_bytes(), &options),
+			Err(Error::RowLength { line: 2, expected: 2, found: 1 })
+		));
+	}
+
+	#[test]
+	fn grid_transpose() {
+		let grid = Grid::new(3, 2, vec![1, 2, 3, 4, 5, 6]);
+		let t = grid.transpose();
+		assert_eq!(&[1, 3, 5], t.row(0));
+		assert_eq!(&[2, 4, 6], t.row(1));
+		assert_eq!(grid, t.transpose());
+		assert_eq!(Grid::<u64>::new(0, 0, vec![]), Grid::new(0, 0, vec![]).transpose());
+	}
+
+	#[test]
+	fn fixed_width_digits() {
+		let text = "73167\n96983\n";
+		let grid: Vec<Vec<u8>> = load_fixed_width_grid_from_reader(text.as_bytes(), 1).unwrap();
+		assert_eq!(vec![vec![7, 3, 1, 6, 7], vec![9, 6, 9, 8, 3]], grid);
+
+		let path = std::env::temp_dir().join("segtrs_fixed_width_grid.txt");
+		fs::write(&path, "  1 22333\n").unwrap();
+		let grid: Vec<Vec<u64>> = load_fixed_width_grid(&path, 3).unwrap();
+		assert_eq!(vec![vec![1, 22, 333]], grid);
+		fs::remove_file(path).unwrap();
+	}
+
+	#[test]
+	fn fixed_width_reports_column() {
+		let result = load_fixed_width_grid_from_reader::<u8>("12\n3x4".as_bytes(), 1);
+		assert!(matches!(result, Err(Error::Parse { line: 2, column: 2, .. })));
+	}
+
+	#[test]
+	fn progress_reader_counts_bytes() {
+		let mut calls = 0;
+		let mut last = 0;
+		let reader = ProgressReader::new("10 20\n30 40\n50 60".as_bytes(), |bytes| {
+			assert!(bytes > last);
+			last = bytes;
+			calls += 1;
+		});
+		let grid: Vec<Vec<u64>> = load_grid_from_reader(reader).unwrap();
+		assert_eq!(3, grid.len());
+		assert_eq!(17, last);
+		assert!(calls >= 3);
+
+		let mut reader = ProgressReader::new("abc".as_bytes(), |_| {});
+		let mut s = String::new();
+		reader.read_to_string(&mut s).unwrap();
+		assert_eq!(3, reader.bytes_read());
+	}
+
+	#[test]
+	fn load_edge_list_from_file() {
+		let path = std::env::temp_dir().join("segtrs_load_edge_list.txt");
+		fs::write(&path, "0 1 16\n\n2 0 12\n").unwrap();
+		let graph = load_edge_list::<u64>(&path).unwrap();
+		assert_eq!(3, graph.vertices());
+		assert_eq!(&Edge { u: 2, v: 0, weight: 12 }, &graph.edges()[1]);
+		assert_eq!(vec![(1, &16), (2, &12)], graph.neighbors(0).collect::<Vec<_>>());
+		fs::remove_file(path).unwrap();
+
+		let result = load_edge_list_from_reader::<u64>("0 1 2\n3 4\n".as_bytes());
+		assert!(matches!(result, Err(Error::MissingField { line: 2, field: 2 })));
+
+		let text = "0 1 2\n7 18446744073709551615 1\n";
+		let result = load_edge_list_from_reader::<u64>(text.as_bytes());
+		assert!(matches!(result, Err(Error::Parse { line: 2, column: 3, .. })));
+	}
+
+	#[test]
+	fn load_adjacency_matrix_formats() {
+		let text = "- 3 -\n3 - 1\n- 1 -\n";
+		let graph = load_adjacency_matrix_from_reader::<u64>(text.as_bytes()).unwrap();
+		let expected = vec![Edge { u: 0, v: 1, weight: 3 }, Edge { u: 1, v: 2, weight: 1 }];
+		assert_eq!(expected, graph.edges());
+
+		let asymmetric = "-,3\n4,-\n";
+		assert!(matches!(
+			load_adjacency_matrix_from_reader::<u64>(asymmetric.as_bytes()),
+			Err(Error::InvalidFormat(_))
+		));
+		let ragged = "-,3\n3\n";
+		assert!(matches!(
+			load_adjacency_matrix_from_reader::<u64>(ragged.as_bytes()),
+			Err(Error::RowLength { line: 2, expected: 2, found: 1 })
+		));
+	}
+
+	#[test]
+	fn load_digit_string_from_file() {
+		let path = std::env::temp_dir().join("segtrs_load_digit_string.txt");
+		fs::write(&path, "0123\n4567 89\n\n").unwrap();
+		assert_eq!((0..10).collect::<Vec<u8>>(), load_digit_string(&path).unwrap());
+		assert_eq!(BigInt::from_int(123_456_789), load_digit_bigint(&path).unwrap());
+		fs::remove_file(path).unwrap();
+
+		let result = load_digit_string_from_reader("12\n3-4\n".as_bytes());
+		assert!(matches!(result, Err(Error::Parse { line: 2, column: 2, .. })));
+		let result = load_digit_bigint_from_reader(" \n".as_bytes());
+		assert!(matches!(result, Err(Error::InvalidFormat(_))));
+	}
+}
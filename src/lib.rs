@@ -9,6 +9,13 @@ pub mod numt;
 pub mod bigint;
 pub mod fib;
 pub mod io;
+pub mod matrix;
+pub mod rational;
+pub mod gaussian;
+pub mod modular;
+pub mod poly;
+pub mod comb;
 
 pub use bigint::BigInt;
 pub use fib::FibonacciIterator;
+pub use rational::Rational;
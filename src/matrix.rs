@@ -0,0 +1,994 @@
+use std::ops::{Add, Div, Index, IndexMut, Mul, Neg, Sub};
+
+/// A matrix with `rows * cols` entries, stored row by row.
+///
+/// Matrices of compatible sizes can be added and multiplied with `+` and
+/// `*`, either by value or by reference. Both panic if the sizes do not
+/// match.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::matrix::Matrix;
+/// let m = Matrix::new(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+/// assert_eq!((2, 3), (m.rows(), m.cols()));
+/// assert_eq!(6, m[(1, 2)]);
+///
+/// let product = &m * &m.transpose();
+/// assert_eq!(Matrix::new(vec![vec![14, 32], vec![32, 77]]), product);
+/// assert_eq!(product, &product * &Matrix::identity(2));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix<T> {
+	rows: usize,
+	cols: usize,
+	entries: Vec<T>,
+}
+
+impl<T> Matrix<T> {
+	/// Create a matrix from its rows. Panics if the rows do not all have the
+	/// same length.
+	pub fn new(rows: Vec<Vec<T>>) -> Self {
+		let cols = rows.first().map_or(0, Vec::len);
+		if rows.iter().any(|row| row.len() != cols) {
+			panic!("all rows of a matrix must have the same length");
+		}
+		Self {
+			rows: rows.len(),
+			cols,
+			entries: rows.into_iter().flatten().collect(),
+		}
+	}
+
+	pub fn rows(&self) -> usize {
+		self.rows
+	}
+
+	pub fn cols(&self) -> usize {
+		self.cols
+	}
+
+	/// The entry at `(row, col)`, if the position is within the matrix.
+	pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+		if row < self.rows && col < self.cols {
+			Some(&self.entries[row * self.cols + col])
+		} else {
+			None
+		}
+	}
+
+	/// The entries of row `row`. Panics if there is no such row.
+	pub fn row(&self, row: usize) -> &[T] {
+		if row >= self.rows {
+			panic!("row {row} is out of bounds for a matrix with {} rows", self.rows);
+		}
+		&self.entries[row * self.cols..(row + 1) * self.cols]
+	}
+
+	/// The matrix whose rows are the columns of this one.
+	pub fn transpose(&self) -> Matrix<T>
+		where T: Clone {
+
+		let entries = (0..self.cols)
+			.flat_map(|col| (0..self.rows).map(move |row| (row, col)))
+			.map(|(row, col)| self[(row, col)].clone())
+			.collect();
+		Matrix {
+			rows: self.cols,
+			cols: self.rows,
+			entries,
+		}
+	}
+
+	/// The matrix with `f` applied to every entry, for example to convert
+	/// integer entries to [`Rational`](crate::Rational) before inverting.
+	pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Matrix<U> {
+		Matrix {
+			rows: self.rows,
+			cols: self.cols,
+			entries: self.entries.iter().map(f).collect(),
+		}
+	}
+
+	fn swap_rows(&mut self, a: usize, b: usize) {
+		for col in 0..self.cols {
+			self.entries.swap(a * self.cols + col, b * self.cols + col);
+		}
+	}
+}
+
+impl<T> Matrix<T>
+	where T: Clone + PartialEq + From<u8> + Add<Output = T> + Sub<Output = T>
+		+ Mul<Output = T> + Div<Output = T> + Neg<Output = T> {
+
+	/// The determinant, computed with fraction-free Bareiss elimination.
+	/// Every division it performs is exact, so the result is exact for
+	/// signed integer entries as well as for [`Rational`](crate::Rational)
+	/// ones, as long as intermediate values do not overflow. Panics if the
+	/// matrix is not square.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::matrix::Matrix;
+	/// let m = Matrix::new(vec![vec![2, -3, 1], vec![2, 0, -1], vec![1, 4, 5]]);
+	/// assert_eq!(49i64, m.determinant());
+	/// ```
+	pub fn determinant(&self) -> T {
+		if self.rows != self.cols {
+			panic!("a {}x{} matrix has no determinant", self.rows, self.cols);
+		}
+		let n = self.rows;
+		let zero = T::from(0);
+		let mut a = self.clone();
+		let mut negate = false;
+		let mut previous = T::from(1);
+		for k in 0..n {
+			if a[(k, k)] == zero {
+				let Some(pivot) = (k + 1..n).find(|&i| a[(i, k)] != zero) else {
+					return zero;
+				};
+				a.swap_rows(k, pivot);
+				negate = !negate;
+			}
+			for i in k + 1..n {
+				for j in k + 1..n {
+					let value = a[(i, j)].clone() * a[(k, k)].clone()
+						- a[(i, k)].clone() * a[(k, j)].clone();
+					a[(i, j)] = value / previous.clone();
+				}
+			}
+			previous = a[(k, k)].clone();
+		}
+		if negate { -previous } else { previous }
+	}
+
+	/// The inverse, found by Gauss-Jordan elimination, or `None` if the
+	/// matrix is singular. The entries must support exact division, as
+	/// [`Rational`](crate::Rational) does; convert integer matrices with
+	/// [`Matrix::map`] first. Panics if the matrix is not square.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::Rational;
+	/// use segtrs::matrix::Matrix;
+	/// let m = Matrix::new(vec![vec![2i64, 1], vec![7, 4]]).map(|&x| Rational::from(x));
+	/// let inverse = m.inverse().unwrap();
+	/// assert_eq!(Rational::from(-7i64), inverse[(1, 0)]);
+	/// assert_eq!(Matrix::identity(2), &m * &inverse);
+	/// ```
+	pub fn inverse(&self) -> Option<Matrix<T>> {
+		if self.rows != self.cols {
+			panic!("a {}x{} matrix has no inverse", self.rows, self.cols);
+		}
+		let n = self.rows;
+		let zero = T::from(0);
+		let mut a = self.clone();
+		let mut inverse: Matrix<T> = Matrix::identity(n);
+		for k in 0..n {
+			let pivot = (k..n).find(|&i| a[(i, k)] != zero)?;
+			a.swap_rows(k, pivot);
+			inverse.swap_rows(k, pivot);
+
+			let p = a[(k, k)].clone();
+			for j in 0..n {
+				a[(k, j)] = a[(k, j)].clone() / p.clone();
+				inverse[(k, j)] = inverse[(k, j)].clone() / p.clone();
+			}
+			for i in (0..n).filter(|&i| i != k) {
+				let factor = a[(i, k)].clone();
+				if factor == zero {
+					continue;
+				}
+				for j in 0..n {
+					a[(i, j)] = a[(i, j)].clone() - factor.clone() * a[(k, j)].clone();
+					inverse[(i, j)] = inverse[(i, j)].clone()
+						- factor.clone() * inverse[(k, j)].clone();
+				}
+			}
+		}
+		Some(inverse)
+	}
+}
+
+impl<T: From<u8>> Matrix<T> {
+	/// The `n` by `n` matrix with ones on its diagonal and zeros elsewhere.
+	pub fn identity(n: usize) -> Self {
+		let entries = (0..n * n)
+			.map(|i| T::from(if i % (n + 1) == 0 { 1 } else { 0 }))
+			.collect();
+		Self {
+			rows: n,
+			cols: n,
+			entries,
+		}
+	}
+}
+
+impl<T> Matrix<T>
+	where T: Add<Output = T> + Mul<Output = T> + From<u8> + Clone {
+
+	/// The `k`th power of a square matrix, computed with $O(\log k)$
+	/// multiplications by repeated squaring. Panics if the matrix is not
+	/// square.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::matrix::Matrix;
+	/// let q = Matrix::<u64>::new(vec![vec![1, 1], vec![1, 0]]);
+	/// // The 90th Fibonacci number
+	/// assert_eq!(2880067194370816120, q.pow(90)[(0, 1)]);
+	/// ```
+	pub fn pow(&self, mut k: u64) -> Matrix<T> {
+		if self.rows != self.cols {
+			panic!("cannot raise a {}x{} matrix to a power", self.rows, self.cols);
+		}
+		let mut result = Matrix::identity(self.rows);
+		let mut base = self.clone();
+		while k > 0 {
+			if k & 1 == 1 {
+				result = &result * &base;
+			}
+			k >>= 1;
+			if k > 0 {
+				base = &base * &base;
+			}
+		}
+		result
+	}
+
+	/// The companion matrix of the linear recurrence
+	/// $a_n = c_1 a_{n-1} + c_2 a_{n-2} + \cdots + c_k a_{n-k}$, with the
+	/// coefficients $c_1, \ldots, c_k$ given in `coeffs`. Multiplying it by
+	/// the column of terms $a_{n-1}, \ldots, a_{n-k}$ gives the column of
+	/// terms $a_n, \ldots, a_{n-k+1}$, so its powers jump ahead in the
+	/// sequence.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::matrix::Matrix;
+	/// // Tribonacci numbers, starting 0, 0, 1: the 12th is 274
+	/// let c = Matrix::<u64>::companion(&[1, 1, 1]);
+	/// let start = Matrix::new(vec![vec![1], vec![0], vec![0]]);
+	/// assert_eq!(274, (&c.pow(10) * &start)[(0, 0)]);
+	/// ```
+	pub fn companion(coeffs: &[T]) -> Matrix<T> {
+		let k = coeffs.len();
+		let mut m = Matrix {
+			rows: k,
+			cols: k,
+			entries: (0..k * k).map(|_| T::from(0)).collect(),
+		};
+		for (j, c) in coeffs.iter().enumerate() {
+			m[(0, j)] = c.clone();
+		}
+		for i in 1..k {
+			m[(i, i - 1)] = T::from(1);
+		}
+		m
+	}
+}
+
+impl Matrix<u64> {
+	/// Like [`Matrix::pow`], but with every entry reduced modulo `m`, so
+	/// that no entry overflows however large `k` is. Panics if `m` is 0.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::matrix::Matrix;
+	/// let q = Matrix::new(vec![vec![1, 1], vec![1, 0]]);
+	/// // The last nine digits of the 10^18th Fibonacci number
+	/// assert_eq!(560546875, q.pow_mod(10u64.pow(18), 1_000_000_000)[(0, 1)]);
+	/// ```
+	pub fn pow_mod(&self, mut k: u64, m: u64) -> Matrix<u64> {
+		if m == 0 {
+			panic!("modulus must be positive");
+		}
+		if self.rows != self.cols {
+			panic!("cannot raise a {}x{} matrix to a power", self.rows, self.cols);
+		}
+		let mut result = Matrix::identity(self.rows).map(|x: &u64| x % m);
+		let mut base = self.map(|x| x % m);
+		while k > 0 {
+			if k & 1 == 1 {
+				result = result.mul_mod(&base, m);
+			}
+			k >>= 1;
+			if k > 0 {
+				base = base.mul_mod(&base, m);
+			}
+		}
+		result
+	}
+
+	/// The product of two matrices of the same square size modulo `m`.
+	fn mul_mod(&self, other: &Matrix<u64>, m: u64) -> Matrix<u64> {
+		let n = self.rows;
+		let mut entries = Vec::with_capacity(n * n);
+		for row in 0..n {
+			for col in 0..n {
+				let entry = (0..n)
+					.map(|k| self[(row, k)] as u128 * other[(k, col)] as u128 % m as u128)
+					.fold(0, |acc, x| (acc + x) % m as u128);
+				entries.push(entry as u64);
+			}
+		}
+		Matrix {
+			rows: n,
+			cols: n,
+			entries,
+		}
+	}
+}
+
+/// The field operations needed to solve linear systems, for entries such as
+/// [`Rational`](crate::Rational), where elimination is exact, and `f64`,
+/// where it is approximate.
+pub trait Field: Clone + PartialOrd + From<u8> + Add<Output = Self> + Sub<Output = Self>
+	+ Mul<Output = Self> + Div<Output = Self> + Neg<Output = Self> {
+
+	/// Whether the value is close enough to 0 to be treated as 0 during
+	/// elimination, where `scale` is the largest magnitude among the values
+	/// it was computed from.
+	fn is_negligible(&self, scale: &Self) -> bool;
+}
+
+impl Field for crate::Rational {
+	fn is_negligible(&self, _scale: &Self) -> bool {
+		self.numer() == 0
+	}
+}
+
+impl Field for f64 {
+	/// Rounding errors grow with the size of the values, so the tolerance is
+	/// relative: a scaled system gives the same answer as the original.
+	fn is_negligible(&self, scale: &Self) -> bool {
+		self.abs() <= scale * 1e-12
+	}
+}
+
+/// The outcome of solving a linear system with [`Matrix::solve`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Solution<T> {
+	/// The system has exactly one solution.
+	Unique(Vec<T>),
+	/// The system has infinitely many solutions. One of them is given, with
+	/// every free variable set to 0, along with the rank of the matrix.
+	Underdetermined {
+		particular: Vec<T>,
+		rank: usize,
+	},
+	/// The system has no solution. The rank is that of the matrix, which is
+	/// less than that of the matrix with the right-hand side appended.
+	Inconsistent {
+		rank: usize,
+	},
+}
+
+impl<T: Field> Matrix<T> {
+	/// The number of linearly independent rows.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::matrix::Matrix;
+	/// let m = Matrix::new(vec![vec![1.0, 2.0, 3.0], vec![2.0, 4.0, 6.0]]);
+	/// assert_eq!(1, m.rank());
+	/// ```
+	pub fn rank(&self) -> usize {
+		self.clone().row_reduce(self.cols).len()
+	}
+
+	/// Solve the system whose coefficients are the entries of this matrix
+	/// and whose right-hand side is `rhs`, by Gaussian elimination with
+	/// partial pivoting. Panics if `rhs` does not have one value per row.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::Rational;
+	/// use segtrs::matrix::{Matrix, Solution};
+	/// let r = |n: i64| Rational::from(n);
+	/// // x + y = 3, x - y = 1
+	/// let m = Matrix::new(vec![vec![r(1), r(1)], vec![r(1), r(-1)]]);
+	/// assert_eq!(Solution::Unique(vec![r(2), r(1)]), m.solve(&[r(3), r(1)]));
+	///
+	/// let m = Matrix::new(vec![vec![r(1), r(1)], vec![r(2), r(2)]]);
+	/// assert_eq!(Solution::Inconsistent { rank: 1 }, m.solve(&[r(3), r(5)]));
+	/// ```
+	pub fn solve(&self, rhs: &[T]) -> Solution<T> {
+		if rhs.len() != self.rows {
+			panic!("expected {} right-hand side values, found {}", self.rows, rhs.len());
+		}
+		let mut augmented = Matrix {
+			rows: self.rows,
+			cols: self.cols + 1,
+			entries: Vec::with_capacity(self.rows * (self.cols + 1)),
+		};
+		for (row, b) in rhs.iter().enumerate() {
+			augmented.entries.extend_from_slice(self.row(row));
+			augmented.entries.push(b.clone());
+		}
+
+		let scale = augmented.max_abs(self.cols + 1);
+		let pivots = augmented.row_reduce(self.cols);
+		let rank = pivots.len();
+		if (rank..self.rows).any(|row| !augmented[(row, self.cols)].is_negligible(&scale)) {
+			return Solution::Inconsistent { rank };
+		}
+		let mut solution = vec![T::from(0); self.cols];
+		for (row, &col) in pivots.iter().enumerate() {
+			solution[col] = augmented[(row, self.cols)].clone();
+		}
+		if rank == self.cols {
+			Solution::Unique(solution)
+		} else {
+			Solution::Underdetermined { particular: solution, rank }
+		}
+	}
+
+	/// Brings the first `n` columns to reduced row echelon form, applying
+	/// the same row operations to the remaining columns. Returns the pivot
+	/// column of each nonzero row, which come first.
+	fn row_reduce(&mut self, n: usize) -> Vec<usize> {
+		let scale = self.max_abs(n);
+		let mut pivots = vec![];
+		for col in 0..n {
+			let top = pivots.len();
+			if top == self.rows {
+				break;
+			}
+			// Partial pivoting: the largest value keeps rounding errors small
+			let mut best = top;
+			for row in top + 1..self.rows {
+				if abs(&self[(row, col)]) > abs(&self[(best, col)]) {
+					best = row;
+				}
+			}
+			if self[(best, col)].is_negligible(&scale) {
+				continue;
+			}
+			self.swap_rows(top, best);
+
+			let p = self[(top, col)].clone();
+			for j in col..self.cols {
+				self[(top, j)] = self[(top, j)].clone() / p.clone();
+			}
+			for row in (0..self.rows).filter(|&row| row != top) {
+				let factor = self[(row, col)].clone();
+				// Rows already normalized by their pivot are not on the same
+				// scale, so only an exact 0 is skipped
+				if factor == T::from(0) {
+					continue;
+				}
+				for j in col..self.cols {
+					self[(row, j)] = self[(row, j)].clone()
+						- factor.clone() * self[(top, j)].clone();
+				}
+			}
+			pivots.push(col);
+		}
+		pivots
+	}
+
+	/// The largest magnitude among the entries in the first `n` columns.
+	fn max_abs(&self, n: usize) -> T {
+		let mut largest = T::from(0);
+		for row in 0..self.rows {
+			for x in &self.row(row)[..n] {
+				let x = abs(x);
+				if x > largest {
+					largest = x;
+				}
+			}
+		}
+		largest
+	}
+}
+
+fn abs<T: Field>(x: &T) -> T {
+	if *x < T::from(0) { -x.clone() } else { x.clone() }
+}
+
+/// A matrix over GF(2), the field with elements 0 and 1 where addition is
+/// XOR. Each row is packed 64 entries to a word, so row operations act on
+/// 64 entries at once.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::matrix::BitMatrix;
+/// let mut m = BitMatrix::new(2, 3);
+/// m.set(0, 0, true);
+/// m.set(1, 2, true);
+/// assert!(m.get(1, 2));
+/// m.flip(1, 2);
+/// assert!(!m.get(1, 2));
+/// assert_eq!(1, m.rank());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitMatrix {
+	rows: usize,
+	cols: usize,
+	// The number of words holding each row
+	stride: usize,
+	words: Vec<u64>,
+}
+
+impl BitMatrix {
+	/// Create a `rows` by `cols` matrix of zeros.
+	pub fn new(rows: usize, cols: usize) -> Self {
+		let stride = cols.div_ceil(64);
+		Self {
+			rows,
+			cols,
+			stride,
+			words: vec![0; rows * stride],
+		}
+	}
+
+	/// Create a matrix from its rows. Panics if the rows do not all have the
+	/// same length.
+	pub fn from_rows(rows: &[Vec<bool>]) -> Self {
+		let cols = rows.first().map_or(0, Vec::len);
+		if rows.iter().any(|row| row.len() != cols) {
+			panic!("all rows of a matrix must have the same length");
+		}
+		let mut m = BitMatrix::new(rows.len(), cols);
+		for (i, row) in rows.iter().enumerate() {
+			for (j, &bit) in row.iter().enumerate() {
+				m.set(i, j, bit);
+			}
+		}
+		m
+	}
+
+	pub fn rows(&self) -> usize {
+		self.rows
+	}
+
+	pub fn cols(&self) -> usize {
+		self.cols
+	}
+
+	/// The entry at `(row, col)`. Panics if the position is outside the
+	/// matrix.
+	pub fn get(&self, row: usize, col: usize) -> bool {
+		let (word, bit) = self.position(row, col);
+		self.words[word] >> bit & 1 == 1
+	}
+
+	/// Panics if the position is outside the matrix.
+	pub fn set(&mut self, row: usize, col: usize, value: bool) {
+		let (word, bit) = self.position(row, col);
+		if value {
+			self.words[word] |= 1 << bit;
+		} else {
+			self.words[word] &= !(1 << bit);
+		}
+	}
+
+	/// Toggle the entry at `(row, col)`. Panics if the position is outside
+	/// the matrix.
+	pub fn flip(&mut self, row: usize, col: usize) {
+		let (word, bit) = self.position(row, col);
+		self.words[word] ^= 1 << bit;
+	}
+
+	fn position(&self, row: usize, col: usize) -> (usize, usize) {
+		if row >= self.rows || col >= self.cols {
+			panic!("({row}, {col}) is out of bounds for a {}x{} matrix", self.rows, self.cols);
+		}
+		(row * self.stride + col / 64, col % 64)
+	}
+
+	/// Add row `src` to row `dst`.
+	fn xor_row(&mut self, dst: usize, src: usize) {
+		for w in 0..self.stride {
+			self.words[dst * self.stride + w] ^= self.words[src * self.stride + w];
+		}
+	}
+
+	fn swap_rows(&mut self, a: usize, b: usize) {
+		for w in 0..self.stride {
+			self.words.swap(a * self.stride + w, b * self.stride + w);
+		}
+	}
+
+	/// Bring the matrix to reduced row echelon form. Returns the pivot
+	/// column of each nonzero row, which come first.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::matrix::BitMatrix;
+	/// let mut m = BitMatrix::from_rows(&[vec![true, true], vec![true, false]]);
+	/// assert_eq!(vec![0, 1], m.row_reduce());
+	/// assert_eq!(BitMatrix::from_rows(&[vec![true, false], vec![false, true]]), m);
+	/// ```
+	pub fn row_reduce(&mut self) -> Vec<usize> {
+		self.reduce(self.cols)
+	}
+
+	/// Like [`BitMatrix::row_reduce`], but only looks for pivots in the
+	/// first `n` columns.
+	fn reduce(&mut self, n: usize) -> Vec<usize> {
+		let mut pivots = vec![];
+		for col in 0..n {
+			let top = pivots.len();
+			let Some(pivot) = (top..self.rows).find(|&row| self.get(row, col)) else {
+				continue;
+			};
+			self.swap_rows(top, pivot);
+			for row in 0..self.rows {
+				if row != top && self.get(row, col) {
+					self.xor_row(row, top);
+				}
+			}
+			pivots.push(col);
+		}
+		pivots
+	}
+
+	/// The number of linearly independent rows.
+	pub fn rank(&self) -> usize {
+		self.clone().row_reduce().len()
+	}
+
+	/// A basis of the vectors `x` with $Mx = 0$, one for each column
+	/// without a pivot.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::matrix::BitMatrix;
+	/// // x0 + x1 + x2 = 0
+	/// let m = BitMatrix::from_rows(&[vec![true, true, true]]);
+	/// assert_eq!(vec![vec![true, true, false], vec![true, false, true]], m.nullspace());
+	/// ```
+	pub fn nullspace(&self) -> Vec<Vec<bool>> {
+		let mut m = self.clone();
+		let pivots = m.row_reduce();
+		let mut is_pivot = vec![false; self.cols];
+		for &col in &pivots {
+			is_pivot[col] = true;
+		}
+		(0..self.cols)
+			.filter(|&free| !is_pivot[free])
+			.map(|free| {
+				let mut x = vec![false; self.cols];
+				x[free] = true;
+				for (row, &col) in pivots.iter().enumerate() {
+					x[col] = m.get(row, free);
+				}
+				x
+			})
+			.collect()
+	}
+
+	/// A vector `x` with $Mx = b$, with every free variable set to 0, or
+	/// `None` if there is none. Panics if `rhs` does not have one value per
+	/// row.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::matrix::BitMatrix;
+	/// // x0 + x1 = 1, x1 = 1
+	/// let m = BitMatrix::from_rows(&[vec![true, true], vec![false, true]]);
+	/// assert_eq!(Some(vec![false, true]), m.solve(&[true, true]));
+	/// ```
+	pub fn solve(&self, rhs: &[bool]) -> Option<Vec<bool>> {
+		if rhs.len() != self.rows {
+			panic!("expected {} right-hand side values, found {}", self.rows, rhs.len());
+		}
+		let mut augmented = BitMatrix::new(self.rows, self.cols + 1);
+		for (row, &b) in rhs.iter().enumerate() {
+			for col in 0..self.cols {
+				augmented.set(row, col, self.get(row, col));
+			}
+			augmented.set(row, self.cols, b);
+		}
+
+		let pivots = augmented.reduce(self.cols);
+		if (pivots.len()..self.rows).any(|row| augmented.get(row, self.cols)) {
+			return None;
+		}
+		let mut x = vec![false; self.cols];
+		for (row, &col) in pivots.iter().enumerate() {
+			x[col] = augmented.get(row, self.cols);
+		}
+		Some(x)
+	}
+}
+
+impl<T> Add for &Matrix<T>
+	where T: Add<Output = T> + Clone {
+
+	type Output = Matrix<T>;
+
+	fn add(self, other: &Matrix<T>) -> Matrix<T> {
+		if (self.rows, self.cols) != (other.rows, other.cols) {
+			panic!("cannot add a {}x{} matrix to a {}x{} matrix",
+				other.rows, other.cols, self.rows, self.cols);
+		}
+		let entries = self.entries.iter().zip(&other.entries)
+			.map(|(a, b)| a.clone() + b.clone())
+			.collect();
+		Matrix {
+			rows: self.rows,
+			cols: self.cols,
+			entries,
+		}
+	}
+}
+
+impl<T> Add for Matrix<T>
+	where T: Add<Output = T> + Clone {
+
+	type Output = Matrix<T>;
+
+	fn add(self, other: Matrix<T>) -> Matrix<T> {
+		&self + &other
+	}
+}
+
+impl<T> Mul for &Matrix<T>
+	where T: Add<Output = T> + Mul<Output = T> + From<u8> + Clone {
+
+	type Output = Matrix<T>;
+
+	fn mul(self, other: &Matrix<T>) -> Matrix<T> {
+		if self.cols != other.rows {
+			panic!("cannot multiply a {}x{} matrix by a {}x{} matrix",
+				self.rows, self.cols, other.rows, other.cols);
+		}
+		let mut entries = Vec::with_capacity(self.rows * other.cols);
+		for row in 0..self.rows {
+			for col in 0..other.cols {
+				let entry = (0..self.cols)
+					.map(|k| self[(row, k)].clone() * other[(k, col)].clone())
+					.fold(T::from(0), |acc, x| acc + x);
+				entries.push(entry);
+			}
+		}
+		Matrix {
+			rows: self.rows,
+			cols: other.cols,
+			entries,
+		}
+	}
+}
+
+impl<T> Mul for Matrix<T>
+	where T: Add<Output = T> + Mul<Output = T> + From<u8> + Clone {
+
+	type Output = Matrix<T>;
+
+	fn mul(self, other: Matrix<T>) -> Matrix<T> {
+		&self * &other
+	}
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+	type Output = T;
+
+	fn index(&self, (row, col): (usize, usize)) -> &T {
+		if row >= self.rows || col >= self.cols {
+			panic!("({row}, {col}) is out of bounds for a {}x{} matrix", self.rows, self.cols);
+		}
+		&self.entries[row * self.cols + col]
+	}
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+	fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+		if row >= self.rows || col >= self.cols {
+			panic!("({row}, {col}) is out of bounds for a {}x{} matrix", self.rows, self.cols);
+		}
+		&mut self.entries[row * self.cols + col]
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Rational;
+
+	#[test]
+	fn matrix_rows_and_entries() {
+		let mut m = Matrix::new(vec![vec![1, 2], vec![3, 4], vec![5, 6]]);
+		m[(2, 0)] = 50;
+		assert_eq!(&[50, 6], m.row(2));
+		assert_eq!(None, m.get(0, 2));
+		assert_eq!((0, 0), (Matrix::<u8>::new(vec![]).rows(), Matrix::<u8>::new(vec![]).cols()));
+	}
+
+	#[test]
+	#[should_panic(expected = "same length")]
+	fn matrix_from_ragged_rows() {
+		Matrix::new(vec![vec![1, 2], vec![3]]);
+	}
+
+	#[test]
+	fn matrix_add_and_transpose() {
+		let a = Matrix::new(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+		let b = Matrix::new(vec![vec![10, 20, 30], vec![40, 50, 60]]);
+		assert_eq!(Matrix::new(vec![vec![11, 22, 33], vec![44, 55, 66]]), a.clone() + b);
+		assert_eq!(Matrix::new(vec![vec![1, 4], vec![2, 5], vec![3, 6]]), a.transpose());
+		assert_eq!(a, a.transpose().transpose());
+	}
+
+	#[test]
+	fn matrix_multiply() {
+		// Powers of the Fibonacci matrix hold consecutive Fibonacci numbers
+		let q = Matrix::<u64>::new(vec![vec![1, 1], vec![1, 0]]);
+		let mut m = Matrix::identity(2);
+		for _ in 0..10 {
+			m = &m * &q;
+		}
+		assert_eq!(Matrix::new(vec![vec![89, 55], vec![55, 34]]), m);
+
+		let row = Matrix::new(vec![vec![1, 2, 3]]);
+		let col = row.transpose();
+		assert_eq!(Matrix::new(vec![vec![14]]), &row * &col);
+		assert_eq!((3, 3), ((&col * &row).rows(), (&col * &row).cols()));
+	}
+
+	#[test]
+	#[should_panic(expected = "cannot multiply a 1x3 matrix by a 1x3 matrix")]
+	fn matrix_multiply_mismatched() {
+		let row = Matrix::new(vec![vec![1, 2, 3]]);
+		let _ = &row * &row;
+	}
+
+	#[test]
+	fn matrix_determinant() {
+		let m = Matrix::<i64>::new(vec![vec![0, 2, 1], vec![3, 1, 4], vec![5, 9, 2]]);
+		assert_eq!(50, m.determinant());
+		let singular = Matrix::<i64>::new(vec![vec![1, 2], vec![2, 4]]);
+		assert_eq!(0, singular.determinant());
+		assert_eq!(1, Matrix::<i64>::new(vec![]).determinant());
+
+		let r = m.map(|&x| Rational::from(x));
+		assert_eq!(Rational::from(50i64), r.determinant());
+	}
+
+	#[test]
+	fn matrix_inverse() {
+		let m = Matrix::<i64>::new(vec![vec![0, 2, 1], vec![3, 1, 4], vec![5, 9, 2]])
+			.map(|&x| Rational::from(x));
+		let inverse = m.inverse().unwrap();
+		assert_eq!(Rational::new(-17, 25), inverse[(0, 0)]);
+		assert_eq!(Matrix::identity(3), &inverse * &m);
+
+		let singular = Matrix::<i64>::new(vec![vec![1, 2], vec![2, 4]]).map(|&x| Rational::from(x));
+		assert_eq!(None, singular.inverse());
+	}
+
+	#[test]
+	fn matrix_pow() {
+		let q = Matrix::<u64>::new(vec![vec![1, 1], vec![1, 0]]);
+		assert_eq!(Matrix::identity(2), q.pow(0));
+		assert_eq!(q, q.pow(1));
+		assert_eq!(Matrix::new(vec![vec![89, 55], vec![55, 34]]), q.pow(10));
+
+		// Padovan numbers: P(n) = P(n - 2) + P(n - 3)
+		let c = Matrix::<u64>::companion(&[0, 1, 1]);
+		let start = Matrix::new(vec![vec![1], vec![1], vec![1]]);
+		assert_eq!(Matrix::new(vec![vec![0, 1, 1], vec![1, 0, 0], vec![0, 1, 0]]), c);
+		assert_eq!(151, (&c.pow(17) * &start)[(0, 0)]);
+	}
+
+	#[test]
+	fn matrix_pow_mod() {
+		let q = Matrix::new(vec![vec![1, 1], vec![1, 0]]);
+		for k in 0..90 {
+			assert_eq!(q.pow(k).map(|x| x % 1000), q.pow_mod(k, 1000));
+		}
+		assert_eq!(Matrix::new(vec![vec![0]]), Matrix::new(vec![vec![5]]).pow_mod(0, 1));
+		// Fibonacci numbers modulo 10 repeat with period 60
+		assert_eq!(Matrix::identity(2), q.pow_mod(u64::MAX / 60 * 60, 10));
+	}
+
+	#[test]
+	fn matrix_solve_exact() {
+		let r = |n: i64| Rational::from(n);
+		let m = Matrix::new(vec![
+			vec![r(2), r(1), r(-1)],
+			vec![r(-3), r(-1), r(2)],
+			vec![r(-2), r(1), r(2)],
+		]);
+		assert_eq!(3, m.rank());
+		assert_eq!(Solution::Unique(vec![r(2), r(3), r(-1)]), m.solve(&[r(8), r(-11), r(-3)]));
+
+		// x + 2y = 4, 2x + 4y = 8
+		let m = Matrix::new(vec![vec![r(1), r(2)], vec![r(2), r(4)]]);
+		assert_eq!(
+			Solution::Underdetermined { particular: vec![r(4), r(0)], rank: 1 },
+			m.solve(&[r(4), r(8)])
+		);
+	}
+
+	#[test]
+	fn matrix_solve_approximate() {
+		// Without pivoting, the tiny leading coefficient ruins the result
+		let m = Matrix::new(vec![vec![1e-20, 1.0], vec![1.0, 1.0]]);
+		let Solution::Unique(x) = m.solve(&[1.0, 2.0]) else {
+			panic!("expected a unique solution");
+		};
+		assert!((x[0] - 1.0).abs() < 1e-12 && (x[1] - 1.0).abs() < 1e-12);
+
+		let m = Matrix::new(vec![vec![1.0, 1.0], vec![1.0, 1.0], vec![0.0, 1.0]]);
+		assert_eq!(Solution::Inconsistent { rank: 2 }, m.solve(&[1.0, 2.0, 0.0]));
+	}
+
+	#[test]
+	fn matrix_solve_scaled() {
+		assert_eq!(Solution::Unique(vec![1.0]), Matrix::new(vec![vec![1e-10]]).solve(&[1e-10]));
+
+		// Scaling a system uniformly does not change its solution or rank
+		let rows = vec![vec![2.0, 1.0, -1.0], vec![-3.0, -1.0, 2.0], vec![-2.0, 1.0, 2.0]];
+		let rhs = [8.0, -11.0, -3.0];
+		for scale in [1e-15, 1e-6, 1.0, 1e6, 1e15] {
+			let m = Matrix::new(rows.clone()).map(|x| x * scale);
+			let Solution::Unique(x) = m.solve(&rhs.map(|b| b * scale)) else {
+				panic!("expected a unique solution at scale {scale}");
+			};
+			for (x, expected) in x.iter().zip([2.0, 3.0, -1.0]) {
+				assert!((x - expected).abs() < 1e-9);
+			}
+			assert_eq!(1, Matrix::new(vec![vec![scale, 2.0 * scale]; 2]).rank());
+		}
+	}
+
+	#[test]
+	fn bit_matrix_wide_rows() {
+		// Rows span several words
+		let mut m = BitMatrix::new(3, 130);
+		m.set(0, 0, true);
+		m.set(0, 129, true);
+		m.set(1, 129, true);
+		m.set(2, 64, true);
+		assert_eq!(3, m.rank());
+		assert_eq!(vec![0, 64, 129], m.clone().row_reduce());
+		assert_eq!(127, m.nullspace().len());
+		for x in m.nullspace() {
+			for row in 0..m.rows() {
+				let dot = (0..m.cols()).filter(|&col| m.get(row, col) && x[col]).count();
+				assert_eq!(0, dot % 2);
+			}
+		}
+	}
+
+	#[test]
+	fn bit_matrix_lights_out() {
+		// On a 2x2 board, each light toggles itself and its neighbors
+		let n = 2;
+		let mut m = BitMatrix::new(n * n, n * n);
+		for r in 0..n {
+			for c in 0..n {
+				for (dr, dc) in [(0, 0), (0, 1), (1, 0), (0, -1), (-1, 0)] {
+					let neighbor = (r.checked_add_signed(dr), c.checked_add_signed(dc));
+					let (Some(rr), Some(cc)) = neighbor else {
+						continue;
+					};
+					if rr < n && cc < n {
+						m.set(rr * n + cc, r * n + c, true);
+					}
+				}
+			}
+		}
+		// Turning off a single light takes the presses of its row, column and itself
+		let x = m.solve(&[true, false, false, false]).unwrap();
+		assert_eq!(vec![true, true, true, false], x);
+		assert!(m.nullspace().is_empty());
+
+		let singular = BitMatrix::from_rows(&[vec![true, true], vec![true, true]]);
+		assert_eq!(None, singular.solve(&[true, false]));
+	}
+}
@@ -0,0 +1,405 @@
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::numt;
+
+/// An integer modulo `M`, always kept in the range $[0, M)$. Values with
+/// different moduli have different types, so they cannot be mixed up.
+///
+/// Division multiplies by the modular inverse, and panics if the divisor is
+/// not coprime to `M`. A modulus of 0 is rejected at compile time.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::modular::ModInt;
+/// type Mint = ModInt<1_000_000_007>;
+/// let a = Mint::from(1_000_000_000);
+/// let b = Mint::from(10);
+/// assert_eq!(Mint::from(999_999_937), a * b);
+/// assert_eq!(a, a * b / b);
+/// assert_eq!(Mint::from(1), Mint::from(2).pow(1_000_000_006));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ModInt<const M: u64> {
+	value: u64,
+}
+
+impl<const M: u64> ModInt<M> {
+	pub const MODULUS: u64 = M;
+
+	/// Create the residue of `value` modulo `M`.
+	pub fn new(value: u64) -> Self {
+		const { assert!(M > 0, "the modulus must be positive") };
+		Self {
+			value: value % M,
+		}
+	}
+
+	/// The representative in $[0, M)$.
+	pub fn value(&self) -> u64 {
+		self.value
+	}
+
+	/// Raise `self` to the power `exp` by repeated squaring.
+	pub fn pow(&self, exp: u64) -> Self {
+		Self::new(numt::pow_mod(self.value, exp, M))
+	}
+
+	/// The multiplicative inverse, or `None` if `self` is not coprime to
+	/// `M`.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::modular::ModInt;
+	/// assert_eq!(Some(ModInt::<10>::from(7)), ModInt::<10>::from(3).inv());
+	/// assert_eq!(None, ModInt::<10>::from(4).inv());
+	/// ```
+	pub fn inv(&self) -> Option<Self> {
+		inverse_mod(self.value, M).map(Self::new)
+	}
+}
+
+/// The inverse of `a` modulo `m`, found with the extended Euclidean
+/// algorithm, or `None` if `a` and `m` are not coprime.
+fn inverse_mod(a: u64, m: u64) -> Option<u64> {
+	let (mut r0, mut r1) = (m as i128, (a % m) as i128);
+	let (mut x0, mut x1) = (0i128, 1i128);
+	while r1 != 0 {
+		let q = r0 / r1;
+		(r0, r1) = (r1, r0 - q * r1);
+		(x0, x1) = (x1, x0 - q * x1);
+	}
+	// Only 1 is invertible modulo 1, where it equals 0
+	(r0 == 1).then(|| x0.rem_euclid(m as i128) as u64)
+}
+
+impl<const M: u64> From<u64> for ModInt<M> {
+	fn from(value: u64) -> Self {
+		Self::new(value)
+	}
+}
+
+impl<const M: u64> Add for ModInt<M> {
+	type Output = Self;
+
+	fn add(self, other: Self) -> Self {
+		Self::new(((self.value as u128 + other.value as u128) % M as u128) as u64)
+	}
+}
+
+impl<const M: u64> Sub for ModInt<M> {
+	type Output = Self;
+
+	fn sub(self, other: Self) -> Self {
+		if self.value >= other.value {
+			Self::new(self.value - other.value)
+		} else {
+			Self::new(M - (other.value - self.value))
+		}
+	}
+}
+
+impl<const M: u64> Mul for ModInt<M> {
+	type Output = Self;
+
+	fn mul(self, other: Self) -> Self {
+		Self::new(numt::mul_mod(self.value, other.value, M))
+	}
+}
+
+impl<const M: u64> Div for ModInt<M> {
+	type Output = Self;
+
+	/// Panics if `other` is not invertible modulo `M`.
+	fn div(self, other: Self) -> Self {
+		let Some(inverse) = other.inv() else {
+			panic!("{} is not invertible modulo {M}", other.value);
+		};
+		Self::new(numt::mul_mod(self.value, inverse.value, M))
+	}
+}
+
+impl<const M: u64> Neg for ModInt<M> {
+	type Output = Self;
+
+	fn neg(self) -> Self {
+		Self::new(0) - self
+	}
+}
+
+impl<const M: u64> AddAssign for ModInt<M> {
+	fn add_assign(&mut self, other: Self) {
+		*self = *self + other;
+	}
+}
+
+impl<const M: u64> SubAssign for ModInt<M> {
+	fn sub_assign(&mut self, other: Self) {
+		*self = *self - other;
+	}
+}
+
+impl<const M: u64> MulAssign for ModInt<M> {
+	fn mul_assign(&mut self, other: Self) {
+		*self = *self * other;
+	}
+}
+
+impl<const M: u64> DivAssign for ModInt<M> {
+	fn div_assign(&mut self, other: Self) {
+		*self = *self / other;
+	}
+}
+
+impl<const M: u64> Sum for ModInt<M> {
+	fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+		iter.fold(Self::new(0), Add::add)
+	}
+}
+
+impl<const M: u64> Product for ModInt<M> {
+	fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+		iter.fold(Self::new(1), Mul::mul)
+	}
+}
+
+impl<const M: u64> fmt::Display for ModInt<M> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", self.value)
+	}
+}
+
+/// Modular arithmetic with a modulus only known at runtime. Methods take and
+/// return plain `u64` values, reducing their arguments first; use
+/// [`Modulus::residue`] to get values that support the arithmetic
+/// operators instead.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::modular::Modulus;
+/// let m = Modulus::new(13);
+/// assert_eq!(2, m.add(7, 8));
+/// assert_eq!(3, m.mul(4, 4));
+/// assert_eq!(1, m.pow(2, 12));
+/// assert_eq!(Some(7), m.inv(2));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Modulus {
+	m: u64,
+}
+
+impl Modulus {
+	/// Panics if `m` is 0.
+	pub fn new(m: u64) -> Self {
+		if m == 0 {
+			panic!("the modulus must be positive");
+		}
+		Self { m }
+	}
+
+	pub fn value(&self) -> u64 {
+		self.m
+	}
+
+	pub fn reduce(&self, a: u64) -> u64 {
+		a % self.m
+	}
+
+	pub fn add(&self, a: u64, b: u64) -> u64 {
+		((a as u128 + b as u128) % self.m as u128) as u64
+	}
+
+	pub fn sub(&self, a: u64, b: u64) -> u64 {
+		let (a, b) = (a % self.m, b % self.m);
+		if a >= b { a - b } else { self.m - (b - a) }
+	}
+
+	pub fn mul(&self, a: u64, b: u64) -> u64 {
+		numt::mul_mod(a, b, self.m)
+	}
+
+	pub fn pow(&self, base: u64, exp: u64) -> u64 {
+		numt::pow_mod(base, exp, self.m)
+	}
+
+	/// The multiplicative inverse of `a`, or `None` if `a` is not coprime
+	/// to the modulus.
+	pub fn inv(&self, a: u64) -> Option<u64> {
+		inverse_mod(a, self.m)
+	}
+
+	/// The residue of `a`, which remembers this modulus.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::modular::Modulus;
+	/// let m = Modulus::new(1_000_000_009);
+	/// let x = m.residue(123_456_789);
+	/// assert_eq!(1, (x * x.inv().unwrap()).value());
+	/// assert_eq!(m.residue(0), x - x);
+	/// ```
+	pub fn residue(&self, a: u64) -> Residue {
+		Residue {
+			value: a % self.m,
+			modulus: *self,
+		}
+	}
+}
+
+/// An integer modulo a [`Modulus`] chosen at runtime, created with
+/// [`Modulus::residue`]. Combining residues with different moduli panics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Residue {
+	value: u64,
+	modulus: Modulus,
+}
+
+impl Residue {
+	/// The representative in $[0, m)$.
+	pub fn value(&self) -> u64 {
+		self.value
+	}
+
+	pub fn modulus(&self) -> Modulus {
+		self.modulus
+	}
+
+	pub fn pow(&self, exp: u64) -> Self {
+		self.modulus.residue(self.modulus.pow(self.value, exp))
+	}
+
+	/// The multiplicative inverse, or `None` if `self` is not coprime to
+	/// the modulus.
+	pub fn inv(&self) -> Option<Self> {
+		self.modulus.inv(self.value).map(|v| self.modulus.residue(v))
+	}
+
+	/// The modulus shared by `self` and `other`. Panics if they differ.
+	fn common(&self, other: &Residue) -> Modulus {
+		if self.modulus != other.modulus {
+			panic!("cannot combine residues modulo {} and {}", self.modulus.m, other.modulus.m);
+		}
+		self.modulus
+	}
+}
+
+impl Add for Residue {
+	type Output = Residue;
+
+	fn add(self, other: Residue) -> Residue {
+		let m = self.common(&other);
+		m.residue(m.add(self.value, other.value))
+	}
+}
+
+impl Sub for Residue {
+	type Output = Residue;
+
+	fn sub(self, other: Residue) -> Residue {
+		let m = self.common(&other);
+		m.residue(m.sub(self.value, other.value))
+	}
+}
+
+impl Mul for Residue {
+	type Output = Residue;
+
+	fn mul(self, other: Residue) -> Residue {
+		let m = self.common(&other);
+		m.residue(m.mul(self.value, other.value))
+	}
+}
+
+impl Div for Residue {
+	type Output = Residue;
+
+	/// Panics if `other` is not invertible modulo the common modulus.
+	fn div(self, other: Residue) -> Residue {
+		let m = self.common(&other);
+		let Some(inverse) = m.inv(other.value) else {
+			panic!("{} is not invertible modulo {}", other.value, m.m);
+		};
+		m.residue(m.mul(self.value, inverse))
+	}
+}
+
+impl Neg for Residue {
+	type Output = Residue;
+
+	fn neg(self) -> Residue {
+		self.modulus.residue(self.modulus.sub(0, self.value))
+	}
+}
+
+impl fmt::Display for Residue {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", self.value)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn modint_arithmetic() {
+		type M7 = ModInt<7>;
+		assert_eq!(M7::from(1), M7::from(5) + M7::from(3));
+		assert_eq!(M7::from(5), M7::from(3) - M7::from(5));
+		assert_eq!(M7::from(6), -M7::from(1));
+		assert_eq!(M7::from(4), M7::from(3) / M7::from(6));
+
+		let mut x = M7::from(2);
+		x *= M7::from(4);
+		x += M7::from(10);
+		assert_eq!(4, x.value());
+
+		// 10! modulo a large prime
+		type Mint = ModInt<1_000_000_007>;
+		let factorial: Mint = (1..=10).map(Mint::from).product();
+		assert_eq!(3_628_800, factorial.value());
+		assert_eq!(Mint::from(55), (1..=10).map(Mint::from).sum());
+	}
+
+	#[test]
+	fn modint_near_u64_max() {
+		type Big = ModInt<{ u64::MAX }>;
+		let a = Big::from(u64::MAX - 1);
+		assert_eq!(Big::from(u64::MAX - 2), a + a);
+		assert_eq!(Big::from(1), a * a);
+		assert_eq!(a, a.inv().unwrap());
+	}
+
+	#[test]
+	#[should_panic(expected = "not invertible")]
+	fn modint_divide_by_non_unit() {
+		let _ = ModInt::<12>::from(5) / ModInt::<12>::from(4);
+	}
+
+	#[test]
+	fn modulus_matches_modint() {
+		type M = ModInt<1_000_000_007>;
+		let m = Modulus::new(M::MODULUS);
+		for (a, b) in [(0, 5), (999_999_999, 123_456_789), (u64::MAX, u64::MAX - 3)] {
+			assert_eq!((M::from(a) + M::from(b)).value(), m.add(a, b));
+			assert_eq!((M::from(a) - M::from(b)).value(), m.sub(a, b));
+			assert_eq!((M::from(a) * M::from(b)).value(), m.mul(a, b));
+			assert_eq!(M::from(a).pow(b).value(), m.pow(a, b));
+			assert_eq!(M::from(a).inv().map(|x| x.value()), m.inv(a));
+		}
+		let x = m.residue(10);
+		assert_eq!(m.residue(M::MODULUS - 10), -x);
+		assert_eq!(x, x * x / x);
+	}
+
+	#[test]
+	#[should_panic(expected = "cannot combine residues modulo 5 and 7")]
+	fn residues_with_different_moduli() {
+		let _ = Modulus::new(5).residue(1) + Modulus::new(7).residue(1);
+	}
+}
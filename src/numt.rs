@@ -1,6 +1,30 @@
-use std::error::Error;
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
 
+use crate::BigInt;
+
+/// The ways in which the fallible functions of this module can fail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+	/// The result does not fit in the return type.
+	Overflow,
+	/// An argument is outside the domain of the function. The message
+	/// describes the offending argument.
+	InvalidArgument(&'static str),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Error::Overflow => write!(f, "overflow"),
+			Error::InvalidArgument(message) => {
+				write!(f, "invalid argument: {message}")
+			},
+		}
+	}
+}
+
+impl std::error::Error for Error {}
 
 /// Determines whether `n` is prime.
 ///
@@ -11,6 +35,7 @@ use std::collections::BTreeSet;
 /// assert!(segtrs::numt::is_prime(2));
 /// assert!(!segtrs::numt::is_prime(4));
 /// ```
+#[allow(clippy::manual_is_multiple_of)]
 pub fn is_prime(n: u64) -> bool {
 	if n == 2 {
 		return true;
@@ -42,6 +67,7 @@ pub fn is_prime(n: u64) -> bool {
 /// assert_eq!(6, numt::gcd(18, 48));
 /// ```
 
+#[allow(clippy::empty_line_after_doc_comments, clippy::needless_return)]
 pub fn gcd(mut p: u64, mut q: u64) -> u64 {
 	while q != 0 {
 		let r = p % q;
@@ -52,7 +78,8 @@ pub fn gcd(mut p: u64, mut q: u64) -> u64 {
 	return p;
 }
 
-/// Computes the least common multiple of `p` and `q`.
+/// Computes the least common multiple of `p` and `q`. On overflow, returns
+/// [`Error::Overflow`].
 ///
 /// # Examples
 ///
@@ -62,13 +89,13 @@ pub fn gcd(mut p: u64, mut q: u64) -> u64 {
 /// assert_eq!(0, numt::lcm(0, 12).unwrap());
 /// assert_eq!(0, numt::lcm(12, 0).unwrap());
 /// assert_eq!(36, numt::lcm(12, 18).unwrap());
-/// assert!(numt::lcm(u64::MAX, u64::MAX - 1).is_err());
+/// assert_eq!(Err(numt::Error::Overflow), numt::lcm(u64::MAX, u64::MAX - 1));
 /// ```
-pub fn lcm(p: u64, q: u64) -> Result<u64, Box<dyn Error>> {
+pub fn lcm(p: u64, q: u64) -> Result<u64, Error> {
 	let result = if p == 0 && q == 0 {
 		0
 	} else {
-		let pq = p.checked_mul(q).ok_or_else(|| "overflow")?;
+		let pq = p.checked_mul(q).ok_or(Error::Overflow)?;
 		pq / gcd(p, q)
 	};
 
@@ -76,7 +103,7 @@ pub fn lcm(p: u64, q: u64) -> Result<u64, Box<dyn Error>> {
 }
 
 /// Computes the $n$th triangular number according to the formula
-/// $t_n = \frac{n(n+1)}{2}. On overflow, returns an error.
+/// $t_n = \frac{n(n+1)}{2}. On overflow, returns [`Error::Overflow`].
 ///
 /// # Examples
 ///
@@ -84,12 +111,15 @@ pub fn lcm(p: u64, q: u64) -> Result<u64, Box<dyn Error>> {
 /// let t_5 = segtrs::numt::triangular_number(5).unwrap();
 /// assert_eq!(15, t_5);
 ///
-/// assert!(segtrs::numt::triangular_number(u64::MAX).is_err());
+/// use segtrs::numt::Error;
+/// assert_eq!(Err(Error::Overflow), segtrs::numt::triangular_number(u64::MAX));
 /// ```
-pub fn triangular_number(n: u64) -> Result<u64, Box<dyn Error>> {
-	let n_plus_1 = n.checked_add(1).ok_or_else(|| "overflow")?;
-	let t_n = n_plus_1.checked_mul(n).ok_or_else(|| "overflow")? / 2;
-	Ok(t_n)
+pub fn triangular_number(n: u64) -> Result<u64, Error> {
+	let n_plus_1 = n.checked_add(1).ok_or(Error::Overflow)?;
+	// Halve whichever factor is even first, so that only a result that does
+	// not fit overflows
+	let (a, b) = if n.is_multiple_of(2) { (n / 2, n_plus_1) } else { (n, n_plus_1 / 2) };
+	a.checked_mul(b).ok_or(Error::Overflow)
 }
 
 /// Produces all the factors of `n`. Uses the convention that $0$ is the only
@@ -125,7 +155,8 @@ pub fn factors_of(n: u64) -> BTreeSet<u64> {
 }
 
 /// Determines whether `s` is a palindrome. Ignores non-alphaumeric characters,
-/// and ignores case sensitivity.
+/// and ignores case sensitivity. Strings with no alphanumeric characters,
+/// including the empty string, are palindromes.
 ///
 /// # Examples
 ///
@@ -133,111 +164,3463 @@ pub fn factors_of(n: u64) -> BTreeSet<u64> {
 /// assert!(segtrs::numt::is_palindrome("Taco Cat"));
 /// assert!(segtrs::numt::is_palindrome("1234321"));
 /// assert!(!segtrs::numt::is_palindrome("kyoto"));
+/// assert!(segtrs::numt::is_palindrome(""));
 /// ```
 pub fn is_palindrome(s: &str) -> bool {
-	let chars: Vec<char>= s.to_lowercase().chars().collect();
-	let mut left_idx = 0;
-	let mut right_idx = chars.len() - 1;
-	while left_idx < right_idx {
-		let left_char = &chars[left_idx];
-		if !left_char.is_alphanumeric() {
-			left_idx += 1;
-			continue;
+	is_palindrome_with(s, true)
+}
+
+/// Determines whether `s` is a palindrome, ignoring case sensitivity. If
+/// `ignore_non_alphanumeric` is `true`, characters other than letters and
+/// digits are skipped; otherwise every character, including spaces and
+/// punctuation, must be mirrored.
+///
+/// ASCII input is checked in place. Other input is compared character by
+/// character after converting each one to lowercase, which may expand it to
+/// several characters; no allocation is made in either case. Canonically
+/// equivalent strings in different Unicode normal forms are not considered
+/// equal.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// assert!(numt::is_palindrome_with("Taco Cat", true));
+/// assert!(!numt::is_palindrome_with("Taco Cat", false));
+/// assert!(numt::is_palindrome_with("Ésé", false));
+/// ```
+pub fn is_palindrome_with(s: &str, ignore_non_alphanumeric: bool) -> bool {
+	if s.is_ascii() {
+		let bytes = s.as_bytes();
+		let keep = |b: &u8| !ignore_non_alphanumeric || b.is_ascii_alphanumeric();
+		let forward = bytes.iter().filter(|b| keep(b));
+		let backward = bytes.iter().rev().filter(|b| keep(b));
+		return forward
+			.zip(backward)
+			.all(|(a, b)| a.eq_ignore_ascii_case(b));
+	}
+
+	let keep = |c: &char| !ignore_non_alphanumeric || c.is_alphanumeric();
+	let forward = s.chars().filter(keep).flat_map(char::to_lowercase);
+	let backward = s.chars().rev().filter(keep).flat_map(|c| c.to_lowercase().rev());
+	forward.eq(backward)
+}
+
+/// Determines whether `n` is a perfect square.
+///
+/// # Examples
+///
+/// ```
+/// assert!(segtrs::numt::is_perfect_square(0));
+/// assert!(segtrs::numt::is_perfect_square(49));
+/// assert!(!segtrs::numt::is_perfect_square(50));
+/// ```
+pub fn is_perfect_square(n: u64) -> bool {
+	let root = n.isqrt();
+	root * root == n
+}
+
+/// Determines whether `n` is a perfect cube.
+///
+/// # Examples
+///
+/// ```
+/// assert!(segtrs::numt::is_perfect_cube(27));
+/// assert!(!segtrs::numt::is_perfect_cube(28));
+/// ```
+pub fn is_perfect_cube(n: u64) -> bool {
+	let (_, exact) = nth_root(n, 3);
+	exact
+}
+
+/// Computes the $k$th root of `n`, rounded down. The second value is `true`
+/// if and only if the root is exact, meaning $r^k = n$. Panics if `k` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// assert_eq!((3, true), numt::nth_root(81, 4));
+/// assert_eq!((4, false), numt::nth_root(80, 3));
+/// ```
+pub fn nth_root(n: u64, k: u32) -> (u64, bool) {
+	if k == 0 {
+		panic!("the degree of the root must be positive");
+	}
+	if k == 1 || n < 2 {
+		return (n, true);
+	}
+
+	// Start from a floating-point estimate and correct it, since the estimate
+	// may be off by one in either direction for large inputs.
+	let mut root = (n as f64).powf(1.0 / k as f64) as u64;
+	while root.checked_pow(k).is_none_or(|p| p > n) {
+		root -= 1;
+	}
+	while (root + 1).checked_pow(k).is_some_and(|p| p <= n) {
+		root += 1;
+	}
+
+	(root, root.pow(k) == n)
+}
+
+/// Computes the continued fraction expansion of $\sqrt{n}$. Returns the
+/// integer part $a_0$ together with the coefficients of the repeating block,
+/// so that $\sqrt{n} = [a_0; \overline{a_1, \ldots, a_r}]$. The repeating
+/// block is empty when `n` is a perfect square.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// assert_eq!((1, vec![2]), numt::sqrt_continued_fraction(2));
+/// assert_eq!((4, vec![1, 3, 1, 8]), numt::sqrt_continued_fraction(23));
+/// assert_eq!((3, vec![]), numt::sqrt_continued_fraction(9));
+/// ```
+pub fn sqrt_continued_fraction(n: u64) -> (u64, Vec<u64>) {
+	let a0 = n.isqrt();
+	let mut period = vec![];
+	if a0 * a0 == n {
+		return (a0, period);
+	}
+
+	// Each step represents the remainder as (sqrt(n) + m) / d. These values
+	// stay below 2 * sqrt(n), so the arithmetic cannot overflow.
+	let (mut m, mut d, mut a) = (0, 1, a0);
+	while a != 2 * a0 {
+		m = d * a - m;
+		d = (n - m * m) / d;
+		a = (a0 + m) / d;
+		period.push(a);
+	}
+
+	(a0, period)
+}
+
+/// An iterator over the convergents of a continued fraction. Given the
+/// coefficients $a_0, a_1, a_2, \ldots$, it produces the successive
+/// approximations $h_n / k_n$ as `(numerator, denominator)` pairs, using the
+/// recurrences $h_n = a_n h_{n-1} + h_{n-2}$ and $k_n = a_n k_{n-1} + k_{n-2}$.
+/// The iterator ends when the coefficients run out.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::BigInt;
+/// use segtrs::numt::Convergents;
+///
+/// // sqrt(2) = [1; 2, 2, 2, ...] has convergents 1/1, 3/2, 7/5, 17/12, ...
+/// let coefficients = std::iter::once(1).chain(std::iter::repeat(2));
+/// let (h, k) = Convergents::new(coefficients).nth(3).unwrap();
+/// assert_eq!(BigInt::from_int(17), h);
+/// assert_eq!(BigInt::from_int(12), k);
+/// ```
+pub struct Convergents<I> {
+	coefficients: I,
+	// The two previous numerators and denominators, most recent first.
+	h: (BigInt, BigInt),
+	k: (BigInt, BigInt),
+}
+
+impl<I: Iterator<Item = u64>> Convergents<I> {
+	pub fn new(coefficients: I) -> Self {
+		Self {
+			coefficients,
+			h: (BigInt::from_int(1), BigInt::from_int(0)),
+			k: (BigInt::from_int(0), BigInt::from_int(1)),
 		}
+	}
+}
 
-		let right_char = &chars[right_idx];
-		if !right_char.is_alphanumeric() {
-			right_idx -= 1;
-			continue;
+impl<I: Iterator<Item = u64>> Iterator for Convergents<I> {
+	type Item = (BigInt, BigInt);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let a = BigInt::from_int(self.coefficients.next()?);
+
+		let h = a.multiply(&self.h.0).add(&self.h.1);
+		let k = a.multiply(&self.k.0).add(&self.k.1);
+		self.h.1 = std::mem::replace(&mut self.h.0, h.clone());
+		self.k.1 = std::mem::replace(&mut self.k.0, k.clone());
+
+		Some((h, k))
+	}
+}
+
+/// An iterator over the Farey sequence of order $n$: the reduced fractions
+/// between 0 and 1 whose denominators do not exceed $n$, in ascending order.
+/// Each fraction is produced as a `(numerator, denominator)` pair. The
+/// sequence of order 0 is empty.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt::FareySequence;
+/// let terms: Vec<(u64, u64)> = FareySequence::new(4).collect();
+/// assert_eq!(
+///     vec![(0, 1), (1, 4), (1, 3), (1, 2), (2, 3), (3, 4), (1, 1)],
+///     terms,
+/// );
+/// ```
+pub struct FareySequence {
+	order: u64,
+	// The next fraction to be returned, if any, and the one after it.
+	current: Option<(u64, u64)>,
+	following: (u64, u64),
+}
+
+impl FareySequence {
+	pub fn new(order: u64) -> Self {
+		Self {
+			order,
+			current: if order > 0 { Some((0, 1)) } else { None },
+			following: (1, order),
 		}
+	}
+}
 
-		if left_char != right_char {
-			return false;
+impl Iterator for FareySequence {
+	type Item = (u64, u64);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let (a, b) = self.current?;
+		if (a, b) == (1, 1) {
+			self.current = None;
+			return Some((a, b));
 		}
-		left_idx += 1;
-		right_idx -= 1;
+
+		// If a/b and c/d are neighbors, the next term is the mediant-derived
+		// fraction (kc - a) / (kd - b) with the largest k allowed by the order.
+		let (c, d) = self.following;
+		let k = (self.order + b) / d;
+		self.current = Some((c, d));
+		self.following = (k * c - a, k * d - b);
+
+		Some((a, b))
 	}
+}
 
-	true
+/// Computes the number of terms in the Farey sequence of order `n`, which
+/// is $1 + \sum_{k=1}^{n} \varphi(k)$. Returns 0 when `n` is 0.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(7, segtrs::numt::farey_length(4));
+/// assert_eq!(33, segtrs::numt::farey_length(10));
+/// ```
+pub fn farey_length(n: u64) -> u64 {
+	if n == 0 {
+		return 0;
+	}
+
+	// Sieve Euler's totient for every k up to n.
+	let n = n as usize;
+	let mut phi: Vec<u64> = (0..=n as u64).collect();
+	for p in 2..=n {
+		if phi[p] == p as u64 {
+			for multiple in (p..=n).step_by(p) {
+				phi[multiple] -= phi[multiple] / p as u64;
+			}
+		}
+	}
+
+	1 + phi[1..].iter().sum::<u64>()
 }
 
-#[cfg(test)]
-mod tests {
-	use super::*;
+/// Finds the fraction closest to `x_num / x_den` whose denominator does not
+/// exceed `max_den`, returned in lowest terms as `(numerator, denominator)`.
+/// It descends the Stern–Brocot tree by way of the continued fraction of the
+/// target, so the best candidate is either the last convergent that fits or
+/// the largest semiconvergent after it. Ties go to the convergent. Panics if
+/// `x_den` or `max_den` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// let pi = (3_141_592_653, 1_000_000_000);
+/// assert_eq!((355, 113), numt::best_rational_approximation(pi.0, pi.1, 1000));
+/// assert_eq!((2, 5), numt::best_rational_approximation(3, 7, 6));
+/// assert_eq!((3, 7), numt::best_rational_approximation(6, 14, 100));
+/// ```
+pub fn best_rational_approximation(x_num: u64, x_den: u64, max_den: u64)
+	-> (u64, u64) {
 
-	#[test]
-	fn below_2_are_not_prime() {
-		assert!(!is_prime(0));
-		assert!(!is_prime(1));
+	if x_den == 0 || max_den == 0 {
+		panic!("denominators must be positive");
+	}
+	let g = gcd(x_num, x_den);
+	let (x_num, x_den) = (x_num / g, x_den / g);
+	if x_den <= max_den {
+		return (x_num, x_den);
 	}
 
-	#[test]
-	fn primes_below_20() {
-		assert!(is_prime(2));
-		assert!(is_prime(3));
-		assert!(is_prime(5));
-		assert!(is_prime(7));
-		assert!(is_prime(11));
-		assert!(is_prime(13));
-		assert!(is_prime(17));
-		assert!(is_prime(19));
+	// Wide integers prevent the convergent numerators from overflowing when
+	// the target is a large number.
+	let max_den = max_den as u128;
+	let (mut p0, mut q0, mut p1, mut q1) = (0u128, 1u128, 1u128, 0u128);
+	let (mut n, mut d) = (x_num as u128, x_den as u128);
+	loop {
+		let a = n / d;
+		let q2 = q0 + a * q1;
+		if q2 > max_den {
+			break;
+		}
+		(p0, q0, p1, q1) = (p1, q1, p0 + a * p1, q2);
+		(n, d) = (d, n - a * d);
 	}
 
-	#[test]
-	fn palindrome_one_casing() {
-		assert!(is_palindrome("tacocat"));
+	// The semiconvergent with the largest admissible denominator is the only
+	// other candidate. Compare distances without division.
+	let k = (max_den - q0) / q1;
+	let (p2, q2) = (p0 + k * p1, q0 + k * q1);
+	if 2 * d * q2 <= x_den as u128 {
+		(p1 as u64, q1 as u64)
+	} else {
+		(p2 as u64, q2 as u64)
 	}
+}
 
-	#[test]
-	fn palindrome_case_insensitive() {
-		assert!(is_palindrome("TacoCat"));
+/// Produces the generalized pentagonal numbers $k(3k-1)/2$ for
+/// $k = 1, -1, 2, -2, \ldots$ that do not exceed `n`, each paired with the
+/// sign of its term in Euler's pentagonal number theorem.
+fn generalized_pentagonals(n: u64) -> impl Iterator<Item = (u64, bool)> {
+	(1..)
+		.flat_map(|k: u64| {
+			let is_positive = k % 2 == 1;
+			[(k * (3 * k - 1) / 2, is_positive), (k * (3 * k + 1) / 2, is_positive)]
+		})
+		.take_while(move |&(g, _)| g <= n)
+}
+
+/// Computes the number of partitions of `n`, meaning the number of ways to
+/// write `n` as a sum of positive integers without regard to order. Uses
+/// Euler's pentagonal number recurrence,
+/// $p(n) = \sum_{k \neq 0} (-1)^{k+1} p(n - k(3k-1)/2)$.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::BigInt;
+/// assert_eq!(BigInt::from_int(1), segtrs::numt::partition_count(0));
+/// assert_eq!(BigInt::from_int(42), segtrs::numt::partition_count(10));
+/// ```
+pub fn partition_count(n: u64) -> BigInt {
+	let mut p = vec![BigInt::from_int(1)];
+	for m in 1..=n {
+		// Accumulate the positive and negative terms separately, since BigInt
+		// is unsigned. The total is always non-negative.
+		let mut positive = BigInt::from_int(0);
+		let mut negative = BigInt::from_int(0);
+		for (g, is_positive) in generalized_pentagonals(m) {
+			let term = &p[(m - g) as usize];
+			if is_positive {
+				positive = positive.add(term);
+			} else {
+				negative = negative.add(term);
+			}
+		}
+		p.push(positive.subtract(&negative));
 	}
 
-	#[test]
-	fn palindrome_with_spaces() {
-		assert!(is_palindrome("taco cat"));
+	p.pop().unwrap()
+}
+
+/// Computes the number of partitions of `n` modulo `m`, using the same
+/// recurrence as [`partition_count`]. Panics if `m` is 0.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(2, segtrs::numt::partition_count_mod(10, 5));
+/// ```
+pub fn partition_count_mod(n: u64, m: u64) -> u64 {
+	if m == 0 {
+		panic!("the modulus must be positive");
 	}
 
-	#[test]
-	fn palindrome_numbers() {
-		assert!(is_palindrome("1234321"));
+	let mut p = vec![1 % m];
+	for k in 1..=n {
+		let mut total = 0;
+		for (g, is_positive) in generalized_pentagonals(k) {
+			let term = p[(k - g) as usize];
+			total = if is_positive {
+				add_mod(total, term, m)
+			} else {
+				sub_mod(total, term, m)
+			};
+		}
+		p.push(total);
 	}
 
-	#[test]
-	fn triangular_small() {
-		assert_eq!(0, triangular_number(0).unwrap());
-		assert_eq!(1, triangular_number(1).unwrap());
-		assert_eq!(3, triangular_number(2).unwrap());
-		assert_eq!(6, triangular_number(3).unwrap());
-		assert_eq!(10, triangular_number(4).unwrap());
-		assert_eq!(15, triangular_number(5).unwrap());
+	p[n as usize]
+}
+
+/// Computes the binomial coefficient $\binom{n}{k}$, the number of ways to
+/// choose `k` items out of `n`. Uses the multiplicative formula, cancelling
+/// common factors at every step so that intermediate values never exceed the
+/// result. Returns `None` on overflow.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// assert_eq!(Some(10), numt::binomial(5, 2));
+/// assert_eq!(Some(0), numt::binomial(2, 5));
+/// assert_eq!(Some(137_846_528_820), numt::binomial(40, 20));
+/// assert_eq!(None, numt::binomial(100, 50));
+/// ```
+pub fn binomial(n: u64, k: u64) -> Option<u64> {
+	if k > n {
+		return Some(0);
 	}
+	let k = k.min(n - k);
 
-	#[test]
-	fn triangular_overflow() -> Result<(), String> {
-		let result = triangular_number(u64::MAX);
-		if result.is_err() {
-			Ok(())
-		} else {
-			Err(String::from("large triangular numbers should overflow"))
-		}
+	// C(n, i) = C(n, i - 1) * (n - k + i) / i, where the division is exact.
+	let mut result: u64 = 1;
+	for i in 1..=k {
+		let mut numerator = n - k + i;
+		let g = gcd(result, i);
+		result /= g;
+		numerator /= i / g;
+		result = result.checked_mul(numerator)?;
 	}
 
-	#[test]
-	fn factors_of_zero_and_one() {
-		assert_eq!(BTreeSet::from([0]), factors_of(0));
-		assert_eq!(BTreeSet::from([1]), factors_of(1));
+	Some(result)
+}
+
+/// Computes the binomial coefficient $\binom{n}{k}$ as a BigInt, for values
+/// that do not fit in a `u64`.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::BigInt;
+/// let expected = BigInt::from_int(137_846_528_820);
+/// assert_eq!(expected, segtrs::numt::binomial_big(40, 20));
+/// ```
+pub fn binomial_big(n: u64, k: u64) -> BigInt {
+	if k > n {
+		return BigInt::from_int(0);
 	}
+	let k = k.min(n - k);
 
-	#[test]
-	fn factors_non_square() {
-		let result = factors_of(28);
-		assert_eq!(BTreeSet::from([1, 2, 4, 7, 14, 28]), result);
+	let mut result = BigInt::from_int(1);
+	for i in 1..=k {
+		let numerator = BigInt::from_int(n - k + i);
+		(result, _) = result.multiply(&numerator).divide_int(i);
 	}
-	
-	#[test]
-	fn factors_of_a_square() {
-		let result = factors_of(64);
-		assert_eq!(BTreeSet::from([1, 2, 4, 8, 16, 32, 64]), result);
+
+	result
+}
+
+/// Computes $n!$, returning `None` on overflow. The largest factorial that
+/// fits in a `u64` is $20!$.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// assert_eq!(Some(1), numt::factorial_checked(0));
+/// assert_eq!(Some(120), numt::factorial_checked(5));
+/// assert_eq!(None, numt::factorial_checked(21));
+/// ```
+pub fn factorial_checked(n: u64) -> Option<u64> {
+	(1..=n).try_fold(1u64, |acc, k| acc.checked_mul(k))
+}
+
+/// Computes $n!$ as a BigInt.
+///
+/// # Examples
+///
+/// ```
+/// // The sum of the digits of 100! is 648
+/// let digit_sum: u64 = segtrs::numt::factorial_big(100)
+///     .digits()
+///     .iter()
+///     .map(|&d| d as u64)
+///     .sum();
+/// assert_eq!(648, digit_sum);
+/// ```
+pub fn factorial_big(n: u64) -> BigInt {
+	(1..=n).fold(BigInt::from_int(1), |acc, k| acc.multiply(&BigInt::from_int(k)))
+}
+
+/// Builds row `n` of the table of unsigned Stirling numbers of the first
+/// kind, meaning the values $c(n, k)$ for $0 \le k \le n$. The number
+/// $c(n, k)$ counts the permutations of `n` elements with exactly `k` cycles.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::BigInt;
+/// let row: Vec<BigInt> = [0, 6, 11, 6, 1]
+///     .into_iter()
+///     .map(BigInt::from_int)
+///     .collect();
+/// assert_eq!(row, segtrs::numt::stirling_first_row(4));
+/// ```
+pub fn stirling_first_row(n: u64) -> Vec<BigInt> {
+	// c(m + 1, k) = m * c(m, k) + c(m, k - 1)
+	stirling_row(n, |m, _| m)
+}
+
+/// Builds row `n` of the table of Stirling numbers of the second kind,
+/// meaning the values $S(n, k)$ for $0 \le k \le n$. The number $S(n, k)$
+/// counts the ways to partition `n` elements into exactly `k` non-empty sets.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::BigInt;
+/// let row: Vec<BigInt> = [0, 1, 7, 6, 1]
+///     .into_iter()
+///     .map(BigInt::from_int)
+///     .collect();
+/// assert_eq!(row, segtrs::numt::stirling_second_row(4));
+/// ```
+pub fn stirling_second_row(n: u64) -> Vec<BigInt> {
+	// S(m + 1, k) = k * S(m, k) + S(m, k - 1)
+	stirling_row(n, |_, k| k)
+}
+
+/// Computes the unsigned Stirling number of the first kind $c(n, k)$. See
+/// [`stirling_first_row`] to compute every value for a fixed `n` at once.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::BigInt;
+/// assert_eq!(BigInt::from_int(11), segtrs::numt::stirling_first(4, 2));
+/// ```
+pub fn stirling_first(n: u64, k: u64) -> BigInt {
+	if k > n {
+		return BigInt::from_int(0);
+	}
+	stirling_first_row(n).swap_remove(k as usize)
+}
+
+/// Computes the Stirling number of the second kind $S(n, k)$. See
+/// [`stirling_second_row`] to compute every value for a fixed `n` at once.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::BigInt;
+/// assert_eq!(BigInt::from_int(7), segtrs::numt::stirling_second(4, 2));
+/// ```
+pub fn stirling_second(n: u64, k: u64) -> BigInt {
+	if k > n {
+		return BigInt::from_int(0);
+	}
+	stirling_second_row(n).swap_remove(k as usize)
+}
+
+/// Builds row `n` of a triangle satisfying
+/// $T(m + 1, k) = w(m, k) T(m, k) + T(m, k - 1)$ with $T(0, 0) = 1$.
+fn stirling_row(n: u64, weight: impl Fn(u64, u64) -> u64) -> Vec<BigInt> {
+	let mut row = vec![BigInt::from_int(1)];
+	for m in 0..n {
+		let mut next = vec![BigInt::from_int(0)];
+		for k in 1..=(m + 1) {
+			let mut value = row[(k - 1) as usize].clone();
+			if let Some(t) = row.get(k as usize) {
+				value = value.add(&t.multiply(&BigInt::from_int(weight(m, k))));
+			}
+			next.push(value);
+		}
+		row = next;
+	}
+
+	row
+}
+
+/// Computes the multinomial coefficient
+/// $\binom{k_1 + \cdots + k_m}{k_1, \ldots, k_m} = (\sum k_i)! / \prod k_i!$,
+/// the number of distinct arrangements of a multiset in which the $i$th
+/// element appears $k_i$ times. It is evaluated as a product of binomial
+/// coefficients to keep the intermediate values small.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::BigInt;
+/// // The letters of "MISSISSIPPI" can be arranged in 34650 ways
+/// let counts = [1, 4, 4, 2];
+/// assert_eq!(BigInt::from_int(34650), segtrs::numt::multinomial(&counts));
+/// ```
+pub fn multinomial(counts: &[u64]) -> BigInt {
+	let mut total = 0;
+	let mut result = BigInt::from_int(1);
+	for &k in counts {
+		total += k;
+		result = result.multiply(&binomial_big(total, k));
+	}
+
+	result
+}
+
+/// An iterator over primitive Pythagorean triples $(a, b, c)$, meaning
+/// $a^2 + b^2 = c^2$ with $\gcd(a, b) = 1$. Each triple is produced exactly
+/// once with $a < b < c$. The triples are generated with Euclid's formula,
+/// $(m^2 - n^2, 2mn, m^2 + n^2)$ for coprime $m > n$ of opposite parity,
+/// ordered by $m$ and then by $n$.
+///
+/// Without a bound the iterator is endless. Use [`max_perimeter`] or
+/// [`max_hypotenuse`] to restrict it to finitely many triples.
+///
+/// [`max_perimeter`]: PythagoreanTriples::max_perimeter
+/// [`max_hypotenuse`]: PythagoreanTriples::max_hypotenuse
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt::PythagoreanTriples;
+/// let triples: Vec<(u64, u64, u64)> = PythagoreanTriples::new()
+///     .max_hypotenuse(30)
+///     .collect();
+/// assert_eq!(
+///     vec![(3, 4, 5), (5, 12, 13), (8, 15, 17), (7, 24, 25), (20, 21, 29)],
+///     triples,
+/// );
+/// ```
+pub struct PythagoreanTriples {
+	m: u64,
+	// The value of n used for the most recent triple, or 0 if m has changed.
+	n: u64,
+	max_perimeter: Option<u64>,
+	max_hypotenuse: Option<u64>,
+}
+
+impl PythagoreanTriples {
+	pub fn new() -> Self {
+		Self {
+			m: 2,
+			n: 0,
+			max_perimeter: None,
+			max_hypotenuse: None,
+		}
+	}
+
+	/// Only produce triples whose perimeter $a + b + c$ is at most `limit`.
+	pub fn max_perimeter(mut self, limit: u64) -> Self {
+		self.max_perimeter = Some(limit);
+		self
+	}
+
+	/// Only produce triples whose hypotenuse $c$ is at most `limit`.
+	pub fn max_hypotenuse(mut self, limit: u64) -> Self {
+		self.max_hypotenuse = Some(limit);
+		self
+	}
+
+	fn within_limits(&self, m: u64, n: u64) -> bool {
+		// The perimeter is (m^2 - n^2) + 2mn + (m^2 + n^2) = 2m(m + n)
+		self.max_perimeter.is_none_or(|limit| 2 * m * (m + n) <= limit)
+			&& self.max_hypotenuse.is_none_or(|limit| m * m + n * n <= limit)
+	}
+}
+
+impl Default for PythagoreanTriples {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Iterator for PythagoreanTriples {
+	type Item = (u64, u64, u64);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			let (m, n) = if self.n == 0 {
+				// The smallest triple for a given m grows with m, so once it
+				// is out of bounds, all remaining triples are too.
+				let n = if self.m.is_multiple_of(2) { 1 } else { 2 };
+				if !self.within_limits(self.m, n) {
+					return None;
+				}
+				(self.m, n)
+			} else {
+				(self.m, self.n + 2)
+			};
+
+			if n >= m || !self.within_limits(m, n) {
+				self.m += 1;
+				self.n = 0;
+				continue;
+			}
+			self.n = n;
+
+			if gcd(m, n) == 1 {
+				let (a, b, c) = (m * m - n * n, 2 * m * n, m * m + n * n);
+				return Some((a.min(b), a.max(b), c));
+			}
+		}
+	}
+}
+
+/// Computes the Jacobi symbol $\left(\frac{a}{n}\right)$ for an odd positive
+/// `n`, using quadratic reciprocity. The result is 0 if `a` and `n` share a
+/// factor, and otherwise 1 or -1. Panics if `n` is even.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// assert_eq!(-1, numt::jacobi_symbol(1001, 9907));
+/// assert_eq!(1, numt::jacobi_symbol(2, 15));
+/// assert_eq!(0, numt::jacobi_symbol(6, 15));
+/// ```
+pub fn jacobi_symbol(a: u64, n: u64) -> i32 {
+	if n.is_multiple_of(2) {
+		panic!("the Jacobi symbol is only defined for odd n");
+	}
+
+	let mut a = a % n;
+	let mut n = n;
+	let mut result = 1;
+	while a != 0 {
+		// (2/n) is -1 exactly when n is 3 or 5 mod 8
+		while a.is_multiple_of(2) {
+			a /= 2;
+			if n % 8 == 3 || n % 8 == 5 {
+				result = -result;
+			}
+		}
+		// Reciprocity flips the sign when both are 3 mod 4
+		std::mem::swap(&mut a, &mut n);
+		if a % 4 == 3 && n % 4 == 3 {
+			result = -result;
+		}
+		a %= n;
+	}
+
+	if n == 1 { result } else { 0 }
+}
+
+/// Computes the Legendre symbol $\left(\frac{a}{p}\right)$ for an odd prime
+/// `p`: 0 if `p` divides `a`, 1 if `a` is a nonzero quadratic residue modulo
+/// `p`, and -1 otherwise. The primality of `p` is not verified; for
+/// composite `p` the result is the Jacobi symbol. Panics if `p` is even.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// assert_eq!(1, numt::legendre_symbol(2, 7));
+/// assert_eq!(-1, numt::legendre_symbol(3, 7));
+/// assert_eq!(0, numt::legendre_symbol(14, 7));
+/// ```
+pub fn legendre_symbol(a: u64, p: u64) -> i32 {
+	jacobi_symbol(a, p)
+}
+
+/// Computes the prime factorization of `n` by trial division, mapping each
+/// prime factor to its exponent. The factorization of 1 is empty. Panics if
+/// `n` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::BTreeMap;
+///
+/// let factorization = segtrs::numt::prime_factorization(360);
+/// assert_eq!(BTreeMap::from([(2, 3), (3, 2), (5, 1)]), factorization);
+/// ```
+pub fn prime_factorization(n: u64) -> BTreeMap<u64, u32> {
+	if n == 0 {
+		panic!("0 has no prime factorization");
+	}
+
+	let mut factorization = BTreeMap::new();
+	let mut n = n;
+	let mut p = 2;
+	while p <= n / p {
+		while n.is_multiple_of(p) {
+			*factorization.entry(p).or_insert(0) += 1;
+			n /= p;
+		}
+		p += if p == 2 { 1 } else { 2 };
+	}
+	if n > 1 {
+		*factorization.entry(n).or_insert(0) += 1;
+	}
+
+	factorization
+}
+
+/// Computes the Carmichael function $\lambda(n)$, the smallest positive $m$
+/// such that $a^m \equiv 1 \pmod{n}$ for every $a$ coprime to `n`. It always
+/// divides Euler's totient $\varphi(n)$. Panics if `n` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// assert_eq!(1, numt::carmichael_lambda(1));
+/// assert_eq!(2, numt::carmichael_lambda(8));
+/// assert_eq!(4, numt::carmichael_lambda(15));
+/// assert_eq!(2, numt::carmichael_lambda(24));
+/// ```
+pub fn carmichael_lambda(n: u64) -> u64 {
+	let mut result = 1;
+	for (p, k) in prime_factorization(n) {
+		// λ(p^k) = φ(p^k), except that λ(2^k) = 2^(k - 2) for k >= 3
+		let lambda = if p == 2 && k >= 3 {
+			1 << (k - 2)
+		} else {
+			(p - 1) * p.pow(k - 1)
+		};
+		// λ(n) divides φ(n) <= n, so this cannot overflow
+		result = result / gcd(result, lambda) * lambda;
+	}
+
+	result
+}
+
+/// Computes the exponent of the prime `p` in the factorization of $n!$ using
+/// Legendre's formula, $\sum_{i \ge 1} \lfloor n / p^i \rfloor$. The
+/// primality of `p` is not verified. Panics if `p` is less than 2.
+///
+/// # Examples
+///
+/// ```
+/// // 10! = 2^8 * 3^4 * 5^2 * 7
+/// assert_eq!(8, segtrs::numt::prime_exponent_in_factorial(10, 2));
+/// assert_eq!(4, segtrs::numt::prime_exponent_in_factorial(10, 3));
+/// ```
+pub fn prime_exponent_in_factorial(n: u64, p: u64) -> u64 {
+	if p < 2 {
+		panic!("p must be a prime");
+	}
+
+	// Dividing n repeatedly yields floor(n / p^i) without computing p^i,
+	// which could overflow.
+	let mut n = n;
+	let mut exponent = 0;
+	while n > 0 {
+		n /= p;
+		exponent += n;
+	}
+
+	exponent
+}
+
+/// Computes the number of trailing zeros of $n!$ written in base 10.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(24, segtrs::numt::factorial_trailing_zeros(100));
+/// ```
+pub fn factorial_trailing_zeros(n: u64) -> u64 {
+	factorial_trailing_zeros_in_base(n, 10)
+}
+
+/// Computes the number of trailing zeros of $n!$ written in base `base`.
+/// For every prime power $p^e$ exactly dividing `base`, $n!$ contains
+/// $\lfloor v_p(n!) / e \rfloor$ copies of it, and the scarcest prime
+/// determines the count. Panics if `base` is less than 2.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// // 10! = 3628800 = 0x375F00
+/// assert_eq!(2, numt::factorial_trailing_zeros_in_base(10, 16));
+/// assert_eq!(8, numt::factorial_trailing_zeros_in_base(10, 2));
+/// ```
+pub fn factorial_trailing_zeros_in_base(n: u64, base: u64) -> u64 {
+	if base < 2 {
+		panic!("the base must be at least 2");
+	}
+
+	prime_factorization(base)
+		.into_iter()
+		.map(|(p, e)| prime_exponent_in_factorial(n, p) / e as u64)
+		.min()
+		.unwrap()
+}
+
+/// Computes `a * b % m` without overflow.
+pub(crate) fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+	(a as u128 * b as u128 % m as u128) as u64
+}
+
+/// Computes `(a + b) mod m` without overflow, for `a` and `b` already
+/// reduced modulo `m`.
+pub(crate) fn add_mod(a: u64, b: u64, m: u64) -> u64 {
+	if a >= m - b { a - (m - b) } else { a + b }
+}
+
+/// Computes `(a - b) mod m` for `a` and `b` already reduced modulo `m`.
+pub(crate) fn sub_mod(a: u64, b: u64, m: u64) -> u64 {
+	if a >= b { a - b } else { a + (m - b) }
+}
+
+/// Computes the pair $(U_n, U_{n+1})$ of the Lucas sequence with parameters
+/// `p` and `q`, modulo `m`, using the doubling identities
+/// $U_{2k} = U_k (2 U_{k+1} - P U_k)$ and $U_{2k+1} = U_{k+1}^2 - Q U_k^2$.
+fn lucas_u_pair(p: i64, q: i64, n: u64, m: u64) -> (u64, u64) {
+	if m == 0 {
+		panic!("the modulus must be positive");
+	}
+	let p = (p as i128).rem_euclid(m as i128) as u64;
+	let q = (q as i128).rem_euclid(m as i128) as u64;
+
+	let (mut u0, mut u1) = (0, 1 % m);
+	for bit in (0..(u64::BITS - n.leading_zeros())).rev() {
+		let u2k = mul_mod(u0, sub_mod(mul_mod(2, u1, m), mul_mod(p, u0, m), m), m);
+		let u2k1 = sub_mod(mul_mod(u1, u1, m), mul_mod(q, mul_mod(u0, u0, m), m), m);
+		(u0, u1) = if (n >> bit) & 1 == 1 {
+			// U_{2k+2} = P U_{2k+1} - Q U_{2k}
+			(u2k1, sub_mod(mul_mod(p, u2k1, m), mul_mod(q, u2k, m), m))
+		} else {
+			(u2k, u2k1)
+		};
+	}
+
+	(u0, u1)
+}
+
+/// Computes the $n$th term of the Lucas sequence $U(P, Q)$ modulo `modulus`,
+/// defined by $U_0 = 0$, $U_1 = 1$, and $U_n = P U_{n-1} - Q U_{n-2}$. Uses
+/// doubling identities, so it takes $O(\log n)$ steps. Panics if `modulus`
+/// is 0.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// // U(1, -1) is the Fibonacci sequence
+/// assert_eq!(55, numt::lucas_u(1, -1, 10, 1000));
+/// // U(3, 2) is 2^n - 1
+/// assert_eq!(1023 % 100, numt::lucas_u(3, 2, 10, 100));
+/// ```
+pub fn lucas_u(p: i64, q: i64, n: u64, modulus: u64) -> u64 {
+	lucas_u_pair(p, q, n, modulus).0
+}
+
+/// Computes the $n$th term of the Lucas sequence $V(P, Q)$ modulo `modulus`,
+/// defined by $V_0 = 2$, $V_1 = P$, and $V_n = P V_{n-1} - Q V_{n-2}$. Uses
+/// the identity $V_n = 2 U_{n+1} - P U_n$. Panics if `modulus` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// // V(1, -1) is the sequence of Lucas numbers
+/// assert_eq!(123, numt::lucas_v(1, -1, 10, 1000));
+/// // V(3, 2) is 2^n + 1
+/// assert_eq!(1025 % 100, numt::lucas_v(3, 2, 10, 100));
+/// ```
+pub fn lucas_v(p: i64, q: i64, n: u64, modulus: u64) -> u64 {
+	let m = modulus;
+	let (u0, u1) = lucas_u_pair(p, q, n, m);
+	let p = (p as i128).rem_euclid(m as i128) as u64;
+	sub_mod(mul_mod(2, u1, m), mul_mod(p, u0, m), m)
+}
+
+/// Determines whether `n` is `b`-smooth, meaning none of its prime factors
+/// exceed `b`. In particular, 1 is `b`-smooth for every `b`. Panics if `n`
+/// is 0.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// assert!(numt::is_b_smooth(360, 5));
+/// assert!(!numt::is_b_smooth(14, 5));
+/// ```
+pub fn is_b_smooth(n: u64, b: u64) -> bool {
+	if n == 0 {
+		panic!("n must be positive");
+	}
+
+	let mut n = n;
+	let mut d = 2;
+	while d <= b && d <= n / d {
+		while n.is_multiple_of(d) {
+			n /= d;
+		}
+		d += 1;
+	}
+
+	// What remains is 1, a prime, or a product of primes larger than b
+	n == 1 || n <= b
+}
+
+/// An iterator over the `b`-smooth numbers in increasing order, starting
+/// with 1. For `b = 5` these are the Hamming numbers. Every term is produced by
+/// multiplying an earlier term by a prime, so the iterator performs a k-way
+/// merge of the sequences "earlier terms times p", one for each prime
+/// $p \le b$. The iterator stops before the first term that would overflow.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt::SmoothNumbers;
+/// let hamming: Vec<u64> = SmoothNumbers::new(5).take(12).collect();
+/// assert_eq!(vec![1, 2, 3, 4, 5, 6, 8, 9, 10, 12, 15, 16], hamming);
+/// ```
+pub struct SmoothNumbers {
+	primes: Vec<u64>,
+	// For each prime, the index of the term it should multiply next
+	indices: Vec<usize>,
+	terms: Vec<u64>,
+	done: bool,
+}
+
+impl SmoothNumbers {
+	pub fn new(b: u64) -> Self {
+		let primes: Vec<u64> = (2..=b).filter(|&k| is_prime(k)).collect();
+		Self {
+			indices: vec![0; primes.len()],
+			primes,
+			terms: vec![],
+			done: false,
+		}
+	}
+}
+
+impl Iterator for SmoothNumbers {
+	type Item = u64;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.done {
+			return None;
+		}
+		if self.terms.is_empty() {
+			self.terms.push(1);
+			return Some(1);
+		}
+
+		let next = self.primes
+			.iter()
+			.zip(&self.indices)
+			.filter_map(|(p, &i)| p.checked_mul(self.terms[i]))
+			.min();
+		let Some(next) = next else {
+			self.done = true;
+			return None;
+		};
+
+		// Advance every sequence that produced the term to avoid duplicates
+		for (p, i) in self.primes.iter().zip(self.indices.iter_mut()) {
+			if p.checked_mul(self.terms[*i]) == Some(next) {
+				*i += 1;
+			}
+		}
+		self.terms.push(next);
+
+		Some(next)
+	}
+}
+
+/// Computes `base` raised to `exp` modulo `m` by repeated squaring.
+pub(crate) fn pow_mod(base: u64, exp: u64, m: u64) -> u64 {
+	let mut result = 1 % m;
+	let mut base = base % m;
+	let mut exp = exp;
+	while exp > 0 {
+		if exp & 1 == 1 {
+			result = mul_mod(result, base, m);
+		}
+		base = mul_mod(base, base, m);
+		exp >>= 1;
+	}
+
+	result
+}
+
+/// Determines whether `n` can be written as $a^2 + b^2$ for non-negative
+/// integers $a$ and $b$. By Fermat's theorem on sums of two squares, this
+/// holds if and only if every prime congruent to 3 mod 4 appears in the
+/// factorization of `n` with an even exponent.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// assert!(numt::is_sum_of_two_squares(45));
+/// assert!(!numt::is_sum_of_two_squares(21));
+/// ```
+pub fn is_sum_of_two_squares(n: u64) -> bool {
+	n == 0 || prime_factorization(n)
+		.into_iter()
+		.all(|(p, k)| p % 4 != 3 || k % 2 == 0)
+}
+
+/// Writes `n` as a sum of two squares $a^2 + b^2$ with $a \le b$, or returns
+/// `None` if that is impossible (see [`is_sum_of_two_squares`]). Each prime
+/// $p \equiv 1 \pmod 4$ is split with the Hermite–Serret algorithm, and the
+/// pieces are combined by multiplying the corresponding Gaussian integers.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// assert_eq!(Some((3, 6)), numt::sum_of_two_squares(45));
+/// assert_eq!(None, numt::sum_of_two_squares(21));
+/// ```
+pub fn sum_of_two_squares(n: u64) -> Option<(u64, u64)> {
+	if n == 0 {
+		return Some((0, 0));
+	}
+
+	// Accumulate a + bi such that a^2 + b^2 is the part of n seen so far
+	let (mut a, mut b): (i128, i128) = (1, 0);
+	for (p, k) in prime_factorization(n) {
+		let (c, d) = match p % 4 {
+			// p^2 = p^2 + 0^2, so only even powers of p can be absorbed
+			3 if k % 2 == 0 => {
+				let scale = p.pow(k / 2) as i128;
+				a *= scale;
+				b *= scale;
+				continue;
+			},
+			3 => return None,
+			// 2 = 1^2 + 1^2
+			2 => (1, 1),
+			_ => split_prime_one_mod_four(p),
+		};
+		for _ in 0..k {
+			(a, b) = (a * c as i128 - b * d as i128, a * d as i128 + b * c as i128);
+		}
+	}
+
+	let (a, b) = (a.unsigned_abs() as u64, b.unsigned_abs() as u64);
+	Some((a.min(b), a.max(b)))
+}
+
+/// Writes a prime $p \equiv 1 \pmod 4$ as $a^2 + b^2$. Starting from a
+/// square root $x$ of $-1$ modulo `p`, the first two remainders below
+/// $\sqrt{p}$ in the Euclidean algorithm on `p` and `x` are the answer.
+fn split_prime_one_mod_four(p: u64) -> (u64, u64) {
+	let non_residue = (2..p).find(|&c| jacobi_symbol(c, p) == -1).unwrap();
+	let x = pow_mod(non_residue, (p - 1) / 4, p);
+
+	let (mut r0, mut r1) = (p, x);
+	while (r1 as u128) * (r1 as u128) > p as u128 {
+		(r0, r1) = (r1, r0 % r1);
+	}
+
+	(r1, (p - r1 * r1).isqrt())
+}
+
+/// Runs the sieve of Eratosthenes, producing a vector whose entry `k` tells
+/// whether `k` is prime, for every `k` from 0 through `limit`.
+///
+/// # Examples
+///
+/// ```
+/// let sieve = segtrs::numt::prime_sieve(10);
+/// let primes: Vec<usize> = (0..sieve.len()).filter(|&k| sieve[k]).collect();
+/// assert_eq!(vec![2, 3, 5, 7], primes);
+/// ```
+pub fn prime_sieve(limit: u64) -> Vec<bool> {
+	let limit = limit as usize;
+	let mut sieve = vec![true; limit + 1];
+	sieve[0] = false;
+	if limit >= 1 {
+		sieve[1] = false;
+	}
+
+	let mut p = 2;
+	while p * p <= limit {
+		if sieve[p] {
+			for multiple in (p * p..=limit).step_by(p) {
+				sieve[multiple] = false;
+			}
+		}
+		p += 1;
+	}
+
+	sieve
+}
+
+/// Finds two primes $p \le q$ with $p + q = n$, choosing the smallest such
+/// $p$. Returns `None` if `n` is odd or less than 4. Sieves the primes up to
+/// `n` on every call; use [`goldbach_pair_with_sieve`] to share one sieve
+/// among many calls.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// assert_eq!(Some((5, 23)), numt::goldbach_pair(28));
+/// assert_eq!(None, numt::goldbach_pair(27));
+/// ```
+pub fn goldbach_pair(n: u64) -> Option<(u64, u64)> {
+	if n < 4 || !n.is_multiple_of(2) {
+		return None;
+	}
+	goldbach_pair_with_sieve(n, &prime_sieve(n))
+}
+
+/// Like [`goldbach_pair`], but looks up primes in a sieve produced by
+/// [`prime_sieve`]. Panics if the sieve does not reach `n`.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// let sieve = numt::prime_sieve(10_000);
+/// for n in (4..=10_000).step_by(2) {
+///     let (p, q) = numt::goldbach_pair_with_sieve(n, &sieve).unwrap();
+///     assert_eq!(n, p + q);
+/// }
+/// ```
+pub fn goldbach_pair_with_sieve(n: u64, sieve: &[bool]) -> Option<(u64, u64)> {
+	if n < 4 || !n.is_multiple_of(2) {
+		return None;
+	}
+	if (sieve.len() as u64) <= n {
+		panic!("the sieve must cover every number up to n");
+	}
+
+	(2..=n / 2)
+		.find(|&p| sieve[p as usize] && sieve[(n - p) as usize])
+		.map(|p| (p, n - p))
+}
+
+/// An endless iterator over the prime numbers in increasing order. Primes are
+/// found with a segmented sieve of Eratosthenes, so memory use stays small
+/// while iterating far.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt::Primes;
+/// let primes: Vec<u64> = Primes::new().take(8).collect();
+/// assert_eq!(vec![2, 3, 5, 7, 11, 13, 17, 19], primes);
+/// assert_eq!(Some(104_743), Primes::new().nth(10_000));
+/// ```
+pub struct Primes {
+	// Primes in the current segment that have not been produced yet
+	segment: std::vec::IntoIter<u64>,
+	// The start of the next segment to sieve
+	low: u64,
+	// Primes used to sieve segments, and the bound they were computed up to
+	base_primes: Vec<u64>,
+	base_limit: u64,
+}
+
+impl Primes {
+	const SEGMENT_SIZE: u64 = 1 << 15;
+
+	pub fn new() -> Self {
+		Self {
+			segment: vec![].into_iter(),
+			low: 0,
+			base_primes: vec![],
+			base_limit: 0,
+		}
+	}
+
+	fn sieve_next_segment(&mut self) {
+		let (low, high) = (self.low, self.low + Self::SEGMENT_SIZE);
+		if high.isqrt() > self.base_limit {
+			// Grow geometrically so the base primes are rarely recomputed
+			self.base_limit = high.isqrt().max(2 * self.base_limit);
+			let sieve = prime_sieve(self.base_limit);
+			self.base_primes = (0..=self.base_limit)
+				.filter(|&k| sieve[k as usize])
+				.collect();
+		}
+
+		let mut is_prime = vec![true; Self::SEGMENT_SIZE as usize];
+		for &p in self.base_primes.iter().take_while(|&&p| p * p < high) {
+			let start = (p * p).max(low.div_ceil(p) * p);
+			for multiple in (start..high).step_by(p as usize) {
+				is_prime[(multiple - low) as usize] = false;
+			}
+		}
+
+		let primes: Vec<u64> = (low.max(2)..high)
+			.filter(|&k| is_prime[(k - low) as usize])
+			.collect();
+		self.segment = primes.into_iter();
+		self.low = high;
+	}
+}
+
+impl Default for Primes {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Iterator for Primes {
+	type Item = u64;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			if let Some(p) = self.segment.next() {
+				return Some(p);
+			}
+			self.sieve_next_segment();
+		}
+	}
+}
+
+/// An endless iterator over the twin primes, the pairs $(p, p + 2)$ where
+/// both numbers are prime.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt::TwinPrimes;
+/// let twins: Vec<(u64, u64)> = TwinPrimes::new().take(4).collect();
+/// assert_eq!(vec![(3, 5), (5, 7), (11, 13), (17, 19)], twins);
+/// ```
+pub struct TwinPrimes {
+	gaps: PrimeGaps,
+}
+
+impl TwinPrimes {
+	pub fn new() -> Self {
+		Self {
+			gaps: PrimeGaps::new(),
+		}
+	}
+}
+
+impl Default for TwinPrimes {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Iterator for TwinPrimes {
+	type Item = (u64, u64);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.gaps
+			.find(|&(_, gap)| gap == 2)
+			.map(|(p, _)| (p, p + 2))
+	}
+}
+
+/// An endless iterator over the gaps between consecutive primes. Produces
+/// pairs $(p, g)$ where $p$ is prime and $p + g$ is the next prime.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt::PrimeGaps;
+/// let gaps: Vec<(u64, u64)> = PrimeGaps::new().take(5).collect();
+/// assert_eq!(vec![(2, 1), (3, 2), (5, 2), (7, 4), (11, 2)], gaps);
+/// ```
+pub struct PrimeGaps {
+	primes: Primes,
+	previous: Option<u64>,
+}
+
+impl PrimeGaps {
+	pub fn new() -> Self {
+		let mut primes = Primes::new();
+		let previous = primes.next();
+		Self {
+			primes,
+			previous,
+		}
+	}
+}
+
+impl Default for PrimeGaps {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Iterator for PrimeGaps {
+	type Item = (u64, u64);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let p = self.previous?;
+		let q = self.primes.next()?;
+		self.previous = Some(q);
+		Some((p, q - p))
+	}
+}
+
+/// Computes the sum of the decimal digits of `n`.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(15, segtrs::numt::digit_sum(12345));
+/// ```
+pub fn digit_sum(n: u64) -> u64 {
+	digit_power_sum(n, 1)
+}
+
+/// Computes the sum of the `k`th powers of the decimal digits of `n`.
+///
+/// # Examples
+///
+/// ```
+/// // 1^3 + 5^3 + 3^3 = 153
+/// assert_eq!(153, segtrs::numt::digit_power_sum(153, 3));
+/// ```
+pub fn digit_power_sum(n: u64, k: u32) -> u64 {
+	let mut n = n;
+	let mut sum = 0;
+	while n > 0 {
+		sum += (n % 10).pow(k);
+		n /= 10;
+	}
+
+	sum
+}
+
+/// Determines whether `n` is a happy number: repeatedly replacing a number by
+/// the sum of the squares of its digits eventually reaches 1. Otherwise the
+/// process falls into a cycle, which is detected with Floyd's algorithm.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// // 19 -> 82 -> 68 -> 100 -> 1
+/// assert!(numt::is_happy(19));
+/// assert!(!numt::is_happy(4));
+/// ```
+pub fn is_happy(n: u64) -> bool {
+	let step = |k| digit_power_sum(k, 2);
+	let mut slow = n;
+	let mut fast = step(n);
+	while slow != fast {
+		slow = step(slow);
+		fast = step(step(fast));
+	}
+
+	slow == 1
+}
+
+/// Produces every happy number less than `limit`, in increasing order. After
+/// a single step every number is at most $20 \cdot 9^2$, so the answers for
+/// those small values are computed once and shared.
+///
+/// # Examples
+///
+/// ```
+/// let happy = segtrs::numt::happy_numbers_below(30);
+/// assert_eq!(vec![1, 7, 10, 13, 19, 23, 28], happy);
+/// ```
+pub fn happy_numbers_below(limit: u64) -> Vec<u64> {
+	let is_small_happy: Vec<bool> = (0..=20 * 81).map(is_happy).collect();
+
+	(1..limit)
+		.filter(|&n| is_small_happy[digit_power_sum(n, 2) as usize])
+		.collect()
+}
+
+/// Applies the Kaprekar map to `n` viewed as a number with `digits` digits,
+/// padding with leading zeros: the digits arranged in descending order minus
+/// the digits arranged in ascending order.
+fn kaprekar_map(n: u64, digits: u32) -> u64 {
+	let mut sorted: Vec<u64> = (0..digits)
+		.map(|i| n / 10u64.pow(i) % 10)
+		.collect();
+	sorted.sort_unstable();
+
+	let ascending = sorted.iter().fold(0, |acc, d| acc * 10 + d);
+	let descending = sorted.iter().rev().fold(0, |acc, d| acc * 10 + d);
+	descending - ascending
+}
+
+/// Counts the iterations of the Kaprekar routine needed for `n`, treated as
+/// a number with `digits` digits, to reach a nonzero fixed point, such as
+/// 6174 for four digits or 495 for three. Returns `None` if the routine
+/// reaches 0 (as it does for repdigits) or falls into a cycle that is not a
+/// fixed point. Panics if `digits` is not between 1 and 19, or if `n` has
+/// more than `digits` digits.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// // 3524 -> 3087 -> 8352 -> 6174
+/// assert_eq!(Some(3), numt::kaprekar_steps(3524, 4));
+/// assert_eq!(Some(0), numt::kaprekar_steps(495, 3));
+/// assert_eq!(None, numt::kaprekar_steps(1111, 4));
+/// ```
+pub fn kaprekar_steps(n: u64, digits: u32) -> Option<u32> {
+	if digits == 0 || digits > 19 {
+		panic!("the number of digits must be between 1 and 19");
+	}
+	if digits < 19 && n >= 10u64.pow(digits) {
+		panic!("n has more than the given number of digits");
+	}
+
+	let mut seen = BTreeSet::new();
+	let mut current = n;
+	let mut steps = 0;
+	loop {
+		let next = kaprekar_map(current, digits);
+		if next == current {
+			return if current == 0 { None } else { Some(steps) };
+		}
+		if !seen.insert(current) {
+			return None;
+		}
+		current = next;
+		steps += 1;
+	}
+}
+
+/// Determines whether `n` is a Harshad (or Niven) number, meaning it is
+/// divisible by the sum of its digits. 0 is not a Harshad number.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// assert!(numt::is_harshad(201));
+/// assert!(!numt::is_harshad(202));
+/// ```
+pub fn is_harshad(n: u64) -> bool {
+	n > 0 && n.is_multiple_of(digit_sum(n))
+}
+
+/// Determines whether `n` is a strong Harshad number: a Harshad number that
+/// yields a prime when divided by the sum of its digits.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// // 201 / 3 = 67, which is prime
+/// assert!(numt::is_strong_harshad(201));
+/// // 12 / 3 = 4, which is not
+/// assert!(!numt::is_strong_harshad(12));
+/// ```
+pub fn is_strong_harshad(n: u64) -> bool {
+	is_harshad(n) && is_prime(n / digit_sum(n))
+}
+
+/// An iterator over the right-truncatable Harshad numbers in increasing
+/// order. These are the Harshad numbers that remain Harshad numbers every
+/// time their last digit is removed. The numbers are built breadth-first by
+/// appending digits to shorter ones, so all numbers of a given length are
+/// produced before any longer one. The iterator stops once appending a
+/// digit would overflow.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt::RightTruncatableHarshads;
+/// let numbers: Vec<u64> = RightTruncatableHarshads::new()
+///     .skip(9)
+///     .take(5)
+///     .collect();
+/// assert_eq!(vec![10, 12, 18, 20, 21], numbers);
+/// ```
+pub struct RightTruncatableHarshads {
+	current: Vec<u64>,
+	index: usize,
+}
+
+impl RightTruncatableHarshads {
+	pub fn new() -> Self {
+		Self {
+			current: (1..=9).collect(),
+			index: 0,
+		}
+	}
+}
+
+impl Default for RightTruncatableHarshads {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Iterator for RightTruncatableHarshads {
+	type Item = u64;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.index == self.current.len() {
+			// Extend every number of the current length by one digit
+			let next: Vec<u64> = self.current
+				.iter()
+				.flat_map(|&n| {
+					(0..=9).filter_map(move |d| n.checked_mul(10)?.checked_add(d))
+				})
+				.filter(|&n| is_harshad(n))
+				.collect();
+			self.current = next;
+			self.index = 0;
+		}
+
+		let n = *self.current.get(self.index)?;
+		self.index += 1;
+		Some(n)
+	}
+}
+
+/// Computes the sum of the proper divisors of `n`, meaning every divisor
+/// except `n` itself, from the prime factorization of `n`. Returns `None` if
+/// the sum overflows. Panics if `n` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// assert_eq!(Some(0), numt::proper_divisor_sum(1));
+/// assert_eq!(Some(28), numt::proper_divisor_sum(28));
+/// assert_eq!(Some(284), numt::proper_divisor_sum(220));
+/// ```
+pub fn proper_divisor_sum(n: u64) -> Option<u64> {
+	// The sum of all divisors is the product of 1 + p + ... + p^k over the
+	// prime powers p^k in the factorization.
+	let mut divisor_sum: u128 = 1;
+	for (p, k) in prime_factorization(n) {
+		let p = p as u128;
+		let mut term = 1;
+		let mut power = 1;
+		for _ in 0..k {
+			power *= p;
+			term += power;
+		}
+		divisor_sum = divisor_sum.checked_mul(term)?;
+	}
+
+	u64::try_from(divisor_sum - n as u128).ok()
+}
+
+/// An iterator over the aliquot sequence starting at `n`: the terms
+/// $n, s(n), s(s(n)), \ldots$ where $s$ is [`proper_divisor_sum`]. The
+/// sequence ends after producing 0, or just before a term would repeat, so
+/// every term is produced at most once. Perfect, amicable, and sociable
+/// numbers lead to such repetitions; the length of the cycle that was found
+/// is available from [`cycle_length`].
+///
+/// Because some aliquot sequences grow for a very long time, the number of
+/// terms and their size can be limited with [`max_steps`] and [`max_value`].
+/// The iterator also stops if a term would overflow.
+///
+/// [`cycle_length`]: AliquotSequence::cycle_length
+/// [`max_steps`]: AliquotSequence::max_steps
+/// [`max_value`]: AliquotSequence::max_value
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt::AliquotSequence;
+/// let terms: Vec<u64> = AliquotSequence::new(10).collect();
+/// assert_eq!(vec![10, 8, 7, 1, 0], terms);
+///
+/// // 12496 is part of a sociable chain of length 5
+/// let mut sequence = AliquotSequence::new(12496);
+/// assert_eq!(5, sequence.by_ref().count());
+/// assert_eq!(Some(5), sequence.cycle_length());
+/// ```
+pub struct AliquotSequence {
+	next: Option<u64>,
+	// Maps each term produced so far to its position in the sequence
+	seen: BTreeMap<u64, usize>,
+	cycle_length: Option<usize>,
+	max_steps: Option<usize>,
+	max_value: Option<u64>,
+}
+
+impl AliquotSequence {
+	pub fn new(n: u64) -> Self {
+		Self {
+			next: Some(n),
+			seen: BTreeMap::new(),
+			cycle_length: None,
+			max_steps: None,
+			max_value: None,
+		}
+	}
+
+	/// Produce at most `steps` terms.
+	pub fn max_steps(mut self, steps: usize) -> Self {
+		self.max_steps = Some(steps);
+		self
+	}
+
+	/// Stop before producing a term larger than `value`.
+	pub fn max_value(mut self, value: u64) -> Self {
+		self.max_value = Some(value);
+		self
+	}
+
+	/// The length of the cycle the sequence entered, if a repeated term has
+	/// been found.
+	pub fn cycle_length(&self) -> Option<usize> {
+		self.cycle_length
+	}
+}
+
+impl Iterator for AliquotSequence {
+	type Item = u64;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let term = self.next?;
+		if self.max_steps.is_some_and(|steps| self.seen.len() >= steps)
+			|| self.max_value.is_some_and(|value| term > value) {
+			self.next = None;
+			return None;
+		}
+		if let Some(&position) = self.seen.get(&term) {
+			self.cycle_length = Some(self.seen.len() - position);
+			self.next = None;
+			return None;
+		}
+
+		self.seen.insert(term, self.seen.len());
+		self.next = if term == 0 { None } else { proper_divisor_sum(term) };
+		Some(term)
+	}
+}
+
+/// Determines whether `n` is squarefree, meaning no prime divides it more
+/// than once. 0 is not squarefree, and 1 is.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// assert!(numt::is_squarefree(30));
+/// assert!(!numt::is_squarefree(18));
+/// ```
+pub fn is_squarefree(n: u64) -> bool {
+	n > 0 && prime_factorization(n).values().all(|&k| k == 1)
+}
+
+/// Produces a vector whose entry `k` tells whether `k` is squarefree, for
+/// every `k` from 0 through `limit`. Rather than factoring each number, it
+/// crosses out the multiples of $p^2$ for every prime $p$.
+///
+/// # Examples
+///
+/// ```
+/// let sieve = segtrs::numt::squarefree_sieve(12);
+/// let squarefree: Vec<usize> = (0..sieve.len()).filter(|&k| sieve[k]).collect();
+/// assert_eq!(vec![1, 2, 3, 5, 6, 7, 10, 11], squarefree);
+/// ```
+pub fn squarefree_sieve(limit: u64) -> Vec<bool> {
+	let root = limit.isqrt() as usize;
+	let limit = limit as usize;
+	let mut sieve = vec![true; limit + 1];
+	sieve[0] = false;
+
+	let primes = prime_sieve(root as u64);
+	for p in (2..=root).filter(|&p| primes[p]) {
+		for multiple in (p * p..=limit).step_by(p * p) {
+			sieve[multiple] = false;
+		}
+	}
+
+	sieve
+}
+
+/// Counts how many times each decimal digit appears in `n`.
+fn digit_counts(n: u64) -> [u8; 10] {
+	let mut counts = [0; 10];
+	let mut n = n;
+	loop {
+		counts[(n % 10) as usize] += 1;
+		n /= 10;
+		if n == 0 {
+			break;
+		}
+	}
+
+	counts
+}
+
+/// Determines whether the decimal digits of `a` are a rearrangement of the
+/// digits of `b`. Leading zeros are not considered, so 120 and 12 are not
+/// permutations of each other.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// assert!(numt::are_digit_permutations(1487, 4817));
+/// assert!(!numt::are_digit_permutations(1487, 4818));
+/// ```
+pub fn are_digit_permutations(a: u64, b: u64) -> bool {
+	digit_counts(a) == digit_counts(b)
+}
+
+/// Determines whether every number in `numbers` is a rearrangement of the
+/// digits of every other one. This holds trivially for fewer than two
+/// numbers.
+///
+/// # Examples
+///
+/// ```
+/// // 142857 times 2, 3, 4, 5, and 6 only rearranges its digits
+/// let multiples: Vec<u64> = (1..=6).map(|k| 142857 * k).collect();
+/// assert!(segtrs::numt::all_digit_permutations(&multiples));
+/// ```
+pub fn all_digit_permutations(numbers: &[u64]) -> bool {
+	let Some((&first, rest)) = numbers.split_first() else {
+		return true;
+	};
+	let counts = digit_counts(first);
+	rest.iter().all(|&n| digit_counts(n) == counts)
+}
+
+/// Computes the binomial coefficient $\binom{n}{k}$ modulo the prime `p`,
+/// even when `n` is far too large for the coefficient to be computed
+/// exactly. By Lucas' theorem, the result is the product of
+/// $\binom{n_i}{k_i}$ over the base-`p` digits $n_i$ and $k_i$ of `n` and
+/// `k`. Each of those is computed with the multiplicative formula, so no
+/// memory proportional to `p` is needed; to answer many queries for a small
+/// prime, build a [`BinomialModPrime`] table instead. The primality of `p` is
+/// not verified. Panics if `p` is less than 2.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// assert_eq!(252 % 13, numt::binomial_mod_p(10, 5, 13));
+/// assert_eq!(4, numt::binomial_mod_p(1000, 500, 7));
+/// assert_eq!(766_300_138, numt::binomial_mod_p(u64::MAX, 2, 1_000_000_007));
+/// ```
+pub fn binomial_mod_p(n: u64, k: u64, p: u64) -> u64 {
+	if p < 2 {
+		panic!("p must be a prime");
+	}
+
+	lucas_product(n, k, p, |ni, ki| {
+		// C(ni, ki) = ni (ni - 1) ... (ni - r + 1) / r! with r = min(ki, ni - ki)
+		let r = ki.min(ni - ki);
+		let mut numerator = 1 % p;
+		let mut denominator = 1 % p;
+		for j in 0..r {
+			numerator = mul_mod(numerator, ni - j, p);
+			denominator = mul_mod(denominator, j + 1, p);
+		}
+		mul_mod(numerator, pow_mod(denominator, p - 2, p), p)
+	})
+}
+
+/// Multiplies the values of `small_binomial` over the base-`p` digits of `n`
+/// and `k`, as in Lucas' theorem. Digits with $k_i > n_i$ make the result 0.
+fn lucas_product(n: u64, k: u64, p: u64, small_binomial: impl Fn(u64, u64) -> u64)
+	-> u64 {
+
+	if k > n {
+		return 0;
+	}
+
+	let (mut n, mut k) = (n, k);
+	let mut result = 1 % p;
+	while k > 0 {
+		let (ni, ki) = (n % p, k % p);
+		if ki > ni {
+			return 0;
+		}
+		result = mul_mod(result, small_binomial(ni, ki), p);
+		n /= p;
+		k /= p;
+	}
+
+	result
+}
+
+/// Tables of factorials and inverse factorials modulo a prime `p`, for
+/// answering many binomial coefficient queries modulo `p` in $O(\log_p n)$
+/// time each. The tables hold `p` entries, so this suits small primes.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt::BinomialModPrime;
+/// let table = BinomialModPrime::new(13);
+/// assert_eq!(252 % 13, table.binomial(10, 5));
+/// assert_eq!(0, table.binomial(13, 1));
+/// ```
+pub struct BinomialModPrime {
+	p: u64,
+	factorial: Vec<u64>,
+	inverse_factorial: Vec<u64>,
+}
+
+impl BinomialModPrime {
+	/// Builds the tables for the prime `p`. The primality of `p` is not
+	/// verified. Panics if `p` is less than 2.
+	pub fn new(p: u64) -> Self {
+		if p < 2 {
+			panic!("p must be a prime");
+		}
+
+		let size = p as usize;
+		let mut factorial = vec![1; size];
+		for i in 1..size {
+			factorial[i] = mul_mod(factorial[i - 1], i as u64, p);
+		}
+		// Fermat's little theorem gives the inverse of the largest factorial;
+		// the others follow from (i - 1)!^-1 = i * i!^-1.
+		let mut inverse_factorial = vec![1; size];
+		inverse_factorial[size - 1] = pow_mod(factorial[size - 1], p - 2, p);
+		for i in (1..size).rev() {
+			inverse_factorial[i - 1] = mul_mod(inverse_factorial[i], i as u64, p);
+		}
+
+		Self {
+			p,
+			factorial,
+			inverse_factorial,
+		}
+	}
+
+	/// Computes $\binom{n}{k}$ modulo `p` using Lucas' theorem.
+	pub fn binomial(&self, n: u64, k: u64) -> u64 {
+		let p = self.p;
+		lucas_product(n, k, p, |ni, ki| {
+			let (ni, ki) = (ni as usize, ki as usize);
+			let result = mul_mod(self.factorial[ni], self.inverse_factorial[ki], p);
+			mul_mod(result, self.inverse_factorial[ni - ki], p)
+		})
+	}
+}
+
+/// Runs the extended Euclidean algorithm, producing $(g, x, y)$ such that
+/// $g = \gcd(a, b) \ge 0$ and $ax + by = g$. Panics if $g = 2^{63}$, which
+/// does not fit in an `i64`. That happens only when each of `a` and `b` is
+/// either 0 or `i64::MIN`, and not both are 0.
+///
+/// # Examples
+///
+/// ```
+/// let (g, x, y) = segtrs::numt::extended_gcd(240, 46);
+/// assert_eq!(2, g);
+/// assert_eq!(2, 240 * x + 46 * y);
+/// ```
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+	let (g, x, y) = extended_gcd_wide(a, b);
+	let Ok(g) = i64::try_from(g) else {
+		panic!("the gcd of {a} and {b} does not fit in an i64");
+	};
+
+	// |x| <= |b| / 2g and |y| <= |a| / 2g, so both fit
+	(g, x as i64, y as i64)
+}
+
+/// Like [`extended_gcd`], but in `i128`, where the gcd always fits.
+fn extended_gcd_wide(a: i64, b: i64) -> (i128, i128, i128) {
+	let (mut r0, mut r1) = (a as i128, b as i128);
+	let (mut x0, mut x1) = (1i128, 0i128);
+	let (mut y0, mut y1) = (0i128, 1i128);
+	while r1 != 0 {
+		let q = r0 / r1;
+		(r0, r1) = (r1, r0 - q * r1);
+		(x0, x1) = (x1, x0 - q * x1);
+		(y0, y1) = (y1, y0 - q * y1);
+	}
+	if r0 < 0 {
+		(r0, x0, y0) = (-r0, -x0, -y0);
+	}
+
+	(r0, x0, y0)
+}
+
+/// Solves the linear Diophantine equation $ax + by = c$ over the integers.
+/// Returns `None` if there is no solution. Otherwise returns
+/// $(x_0, y_0, d_x, d_y)$ such that the solutions are exactly
+/// $x = x_0 + t d_x$ and $y = y_0 + t d_y$ for every integer $t$. The step
+/// is normalized so that $d_x > 0$, or $d_y > 0$ when $b = 0$, and the
+/// particular solution is the one with the smallest non-negative $x_0$ (or
+/// $y_0$ when $b = 0$). When $a = b = 0$ and $c = 0$, every pair is a
+/// solution and the step is reported as $(0, 0)$.
+///
+/// Panics if the particular solution does not fit in an `i64`.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// // 6x + 10y = 8 has the solutions x = 3 + 5t, y = -1 - 3t
+/// assert_eq!(Some((3, -1, 5, -3)), numt::solve_linear_diophantine(6, 10, 8));
+/// assert_eq!(None, numt::solve_linear_diophantine(6, 10, 7));
+/// ```
+pub fn solve_linear_diophantine(a: i64, b: i64, c: i64)
+	-> Option<(i64, i64, i64, i64)> {
+
+	let (g, x, y) = extended_gcd_wide(a, b);
+	if g == 0 {
+		return if c == 0 { Some((0, 0, 0, 0)) } else { None };
+	}
+	let (a, b, c) = (a as i128, b as i128, c as i128);
+	if c % g != 0 {
+		return None;
+	}
+
+	let (mut dx, mut dy) = (b / g, -a / g);
+	if dx < 0 || (dx == 0 && dy < 0) {
+		(dx, dy) = (-dx, -dy);
+	}
+
+	// Scale the Bezout coefficients, then shift along the step to reach the
+	// smallest non-negative x (or y), keeping the values small.
+	let (mut x0, mut y0) = (x * (c / g), y * (c / g));
+	let t = if dx != 0 { x0.div_euclid(dx) } else { y0.div_euclid(dy) };
+	x0 -= t * dx;
+	y0 -= t * dy;
+
+	let narrow = |v: i128| {
+		i64::try_from(v).expect("solution does not fit in an i64")
+	};
+	Some((narrow(x0), narrow(y0), narrow(dx), narrow(dy)))
+}
+
+/// Produces the quadratic residues modulo `n`, meaning every value of
+/// $x^2 \bmod n$. This includes 0 and residues that share a factor with
+/// `n`. Takes $O(n)$ time; use [`is_quadratic_residue`] to test a single
+/// value for large `n`. Panics if `n` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::BTreeSet;
+///
+/// let residues = segtrs::numt::quadratic_residues(10);
+/// assert_eq!(BTreeSet::from([0, 1, 4, 5, 6, 9]), residues);
+/// ```
+pub fn quadratic_residues(n: u64) -> BTreeSet<u64> {
+	if n == 0 {
+		panic!("the modulus must be positive");
+	}
+
+	// x and n - x have the same square
+	(0..=n / 2).map(|x| mul_mod(x, x, n)).collect()
+}
+
+/// Determines whether `a` is a quadratic residue modulo `n`, meaning
+/// $x^2 \equiv a \pmod{n}$ has a solution. Unlike the Legendre symbol, this
+/// works for composite `n`: `a` must be a square modulo every prime power in
+/// the factorization of `n`, which is decided from the Legendre symbol for
+/// odd primes and from the residue mod 8 for powers of 2. Panics if `n` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// assert!(numt::is_quadratic_residue(6, 10));
+/// assert!(!numt::is_quadratic_residue(7, 10));
+/// // 2 is a residue modulo 7, and modulo 49, but not modulo 8
+/// assert!(numt::is_quadratic_residue(2, 49));
+/// assert!(!numt::is_quadratic_residue(2, 8 * 7));
+/// ```
+pub fn is_quadratic_residue(a: u64, n: u64) -> bool {
+	prime_factorization(n).into_iter().all(|(p, k)| {
+		let modulus = p.pow(k);
+		let mut a = a % modulus;
+		if a == 0 {
+			return true;
+		}
+
+		// Write a = p^e * u with u coprime to p. Then a is a square modulo
+		// p^k exactly when e is even and u is a square modulo p^(k - e).
+		let mut e = 0;
+		while a.is_multiple_of(p) {
+			a /= p;
+			e += 1;
+		}
+		if e % 2 == 1 {
+			return false;
+		}
+		if p == 2 {
+			match k - e {
+				1 => true,
+				2 => a % 4 == 1,
+				_ => a % 8 == 1,
+			}
+		} else {
+			legendre_symbol(a, p) == 1
+		}
+	})
+}
+
+/// Builds the palindrome whose first half is `half`. If `odd` is `true`, the
+/// last digit of `half` is the middle digit and is not repeated. Returns
+/// `None` on overflow.
+fn mirror(half: u64, odd: bool) -> Option<u64> {
+	let mut result = half;
+	let mut rest = if odd { half / 10 } else { half };
+	while rest > 0 {
+		result = result.checked_mul(10)?.checked_add(rest % 10)?;
+		rest /= 10;
+	}
+
+	Some(result)
+}
+
+/// Finds the smallest palindromic number strictly greater than `n`, or
+/// `None` if it does not fit in a `u64`. Rather than testing successive
+/// integers, it mirrors the first half of the digits, incrementing that half
+/// if the mirror is too small.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// assert_eq!(Some(1331), numt::next_palindrome(1234));
+/// assert_eq!(Some(1001), numt::next_palindrome(999));
+/// assert_eq!(Some(12421), numt::next_palindrome(12321));
+/// ```
+pub fn next_palindrome(n: u64) -> Option<u64> {
+	let target = n.checked_add(1)?;
+	let length = target.ilog10() + 1;
+	let odd = length % 2 == 1;
+	let half = target / 10u64.pow(length / 2);
+
+	let candidate = mirror(half, odd)?;
+	if candidate >= target {
+		return Some(candidate);
+	}
+
+	// Incrementing the half either keeps its length, or it was all nines and
+	// the answer is the smallest palindrome with one more digit, 10...01.
+	let next_half = half + 1;
+	if next_half.ilog10() == half.ilog10() {
+		mirror(next_half, odd)
+	} else {
+		10u64.checked_pow(length)?.checked_add(1)
+	}
+}
+
+/// An iterator over the palindromic numbers in increasing order, starting
+/// with 0. Each term is built from the previous one with
+/// [`next_palindrome`], so non-palindromes are never examined. The iterator
+/// stops at the largest palindrome that fits in a `u64`.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt::Palindromes;
+/// let palindromes: Vec<u64> = Palindromes::new().skip(8).take(5).collect();
+/// assert_eq!(vec![8, 9, 11, 22, 33], palindromes);
+///
+/// let palindromes: Vec<u64> = Palindromes::starting_at(990).take(3).collect();
+/// assert_eq!(vec![999, 1001, 1111], palindromes);
+/// ```
+pub struct Palindromes {
+	next: Option<u64>,
+}
+
+impl Palindromes {
+	pub fn new() -> Self {
+		Self::starting_at(0)
+	}
+
+	/// Create an iterator whose first term is the smallest palindrome that
+	/// is at least `n`.
+	pub fn starting_at(n: u64) -> Self {
+		Self {
+			next: match n {
+				0 => Some(0),
+				_ => next_palindrome(n - 1),
+			},
+		}
+	}
+}
+
+impl Default for Palindromes {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Iterator for Palindromes {
+	type Item = u64;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let current = self.next?;
+		self.next = next_palindrome(current);
+		Some(current)
+	}
+}
+
+/// Finds the `k`th digit after the decimal point of Champernowne's constant,
+/// $0.123456789101112\ldots$, formed by concatenating the positive integers.
+/// The digits of the $d$-digit numbers form a block of $9 \cdot 10^{d-1} d$
+/// digits, so skipping whole blocks locates the digit in $O(\log k)$ steps.
+/// Panics if `k` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// assert_eq!(1, numt::champernowne_digit(1));
+/// // The 12th digit is the first digit of 11
+/// assert_eq!(1, numt::champernowne_digit(12));
+/// assert_eq!(0, numt::champernowne_digit(11));
+/// ```
+pub fn champernowne_digit(k: u64) -> u8 {
+	if k == 0 {
+		panic!("digits are numbered starting at 1");
+	}
+
+	// Wide integers keep the block sizes from overflowing for large k
+	let mut k = k as u128 - 1;
+	let (mut length, mut count, mut start) = (1u128, 9u128, 1u128);
+	while k >= length * count {
+		k -= length * count;
+		length += 1;
+		count *= 10;
+		start *= 10;
+	}
+
+	let number = start + k / length;
+	let position_from_right = length - 1 - k % length;
+	(number / 10u128.pow(position_from_right as u32) % 10) as u8
+}
+
+const ONES: [&str; 20] = [
+	"zero", "one", "two", "three", "four", "five", "six", "seven", "eight",
+	"nine", "ten", "eleven", "twelve", "thirteen", "fourteen", "fifteen",
+	"sixteen", "seventeen", "eighteen", "nineteen",
+];
+const TENS: [&str; 10] = [
+	"", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy",
+	"eighty", "ninety",
+];
+const SCALES: [&str; 7] = [
+	"", "thousand", "million", "billion", "trillion", "quadrillion",
+	"quintillion",
+];
+
+/// Spells out `n`, which must be between 1 and 999, appending the words to
+/// `words`.
+fn push_english_hundreds(n: u64, use_and: bool, words: &mut Vec<String>) {
+	let (hundreds, rest) = (n / 100, n % 100);
+	if hundreds > 0 {
+		words.push(ONES[hundreds as usize].to_string());
+		words.push("hundred".to_string());
+		if rest > 0 && use_and {
+			words.push("and".to_string());
+		}
+	}
+	let (tens, ones) = ((rest / 10) as usize, (rest % 10) as usize);
+	match rest {
+		0 => {},
+		1..=19 => words.push(ONES[rest as usize].to_string()),
+		_ if ones == 0 => words.push(TENS[tens].to_string()),
+		_ => words.push(format!("{}-{}", TENS[tens], ONES[ones])),
+	}
+}
+
+/// Spells out `n` in English words using the British convention of inserting
+/// "and" before the tens and units, as in "one hundred and fifteen". See
+/// [`to_english_words_with`] to leave it out.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// assert_eq!("three hundred and forty-two", numt::to_english_words(342));
+/// assert_eq!("one thousand and one", numt::to_english_words(1001));
+/// ```
+pub fn to_english_words(n: u64) -> String {
+	to_english_words_with(n, true)
+}
+
+/// Spells out `n` in English words. If `use_and` is `true`, "and" is placed
+/// before the tens and units of each group, and before a final group below
+/// one hundred, as in British usage. Otherwise it is omitted, as is common in
+/// American usage. Numbers up to `u64::MAX`, in the quintillions, are
+/// supported.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// assert_eq!(
+///     "one million two hundred thirty-four thousand five hundred sixty-seven",
+///     numt::to_english_words_with(1_234_567, false),
+/// );
+/// assert_eq!("zero", numt::to_english_words_with(0, false));
+/// ```
+pub fn to_english_words_with(n: u64, use_and: bool) -> String {
+	if n == 0 {
+		return ONES[0].to_string();
+	}
+
+	// Split into groups of three digits, least significant first
+	let mut groups = vec![];
+	let mut rest = n;
+	while rest > 0 {
+		groups.push(rest % 1000);
+		rest /= 1000;
+	}
+
+	let mut words = vec![];
+	for (scale, &group) in groups.iter().enumerate().rev() {
+		if group == 0 {
+			continue;
+		}
+		if scale == 0 && group < 100 && n >= 1000 && use_and {
+			words.push("and".to_string());
+		}
+		push_english_hundreds(group, use_and, &mut words);
+		if scale > 0 {
+			words.push(SCALES[scale].to_string());
+		}
+	}
+
+	words.join(" ")
+}
+
+/// Counts the letters in the English spelling of `n` produced by
+/// [`to_english_words`], not counting spaces or hyphens.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// // "three hundred and forty-two"
+/// assert_eq!(23, numt::english_letter_count(342));
+/// // "one hundred and fifteen"
+/// assert_eq!(20, numt::english_letter_count(115));
+/// ```
+pub fn english_letter_count(n: u64) -> usize {
+	to_english_words(n)
+		.chars()
+		.filter(|c| c.is_ascii_alphabetic())
+		.count()
+}
+
+const ROMAN_NUMERALS: [(u64, &str); 13] = [
+	(1000, "M"), (900, "CM"), (500, "D"), (400, "CD"), (100, "C"), (90, "XC"),
+	(50, "L"), (40, "XL"), (10, "X"), (9, "IX"), (5, "V"), (4, "IV"), (1, "I"),
+];
+
+/// The largest value [`to_roman`] accepts. Thousands are written as
+/// repeated Ms rather than with overlines, so this is a thousand Ms.
+pub const ROMAN_MAX: u64 = 1_000_000;
+
+/// Writes `n` in Roman numerals in minimal form, using the subtractive pairs
+/// IV, IX, XL, XC, CD, and CM. Thousands are written as repeated Ms. Returns
+/// [`Error::InvalidArgument`] if `n` is 0 or exceeds [`ROMAN_MAX`].
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// assert_eq!(Ok("MCMXCIV".to_string()), numt::to_roman(1994));
+/// assert_eq!(Ok("XLIX".to_string()), numt::to_roman(49));
+/// assert!(numt::to_roman(u64::MAX).is_err());
+/// ```
+pub fn to_roman(n: u64) -> Result<String, Error> {
+	if n == 0 {
+		return Err(Error::InvalidArgument("Roman numerals cannot represent 0"));
+	}
+	if n > ROMAN_MAX {
+		return Err(Error::InvalidArgument("value is too large for Roman numerals"));
+	}
+
+	let mut result = String::new();
+	let mut rest = n;
+	for (value, numeral) in ROMAN_NUMERALS {
+		while rest >= value {
+			result.push_str(numeral);
+			rest -= value;
+		}
+	}
+
+	Ok(result)
+}
+
+/// Parses a number written in Roman numerals. Forms that are valid but not
+/// minimal, such as "IIII" or "XVIIII", are accepted. The numerals must
+/// appear in non-increasing order of value, except for the subtractive pairs
+/// IV, IX, XL, XC, CD, and CM, which may only be followed by numerals smaller
+/// than the one they subtract, so "IXV" is rejected. Returns
+/// [`Error::InvalidArgument`] for empty input, unknown characters, or
+/// numerals out of order, and [`Error::Overflow`] if the value does not fit
+/// in a `u64`.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::numt;
+/// assert_eq!(Ok(1994), numt::from_roman("MCMXCIV"));
+/// assert_eq!(Ok(49), numt::from_roman("XXXXVIIII"));
+/// assert!(numt::from_roman("IC").is_err());
+/// ```
+pub fn from_roman(s: &str) -> Result<u64, Error> {
+	let values = s
+		.chars()
+		.map(|c| match c {
+			'I' => Ok(1),
+			'V' => Ok(5),
+			'X' => Ok(10),
+			'L' => Ok(50),
+			'C' => Ok(100),
+			'D' => Ok(500),
+			'M' => Ok(1000),
+			_ => Err(Error::InvalidArgument("unknown Roman numeral")),
+		})
+		.collect::<Result<Vec<u64>, Error>>()?;
+	if values.is_empty() {
+		return Err(Error::InvalidArgument("empty Roman numeral"));
+	}
+
+	let mut total: u64 = 0;
+	// The largest value allowed next
+	let mut limit = u64::MAX;
+	let mut i = 0;
+	while i < values.len() {
+		// A smaller numeral before a larger one forms a subtractive pair,
+		// which is only allowed for a power of ten and the next two numerals.
+		let (value, next_limit) = match values.get(i + 1) {
+			Some(&next) if next > values[i] => {
+				let is_valid_pair = matches!(
+					(values[i], next),
+					(1, 5 | 10) | (10, 50 | 100) | (100, 500 | 1000)
+				);
+				if !is_valid_pair {
+					return Err(Error::InvalidArgument("invalid subtractive pair"));
+				}
+				i += 2;
+				// Anything from the subtracted numeral up, as in "IVI" or
+				// "XCX", would undo the subtraction
+				(next - values[i - 2], values[i - 2] - 1)
+			},
+			_ => {
+				i += 1;
+				(values[i - 1], values[i - 1])
+			},
+		};
+		if value > limit {
+			return Err(Error::InvalidArgument("Roman numerals out of order"));
+		}
+		limit = next_limit;
+		total = total.checked_add(value).ok_or(Error::Overflow)?;
+	}
+
+	Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn below_2_are_not_prime() {
+		assert!(!is_prime(0));
+		assert!(!is_prime(1));
+	}
+
+	#[test]
+	fn primes_below_20() {
+		assert!(is_prime(2));
+		assert!(is_prime(3));
+		assert!(is_prime(5));
+		assert!(is_prime(7));
+		assert!(is_prime(11));
+		assert!(is_prime(13));
+		assert!(is_prime(17));
+		assert!(is_prime(19));
+	}
+
+	#[test]
+	fn palindrome_one_casing() {
+		assert!(is_palindrome("tacocat"));
+	}
+
+	#[test]
+	fn palindrome_case_insensitive() {
+		assert!(is_palindrome("TacoCat"));
+	}
+
+	#[test]
+	fn palindrome_with_spaces() {
+		assert!(is_palindrome("taco cat"));
+	}
+
+	#[test]
+	fn palindrome_numbers() {
+		assert!(is_palindrome("1234321"));
+	}
+
+	#[test]
+	fn palindrome_without_alphanumerics() {
+		assert!(is_palindrome(""));
+		assert!(is_palindrome("!?, ."));
+		assert!(is_palindrome_with("", false));
+		assert!(!is_palindrome_with("!?", false));
+	}
+
+	#[test]
+	fn palindrome_unicode() {
+		assert!(is_palindrome("Été"));
+		assert!(is_palindrome("А роза упала на лапу Азора"));
+		assert!(!is_palindrome("Straße"));
+		assert!(is_palindrome_with("żÓŻ", false));
+	}
+
+	#[test]
+	fn triangular_small() {
+		assert_eq!(0, triangular_number(0).unwrap());
+		assert_eq!(1, triangular_number(1).unwrap());
+		assert_eq!(3, triangular_number(2).unwrap());
+		assert_eq!(6, triangular_number(3).unwrap());
+		assert_eq!(10, triangular_number(4).unwrap());
+		assert_eq!(15, triangular_number(5).unwrap());
+	}
+
+	#[test]
+	fn triangular_overflow() -> Result<(), String> {
+		let result = triangular_number(u64::MAX);
+		if result.is_err() {
+			Ok(())
+		} else {
+			Err(String::from("large triangular numbers should overflow"))
+		}
+	}
+
+	#[test]
+	fn factors_of_zero_and_one() {
+		assert_eq!(BTreeSet::from([0]), factors_of(0));
+		assert_eq!(BTreeSet::from([1]), factors_of(1));
+	}
+
+	#[test]
+	fn factors_non_square() {
+		let result = factors_of(28);
+		assert_eq!(BTreeSet::from([1, 2, 4, 7, 14, 28]), result);
+	}
+	
+	#[test]
+	fn factors_of_a_square() {
+		let result = factors_of(64);
+		assert_eq!(BTreeSet::from([1, 2, 4, 8, 16, 32, 64]), result);
+	}
+
+	#[test]
+	fn perfect_squares_near_max() {
+		let root = u64::MAX.isqrt();
+		assert!(is_perfect_square(root * root));
+		assert!(!is_perfect_square(root * root - 1));
+		assert!(!is_perfect_square(u64::MAX));
+	}
+
+	#[test]
+	fn nth_root_of_small_values() {
+		assert_eq!((0, true), nth_root(0, 5));
+		assert_eq!((1, true), nth_root(1, 5));
+		assert_eq!((1, false), nth_root(2, 2));
+		assert_eq!((17, true), nth_root(17, 1));
+	}
+
+	#[test]
+	fn nth_root_of_large_values() {
+		assert_eq!((2_642_245, false), nth_root(u64::MAX, 3));
+		assert_eq!((2, false), nth_root(u64::MAX, 63));
+		assert_eq!((1, false), nth_root(u64::MAX, 64));
+		assert_eq!((1 << 16, true), nth_root(1 << 48, 3));
+	}
+
+	#[test]
+	#[should_panic(expected = "must be positive")]
+	fn nth_root_of_degree_zero() {
+		nth_root(8, 0);
+	}
+
+	#[test]
+	fn sqrt_continued_fraction_periods() {
+		assert_eq!((0, vec![]), sqrt_continued_fraction(0));
+		assert_eq!((1, vec![]), sqrt_continued_fraction(1));
+		assert_eq!((1, vec![1, 2]), sqrt_continued_fraction(3));
+		assert_eq!((3, vec![1, 1, 1, 1, 6]), sqrt_continued_fraction(13));
+		assert_eq!((7, vec![14]), sqrt_continued_fraction(50));
+	}
+
+	#[test]
+	fn sqrt_continued_fraction_odd_period_count() {
+		// Project Euler 64: four of the continued fractions for N <= 13 have
+		// an odd period.
+		let odd = (2..=13)
+			.filter(|&n| sqrt_continued_fraction(n).1.len() % 2 == 1)
+			.count();
+		assert_eq!(4, odd);
+	}
+
+	#[test]
+	fn sqrt_continued_fraction_large_input() {
+		let n = u64::MAX - 1;
+		let (a0, period) = sqrt_continued_fraction(n);
+		assert_eq!(n.isqrt(), a0);
+		assert_eq!(Some(&(2 * a0)), period.last());
+	}
+
+	#[test]
+	fn convergents_of_finite_fraction() {
+		// 415/93 = [4; 2, 6, 7]
+		let coefficients = vec![4, 2, 6, 7].into_iter();
+		let convergents: Vec<(BigInt, BigInt)> = Convergents::new(coefficients)
+			.collect();
+		let expected: Vec<(BigInt, BigInt)> = [(4, 1), (9, 2), (58, 13), (415, 93)]
+			.into_iter()
+			.map(|(h, k)| (BigInt::from_int(h), BigInt::from_int(k)))
+			.collect();
+		assert_eq!(expected, convergents);
+	}
+
+	#[test]
+	fn convergents_of_e() {
+		// e = [2; 1, 2, 1, 1, 4, 1, 1, 6, 1, ...]. The digit sum of the
+		// numerator of the 100th convergent is 272 (Project Euler 65).
+		let coefficients = std::iter::once(2)
+			.chain((1..).flat_map(|k| [1, 2 * k, 1]));
+		let (h, _) = Convergents::new(coefficients).nth(99).unwrap();
+		let digit_sum: u64 = h.digits().iter().map(|&d| d as u64).sum();
+		assert_eq!(272, digit_sum);
+	}
+
+	#[test]
+	fn convergents_of_sqrt_continued_fraction() {
+		// The fundamental solution of x^2 - 61y^2 = 1 is a convergent of sqrt(61).
+		let (a0, period) = sqrt_continued_fraction(61);
+		let coefficients = std::iter::once(a0).chain(period.into_iter().cycle());
+		let (h, k) = Convergents::new(coefficients).nth(21).unwrap();
+		assert_eq!(BigInt::from_int(1_766_319_049), h);
+		assert_eq!(BigInt::from_int(226_153_980), k);
+	}
+
+	#[test]
+	fn farey_sequence_small_orders() {
+		assert_eq!(0, FareySequence::new(0).count());
+		assert_eq!(vec![(0, 1), (1, 1)], FareySequence::new(1).collect::<Vec<_>>());
+		assert_eq!(0, farey_length(0));
+		assert_eq!(2, farey_length(1));
+	}
+
+	#[test]
+	fn farey_sequence_is_reduced_and_ascending() {
+		let terms: Vec<(u64, u64)> = FareySequence::new(12).collect();
+		assert_eq!(farey_length(12), terms.len() as u64);
+		for pair in terms.windows(2) {
+			let ((a, b), (c, d)) = (pair[0], pair[1]);
+			// Neighbors in a Farey sequence satisfy bc - ad = 1
+			assert_eq!(1, b * c - a * d);
+			assert_eq!(1, gcd(c, d));
+		}
+	}
+
+	#[test]
+	fn farey_length_of_large_order() {
+		// Project Euler 72 counts the terms strictly between 0 and 1.
+		assert_eq!(303_963_552_391, farey_length(1_000_000) - 2);
+	}
+
+	#[test]
+	fn best_rational_approximation_of_integers() {
+		assert_eq!((7, 1), best_rational_approximation(7, 1, 1));
+		assert_eq!((0, 1), best_rational_approximation(0, 5, 3));
+		assert_eq!((3, 1), best_rational_approximation(29, 10, 1));
+	}
+
+	#[test]
+	fn best_rational_approximation_matches_brute_force() {
+		let (x_num, x_den) = (1_234_567, 7_654_321);
+		for max_den in 1..=60 {
+			let (p, q) = best_rational_approximation(x_num, x_den, max_den);
+			// |p/q - x| scaled by x_den * q
+			let error = |p: u64, q: u64| {
+				(p as i128 * x_den as i128 - x_num as i128 * q as i128).abs()
+			};
+			for den in 1..=max_den {
+				let num = (x_num * den + x_den / 2) / x_den;
+				assert!(error(p, q) * den as i128 <= error(num, den) * q as i128);
+			}
+		}
+	}
+
+	#[test]
+	fn best_rational_approximation_of_large_values() {
+		let (p, q) = best_rational_approximation(u64::MAX, u64::MAX - 1, 1000);
+		assert_eq!((1, 1), (p, q));
+	}
+
+	#[test]
+	fn partition_count_small_values() {
+		let expected = [1, 1, 2, 3, 5, 7, 11, 15, 22, 30, 42];
+		for (n, &count) in expected.iter().enumerate() {
+			assert_eq!(BigInt::from_int(count), partition_count(n as u64));
+		}
+	}
+
+	#[test]
+	fn partition_count_exceeds_u64() {
+		// p(500) = 2300165032574323995027
+		let expected = BigInt::new(
+			"2300165032574323995027".bytes().rev().map(|b| b - b'0')
+		);
+		assert_eq!(expected, partition_count(500));
+	}
+
+	#[test]
+	fn partition_count_mod_matches_exact() {
+		// Project Euler 78: the least n with p(n) divisible by one million.
+		assert_eq!(0, partition_count_mod(55374, 1_000_000));
+		assert_eq!(190_569_292 % 1000, partition_count_mod(100, 1000));
+		assert_eq!(0, partition_count_mod(7, 1));
+	}
+
+	#[test]
+	fn partition_count_mod_large_modulus() {
+		let m = u64::MAX - 58;
+		assert_eq!(190_569_292, partition_count_mod(100, m));
+		// p(500) = 2300165032574323995027 exceeds the modulus
+		assert_eq!(12_768_767_434_339_601_959, partition_count_mod(500, m));
+	}
+
+	#[test]
+	fn binomial_pascal_rule() {
+		for n in 1..=60 {
+			for k in 1..n {
+				let expected = binomial(n - 1, k - 1).unwrap()
+					+ binomial(n - 1, k).unwrap();
+				assert_eq!(Some(expected), binomial(n, k));
+			}
+		}
+	}
+
+	#[test]
+	fn binomial_edges_and_overflow() {
+		assert_eq!(Some(1), binomial(0, 0));
+		assert_eq!(Some(1), binomial(u64::MAX, u64::MAX));
+		assert_eq!(Some(u64::MAX), binomial(u64::MAX, 1));
+		assert_eq!(Some(14_226_520_737_620_288_370), binomial(67, 33));
+		assert_eq!(None, binomial(68, 34));
+	}
+
+	#[test]
+	fn binomial_big_matches_checked() {
+		for n in 0..=67 {
+			for k in 0..=n {
+				let expected = BigInt::from_int(binomial(n, k).unwrap());
+				assert_eq!(expected, binomial_big(n, k));
+			}
+		}
+		assert_eq!(BigInt::from_int(0), binomial_big(3, 4));
+	}
+
+	#[test]
+	fn factorial_checked_limit() {
+		assert_eq!(Some(2_432_902_008_176_640_000), factorial_checked(20));
+		assert_eq!(None, factorial_checked(u64::MAX));
+	}
+
+	#[test]
+	fn factorial_big_matches_checked() {
+		for n in 0..=20 {
+			let expected = BigInt::from_int(factorial_checked(n).unwrap());
+			assert_eq!(expected, factorial_big(n));
+		}
+		let (quotient, remainder) = factorial_big(21).divide_int(21);
+		assert_eq!(factorial_big(20), quotient);
+		assert_eq!(0, remainder);
+	}
+
+	#[test]
+	fn stirling_edge_cases() {
+		assert_eq!(BigInt::from_int(1), stirling_first(0, 0));
+		assert_eq!(BigInt::from_int(1), stirling_second(0, 0));
+		assert_eq!(BigInt::from_int(0), stirling_first(5, 0));
+		assert_eq!(BigInt::from_int(0), stirling_second(5, 6));
+		assert_eq!(BigInt::from_int(1), stirling_second(9, 9));
+	}
+
+	#[test]
+	fn stirling_first_row_sums_to_factorial() {
+		// Every permutation has some number of cycles
+		let sum = stirling_first_row(25)
+			.iter()
+			.fold(BigInt::from_int(0), |acc, c| acc.add(c));
+		assert_eq!(factorial_big(25), sum);
+	}
+
+	#[test]
+	fn stirling_second_known_values() {
+		assert_eq!(BigInt::from_int(42_525), stirling_second(10, 5));
+		assert_eq!(BigInt::from_int(511), stirling_second(10, 2));
+		assert_eq!(BigInt::from_int(269_325), stirling_first(10, 5));
+	}
+
+	#[test]
+	fn multinomial_reduces_to_binomial() {
+		assert_eq!(BigInt::from_int(1), multinomial(&[]));
+		assert_eq!(BigInt::from_int(1), multinomial(&[7]));
+		assert_eq!(binomial_big(40, 20), multinomial(&[20, 20]));
+	}
+
+	#[test]
+	fn multinomial_counts_lattice_walks() {
+		// Paths through a 3-dimensional grid taking 10 steps along each axis
+		let paths = multinomial(&[10, 10, 10]);
+		assert_eq!(BigInt::from_int(5_550_996_791_340), paths);
+	}
+
+	#[test]
+	fn pythagorean_triples_are_primitive() {
+		for (a, b, c) in PythagoreanTriples::new().take(500) {
+			assert_eq!(c * c, a * a + b * b);
+			assert_eq!(1, gcd(a, b));
+			assert!(a < b && b < c);
+		}
+	}
+
+	#[test]
+	fn pythagorean_triples_bounded() {
+		assert_eq!(16, PythagoreanTriples::new().max_hypotenuse(100).count());
+		assert_eq!(70, PythagoreanTriples::new().max_perimeter(1000).count());
+		assert_eq!(0, PythagoreanTriples::new().max_perimeter(11).count());
+		assert_eq!(1, PythagoreanTriples::new().max_perimeter(12).count());
+	}
+
+	#[test]
+	fn pythagorean_triples_with_both_bounds() {
+		let triples: Vec<(u64, u64, u64)> = PythagoreanTriples::new()
+			.max_perimeter(60)
+			.max_hypotenuse(20)
+			.collect();
+		assert_eq!(vec![(3, 4, 5), (5, 12, 13), (8, 15, 17)], triples);
+	}
+
+	#[test]
+	fn legendre_symbol_matches_squares() {
+		for p in [3, 5, 7, 11, 13, 101, 997] {
+			let residues: BTreeSet<u64> = (1..p).map(|x| x * x % p).collect();
+			for a in 0..(2 * p) {
+				let expected = if a % p == 0 {
+					0
+				} else if residues.contains(&(a % p)) {
+					1
+				} else {
+					-1
+				};
+				assert_eq!(expected, legendre_symbol(a, p));
+			}
+		}
+	}
+
+	#[test]
+	fn jacobi_symbol_is_multiplicative() {
+		for a in 0..60 {
+			assert_eq!(
+				jacobi_symbol(a, 7) * jacobi_symbol(a, 9) * jacobi_symbol(a, 11),
+				jacobi_symbol(a, 693),
+			);
+		}
+		assert_eq!(1, jacobi_symbol(0, 1));
+		assert_eq!(0, jacobi_symbol(5, u64::MAX));
+		assert_eq!(1, jacobi_symbol(5, (1 << 61) - 1));
+	}
+
+	#[test]
+	#[should_panic(expected = "odd n")]
+	fn jacobi_symbol_even_modulus() {
+		jacobi_symbol(3, 10);
+	}
+
+	#[test]
+	fn prime_factorization_edge_cases() {
+		assert!(prime_factorization(1).is_empty());
+		assert_eq!(BTreeMap::from([(2, 63)]), prime_factorization(1 << 63));
+		let p = 4_294_967_291;
+		assert_eq!(BTreeMap::from([(p, 1)]), prime_factorization(p));
+		assert_eq!(BTreeMap::from([(3, 1), (p, 1)]), prime_factorization(3 * p));
+	}
+
+	#[test]
+	fn prime_factorization_reconstructs_n() {
+		for n in 1..2000u64 {
+			let product: u64 = prime_factorization(n)
+				.into_iter()
+				.map(|(p, k)| {
+					assert!(is_prime(p));
+					p.pow(k)
+				})
+				.product();
+			assert_eq!(n, product);
+		}
+	}
+
+	#[test]
+	fn carmichael_lambda_is_minimal_exponent() {
+		for n in 2..120u64 {
+			let lambda = carmichael_lambda(n);
+			let units: Vec<u64> = (1..n).filter(|&a| gcd(a, n) == 1).collect();
+			let is_exponent = |m: u64| {
+				units.iter().all(|&a| (0..m).fold(1, |acc, _| acc * a % n) == 1)
+			};
+			assert!(is_exponent(lambda));
+			assert!((1..lambda).all(|m| !is_exponent(m)));
+		}
+	}
+
+	#[test]
+	fn prime_exponent_in_small_factorials() {
+		for n in 0..=20 {
+			let mut factorial = factorial_checked(n).unwrap();
+			let mut exponent = 0;
+			while factorial.is_multiple_of(3) {
+				factorial /= 3;
+				exponent += 1;
+			}
+			assert_eq!(exponent, prime_exponent_in_factorial(n, 3));
+		}
+	}
+
+	#[test]
+	fn prime_exponent_in_large_factorial() {
+		assert_eq!(u64::MAX - 64, prime_exponent_in_factorial(u64::MAX, 2));
+		assert_eq!(0, prime_exponent_in_factorial(4, 5));
+	}
+
+	#[test]
+	fn factorial_trailing_zeros_matches_digits() {
+		for n in 0..60 {
+			let zeros = factorial_big(n)
+				.digits()
+				.iter()
+				.take_while(|&&d| d == 0)
+				.count();
+			assert_eq!(zeros as u64, factorial_trailing_zeros(n));
+		}
+		assert_eq!(249_998, factorial_trailing_zeros(1_000_000));
+	}
+
+	#[test]
+	fn factorial_trailing_zeros_other_bases() {
+		// 12 = 2^2 * 3 and 20! has eighteen 2s and eight 3s
+		assert_eq!(8, factorial_trailing_zeros_in_base(20, 12));
+		assert_eq!(18, factorial_trailing_zeros_in_base(20, 2));
+		assert_eq!(9, factorial_trailing_zeros_in_base(20, 4));
+	}
+
+	#[test]
+	fn lucas_sequences_match_recurrence() {
+		let m: u64 = 1_000_000_007;
+		for (p, q) in [(1, -1), (2, -1), (3, 2), (-4, 7), (5, -9)] {
+			let reduce = |x: i128| x.rem_euclid(m as i128);
+			let (mut u, mut v) = ((0i128, 1i128), (2i128, reduce(p as i128)));
+			for n in 0..200 {
+				assert_eq!(u.0 as u64, lucas_u(p, q, n, m));
+				assert_eq!(v.0 as u64, lucas_v(p, q, n, m));
+				u = (u.1, reduce(p as i128 * u.1 - q as i128 * u.0));
+				v = (v.1, reduce(p as i128 * v.1 - q as i128 * v.0));
+			}
+		}
+	}
+
+	#[test]
+	fn lucas_sequences_large_modulus() {
+		// For a prime p with (5/p) = -1, F(p + 1) is divisible by p
+		let p = 18_446_744_073_709_551_557;
+		assert_eq!(-1, jacobi_symbol(5, p));
+		assert_eq!(0, lucas_u(1, -1, p + 1, p));
+		assert_eq!(0, lucas_v(1, -1, 5, 1));
+	}
+
+	#[test]
+	fn is_b_smooth_small_values() {
+		assert!(is_b_smooth(1, 0));
+		assert!(!is_b_smooth(2, 1));
+		assert!(is_b_smooth(97, 97));
+		assert!(!is_b_smooth(97, 96));
+		assert!(is_b_smooth(1 << 63, 2));
+		assert!(!is_b_smooth(3 * 1_000_000_007, 1_000_000));
+	}
+
+	#[test]
+	fn smooth_numbers_match_is_b_smooth() {
+		let expected: Vec<u64> = (1..=10_000)
+			.filter(|&n| is_b_smooth(n, 7))
+			.collect();
+		let generated: Vec<u64> = SmoothNumbers::new(7)
+			.take_while(|&n| n <= 10_000)
+			.collect();
+		assert_eq!(expected, generated);
+	}
+
+	#[test]
+	fn smooth_numbers_stop_on_overflow() {
+		assert_eq!(vec![1], SmoothNumbers::new(1).collect::<Vec<u64>>());
+		assert_eq!(64, SmoothNumbers::new(2).count());
+		assert_eq!(Some(1 << 63), SmoothNumbers::new(2).last());
+	}
+
+	#[test]
+	fn sum_of_two_squares_matches_brute_force() {
+		for n in 0..3000u64 {
+			let brute_force = (0..=n.isqrt())
+				.any(|a| is_perfect_square(n - a * a));
+			assert_eq!(brute_force, is_sum_of_two_squares(n));
+
+			match sum_of_two_squares(n) {
+				Some((a, b)) => {
+					assert!(a <= b);
+					assert_eq!(n, a * a + b * b);
+				},
+				None => assert!(!brute_force),
+			}
+		}
+	}
+
+	#[test]
+	fn sum_of_two_squares_large_values() {
+		// A large prime congruent to 1 mod 4
+		let p = 1_000_000_009;
+		let (a, b) = sum_of_two_squares(p).unwrap();
+		assert_eq!(p, a * a + b * b);
+
+		let n = 5u64.pow(13) * 13u64.pow(4) * 49;
+		let (a, b) = sum_of_two_squares(n).unwrap();
+		assert_eq!(n, a * a + b * b);
+	}
+
+	#[test]
+	fn prime_sieve_matches_is_prime() {
+		assert_eq!(vec![false], prime_sieve(0));
+		assert_eq!(vec![false, false], prime_sieve(1));
+		let sieve = prime_sieve(1000);
+		for (k, &flag) in sieve.iter().enumerate() {
+			assert_eq!(is_prime(k as u64), flag);
+		}
+	}
+
+	#[test]
+	fn goldbach_pair_small_values() {
+		assert_eq!(None, goldbach_pair(2));
+		assert_eq!(Some((2, 2)), goldbach_pair(4));
+		assert_eq!(Some((3, 3)), goldbach_pair(6));
+		assert_eq!(Some((3, 97)), goldbach_pair(100));
+	}
+
+	#[test]
+	#[should_panic(expected = "the sieve must cover")]
+	fn goldbach_pair_with_short_sieve() {
+		goldbach_pair_with_sieve(100, &prime_sieve(50));
+	}
+
+	#[test]
+	fn primes_match_sieve() {
+		let sieve = prime_sieve(200_000);
+		let expected: Vec<u64> = (0..=200_000)
+			.filter(|&k| sieve[k as usize])
+			.collect();
+		let generated: Vec<u64> = Primes::new()
+			.take_while(|&p| p <= 200_000)
+			.collect();
+		assert_eq!(expected, generated);
+	}
+
+	#[test]
+	fn twin_primes_below_1000() {
+		let count = TwinPrimes::new()
+			.take_while(|&(_, q)| q < 1000)
+			.count();
+		assert_eq!(35, count);
+	}
+
+	#[test]
+	fn first_prime_gap_of_given_size() {
+		assert_eq!(Some((89, 8)), PrimeGaps::new().find(|&(_, g)| g >= 8));
+		assert_eq!(Some((1327, 34)), PrimeGaps::new().find(|&(_, g)| g >= 30));
+	}
+
+	#[test]
+	fn digit_sums() {
+		assert_eq!(0, digit_sum(0));
+		assert_eq!(1, digit_sum(1_000_000));
+		assert_eq!(87, digit_sum(u64::MAX));
+		assert_eq!(0, digit_power_sum(0, 5));
+	}
+
+	#[test]
+	fn happy_edge_cases() {
+		assert!(!is_happy(0));
+		assert!(is_happy(1));
+		assert!(is_happy(1_000_000_000_000));
+		assert!(!is_happy(u64::MAX));
+	}
+
+	#[test]
+	fn happy_numbers_below_agrees_with_is_happy() {
+		let expected: Vec<u64> = (1..10_000).filter(|&n| is_happy(n)).collect();
+		assert_eq!(expected, happy_numbers_below(10_000));
+		assert_eq!(142, happy_numbers_below(1000).len());
+		assert!(happy_numbers_below(1).is_empty());
+	}
+
+	#[test]
+	fn kaprekar_four_digits_takes_at_most_seven_steps() {
+		for n in 0..10_000 {
+			let is_repdigit = n % 1111 == 0;
+			match kaprekar_steps(n, 4) {
+				Some(steps) => assert!(!is_repdigit && steps <= 7),
+				None => assert!(is_repdigit),
+			}
+		}
+		// Leading zeros count as digits
+		assert_eq!(Some(5), kaprekar_steps(2111, 4));
+		assert_eq!(Some(3), kaprekar_steps(21, 4));
+	}
+
+	#[test]
+	fn kaprekar_cycles_without_fixed_point() {
+		// Two-digit numbers fall into the cycle 9 -> 81 -> 63 -> 27 -> 45 -> 9
+		assert_eq!(None, kaprekar_steps(12, 2));
+		// Every one-digit number maps straight to 0
+		assert_eq!(None, kaprekar_steps(7, 1));
+	}
+
+	#[test]
+	#[should_panic(expected = "more than the given number of digits")]
+	fn kaprekar_too_many_digits() {
+		kaprekar_steps(12345, 4);
+	}
+
+	#[test]
+	fn harshad_small_values() {
+		assert!(!is_harshad(0));
+		let harshad: Vec<u64> = (1..=30).filter(|&n| is_harshad(n)).collect();
+		assert_eq!(
+			vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 12, 18, 20, 21, 24, 27, 30],
+			harshad,
+		);
+		assert!(is_harshad(u64::MAX - 81));
+	}
+
+	#[test]
+	fn strong_harshad_small_values() {
+		let strong: Vec<u64> = (1..=50).filter(|&n| is_strong_harshad(n)).collect();
+		assert_eq!(vec![18, 21, 27, 42, 45], strong);
+	}
+
+	#[test]
+	fn right_truncatable_harshads_are_truncatable() {
+		let numbers: Vec<u64> = RightTruncatableHarshads::new()
+			.take_while(|&n| n < 100_000)
+			.collect();
+		let expected: Vec<u64> = (1..100_000)
+			.filter(|&n| {
+				let mut k = n;
+				while k > 0 && is_harshad(k) {
+					k /= 10;
+				}
+				k == 0
+			})
+			.collect();
+		assert_eq!(expected, numbers);
+	}
+
+	#[test]
+	fn right_truncatable_harshads_terminate() {
+		// The sequence is infinite in principle, but stops before overflowing
+		let last = RightTruncatableHarshads::new().last().unwrap();
+		assert!(last > u64::MAX / 10);
+	}
+
+	#[test]
+	fn proper_divisor_sum_matches_factors() {
+		for n in 1..2000 {
+			let expected = factors_of(n).into_iter().sum::<u64>() - n;
+			assert_eq!(Some(expected), proper_divisor_sum(n));
+		}
+		assert_eq!(None, proper_divisor_sum(u64::MAX - 15));
+	}
+
+	#[test]
+	fn aliquot_perfect_and_amicable() {
+		let mut perfect = AliquotSequence::new(496);
+		assert_eq!(vec![496], perfect.by_ref().collect::<Vec<u64>>());
+		assert_eq!(Some(1), perfect.cycle_length());
+
+		let mut amicable = AliquotSequence::new(220);
+		assert_eq!(vec![220, 284], amicable.by_ref().collect::<Vec<u64>>());
+		assert_eq!(Some(2), amicable.cycle_length());
+
+		// 95 -> 25 -> 6 -> 6, which enters a cycle after two steps
+		let mut sequence = AliquotSequence::new(95);
+		assert_eq!(vec![95, 25, 6], sequence.by_ref().collect::<Vec<u64>>());
+		assert_eq!(Some(1), sequence.cycle_length());
+	}
+
+	#[test]
+	fn aliquot_bounds() {
+		let mut terminating = AliquotSequence::new(0);
+		assert_eq!(vec![0], terminating.by_ref().collect::<Vec<u64>>());
+		assert_eq!(None, terminating.cycle_length());
+
+		// The sequence of 276 is open and grows quickly
+		assert_eq!(50, AliquotSequence::new(276).max_steps(50).count());
+		let bounded: Vec<u64> = AliquotSequence::new(276).max_value(400).collect();
+		assert_eq!(vec![276, 396], bounded);
+	}
+
+	#[test]
+	fn squarefree_edge_cases() {
+		assert!(!is_squarefree(0));
+		assert!(is_squarefree(1));
+		assert!(is_squarefree(4_294_967_291));
+		assert!(!is_squarefree(1 << 40));
+		assert_eq!(vec![false], squarefree_sieve(0));
+		assert_eq!(vec![false, true], squarefree_sieve(1));
+	}
+
+	#[test]
+	fn squarefree_sieve_matches_is_squarefree() {
+		let sieve = squarefree_sieve(5000);
+		for (k, &flag) in sieve.iter().enumerate() {
+			assert_eq!(is_squarefree(k as u64), flag);
+		}
+		// The density of squarefree numbers tends to 6 / pi^2
+		let count = squarefree_sieve(1_000_000).iter().filter(|&&b| b).count();
+		assert_eq!(607_926, count);
+	}
+
+	#[test]
+	fn digit_permutations_with_zeros() {
+		assert!(are_digit_permutations(0, 0));
+		assert!(are_digit_permutations(1020, 2100));
+		assert!(!are_digit_permutations(120, 12));
+		assert!(!are_digit_permutations(112, 122));
+	}
+
+	#[test]
+	fn all_digit_permutations_of_few_numbers() {
+		assert!(all_digit_permutations(&[]));
+		assert!(all_digit_permutations(&[7]));
+		assert!(all_digit_permutations(&[1487, 4817, 8147]));
+		assert!(!all_digit_permutations(&[1487, 4817, 8148]));
+	}
+
+	#[test]
+	fn binomial_mod_p_matches_exact() {
+		for p in [2, 3, 5, 7, 11, 13] {
+			let table = BinomialModPrime::new(p);
+			for n in 0..=60 {
+				for k in 0..=n + 1 {
+					let expected = binomial(n, k).unwrap() % p;
+					assert_eq!(expected, binomial_mod_p(n, k, p));
+					assert_eq!(expected, table.binomial(n, k));
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn binomial_mod_p_large_arguments() {
+		let p = 1_000_000_007;
+		let (_, expected) = binomial_big(1000, 300).divide_int(p);
+		assert_eq!(expected, binomial_mod_p(1000, 300, p));
+		// Kummer's theorem: C(2^k, 1) = 2^k is even
+		assert_eq!(0, binomial_mod_p(1 << 62, 1, 2));
+		assert_eq!(1, binomial_mod_p(1 << 62, 1 << 62, 2));
+	}
+
+	#[test]
+	fn extended_gcd_signs() {
+		let pairs = [
+			(0, 0), (0, 7), (-12, 18), (35, -15), (-4, -6), (i64::MIN + 1, 3),
+			(i64::MIN, 1), (i64::MIN, 6), (i64::MIN, i64::MAX), (i64::MIN, -(1 << 62)),
+		];
+		for (a, b) in pairs {
+			let (g, x, y) = extended_gcd(a, b);
+			assert_eq!(gcd(a.unsigned_abs(), b.unsigned_abs()), g as u64);
+			assert_eq!(g as i128, a as i128 * x as i128 + b as i128 * y as i128);
+		}
+	}
+
+	#[test]
+	#[should_panic(expected = "does not fit in an i64")]
+	fn extended_gcd_of_min_and_zero() {
+		extended_gcd(i64::MIN, 0);
+	}
+
+	#[test]
+	fn linear_diophantine_solutions_are_complete() {
+		let equations = [(6, 10, 8), (-6, 10, 8), (3, -7, -1), (4, 0, 12), (0, -5, 10)];
+		for (a, b, c) in equations {
+			let (x0, y0, dx, dy) = solve_linear_diophantine(a, b, c).unwrap();
+			for t in -3..=3 {
+				assert_eq!(c, a * (x0 + t * dx) + b * (y0 + t * dy));
+			}
+			// Brute force: every small solution lies on the line
+			for x in -20..=20i64 {
+				for y in -20..=20i64 {
+					if a * x + b * y == c {
+						if dx != 0 {
+							let t = (x - x0) / dx;
+							assert_eq!((x, y), (x0 + t * dx, y0 + t * dy));
+						} else {
+							assert_eq!(x, x0);
+						}
+					}
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn linear_diophantine_degenerate_cases() {
+		assert_eq!(Some((0, 0, 0, 0)), solve_linear_diophantine(0, 0, 0));
+		assert_eq!(None, solve_linear_diophantine(0, 0, 1));
+		assert_eq!(None, solve_linear_diophantine(4, 0, 6));
+		assert_eq!(Some((3, 0, 0, 1)), solve_linear_diophantine(4, 0, 12));
+		// The gcd is 2^63 here, which only fits in an i128
+		assert_eq!(Some((1, 0, 0, 1)), solve_linear_diophantine(i64::MIN, 0, i64::MIN));
+		assert_eq!(None, solve_linear_diophantine(i64::MIN, i64::MIN, 1));
+	}
+
+	#[test]
+	fn is_quadratic_residue_matches_enumeration() {
+		for n in 1..300 {
+			let residues = quadratic_residues(n);
+			for a in 0..n {
+				assert_eq!(residues.contains(&a), is_quadratic_residue(a, n));
+			}
+		}
+	}
+
+	#[test]
+	fn quadratic_residues_of_large_modulus() {
+		let n = 1_000_000_007;
+		assert!(is_quadratic_residue(mul_mod(123_456_789, 123_456_789, n), n));
+		assert!(!is_quadratic_residue(n - 1, n));
+		assert_eq!(BTreeSet::from([0]), quadratic_residues(1));
+	}
+
+	#[test]
+	fn error_messages() {
+		assert_eq!("overflow", Error::Overflow.to_string());
+		let error = Error::InvalidArgument("n must be positive");
+		assert_eq!("invalid argument: n must be positive", error.to_string());
+	}
+
+	#[test]
+	fn next_palindrome_matches_brute_force() {
+		let is_numeric_palindrome = |n: u64| {
+			let s = n.to_string();
+			s.chars().eq(s.chars().rev())
+		};
+		for n in 0..20_000 {
+			let expected = ((n + 1)..).find(|&k| is_numeric_palindrome(k));
+			assert_eq!(expected, next_palindrome(n));
+		}
+	}
+
+	#[test]
+	fn next_palindrome_near_max() {
+		let largest = 18_446_744_066_044_764_481;
+		assert_eq!(Some(largest), next_palindrome(largest - 1));
+		assert_eq!(None, next_palindrome(largest));
+		assert_eq!(None, next_palindrome(u64::MAX));
+		assert_eq!(Some(largest), Palindromes::starting_at(largest).last());
+	}
+
+	#[test]
+	fn palindromes_count_by_length() {
+		// There are 9 * 10^(k - 1) palindromes with 2k - 1 or 2k digits
+		let count = Palindromes::new()
+			.take_while(|&p| p < 1_000_000)
+			.count();
+		assert_eq!(10 + 9 + 90 + 90 + 900 + 900, count);
+	}
+
+	#[test]
+	fn champernowne_matches_concatenation() {
+		let digits: String = (1..10_000).map(|n| n.to_string()).collect();
+		for (i, c) in digits.chars().enumerate() {
+			let expected = c.to_digit(10).unwrap() as u8;
+			assert_eq!(expected, champernowne_digit(i as u64 + 1));
+		}
+	}
+
+	#[test]
+	fn champernowne_product_and_large_indices() {
+		// Project Euler 40
+		let product: u64 = (0..7)
+			.map(|i| champernowne_digit(10u64.pow(i)) as u64)
+			.product();
+		assert_eq!(210, product);
+		assert!(champernowne_digit(u64::MAX) <= 9);
+	}
+
+	#[test]
+	fn english_words_small_numbers() {
+		assert_eq!("seven", to_english_words(7));
+		assert_eq!("nineteen", to_english_words(19));
+		assert_eq!("forty", to_english_words(40));
+		assert_eq!("ninety-nine", to_english_words(99));
+		assert_eq!("one hundred", to_english_words(100));
+		assert_eq!("five hundred and five", to_english_words(505));
+		assert_eq!("five hundred five", to_english_words_with(505, false));
+	}
+
+	#[test]
+	fn english_words_large_numbers() {
+		assert_eq!("one million", to_english_words(1_000_000));
+		assert_eq!("two billion and twelve", to_english_words(2_000_000_012));
+		assert_eq!(
+			"eighteen quintillion four hundred and forty-six quadrillion \
+			seven hundred and forty-four trillion seventy-three billion \
+			seven hundred and nine million five hundred and fifty-one thousand \
+			six hundred and fifteen",
+			to_english_words(u64::MAX),
+		);
+	}
+
+	#[test]
+	fn english_letter_count_up_to_one_thousand() {
+		// Project Euler 17
+		let total: usize = (1..=1000).map(english_letter_count).sum();
+		assert_eq!(21124, total);
+	}
+
+	#[test]
+	fn roman_round_trip() {
+		for n in 1..5000 {
+			assert_eq!(Ok(n), from_roman(&to_roman(n).unwrap()));
+		}
+		assert_eq!(Ok("MMMMCMXCIX".to_string()), to_roman(4999));
+	}
+
+	#[test]
+	fn to_roman_limits() {
+		assert_eq!(1000, to_roman(ROMAN_MAX).unwrap().len());
+		assert_eq!(Ok(ROMAN_MAX), from_roman(&to_roman(ROMAN_MAX).unwrap()));
+		let too_large = Err(Error::InvalidArgument("value is too large for Roman numerals"));
+		assert_eq!(too_large, to_roman(ROMAN_MAX + 1));
+		assert_eq!(too_large, to_roman(u64::MAX));
+		assert!(to_roman(0).is_err());
+	}
+
+	#[test]
+	fn roman_non_minimal_forms() {
+		assert_eq!(Ok(4), from_roman("IIII"));
+		assert_eq!(Ok(16), from_roman("XIIIIII"));
+		assert_eq!(Ok(19), from_roman("XVIIII"));
+		assert_eq!(Ok(1000), from_roman("DD"));
+	}
+
+	#[test]
+	fn roman_invalid_input() {
+		let invalid = |message| Err(Error::InvalidArgument(message));
+		assert_eq!(invalid("empty Roman numeral"), from_roman(""));
+		assert_eq!(invalid("unknown Roman numeral"), from_roman("XIZ"));
+		assert_eq!(invalid("invalid subtractive pair"), from_roman("VX"));
+		assert_eq!(invalid("Roman numerals out of order"), from_roman("IIX"));
+		for malformed in ["IVI", "IXV", "XCX", "XCL", "CMD"] {
+			assert_eq!(invalid("Roman numerals out of order"), from_roman(malformed));
+		}
+		assert!(from_roman("xii").is_err());
 	}
 }
@@ -0,0 +1,675 @@
+use std::collections::BTreeSet;
+use std::ops::{Add, Mul, Neg, Sub};
+
+use crate::Rational;
+use crate::numt;
+
+/// A polynomial $c_0 + c_1 x + \cdots + c_n x^n$ with coefficients of type
+/// `T`, stored lowest degree first without trailing zero coefficients.
+///
+/// Polynomials can be added, subtracted and multiplied with `+`, `-` and
+/// `*`, either by value or by reference.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::poly::Polynomial;
+/// // (1 + x)(1 - x) = 1 - x^2
+/// let p = Polynomial::new(vec![1, 1]);
+/// let q = Polynomial::new(vec![1, -1]);
+/// let product = &p * &q;
+/// assert_eq!(&[1, 0, -1], product.coeffs());
+/// assert_eq!(Some(2), product.degree());
+/// assert_eq!(-8, product.evaluate(3));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Polynomial<T> {
+	coeffs: Vec<T>,
+}
+
+impl<T: PartialEq + From<u8>> Polynomial<T> {
+	/// Create the polynomial with the given coefficients, lowest degree
+	/// first. Trailing zero coefficients are dropped.
+	pub fn new(coeffs: Vec<T>) -> Self {
+		let mut p = Self { coeffs };
+		p.trim();
+		p
+	}
+
+	/// The polynomial with no terms.
+	pub fn zero() -> Self {
+		Self { coeffs: vec![] }
+	}
+
+	/// The polynomial $cx^k$.
+	pub fn monomial(c: T, k: usize) -> Self {
+		let mut coeffs: Vec<T> = (0..k).map(|_| T::from(0)).collect();
+		coeffs.push(c);
+		Self::new(coeffs)
+	}
+
+	fn trim(&mut self) {
+		while self.coeffs.last().is_some_and(|c| *c == T::from(0)) {
+			self.coeffs.pop();
+		}
+	}
+}
+
+impl<T> Polynomial<T> {
+	/// The coefficients, lowest degree first. The zero polynomial has none.
+	pub fn coeffs(&self) -> &[T] {
+		&self.coeffs
+	}
+
+	/// The degree, or `None` for the zero polynomial.
+	pub fn degree(&self) -> Option<usize> {
+		self.coeffs.len().checked_sub(1)
+	}
+
+	pub fn is_zero(&self) -> bool {
+		self.coeffs.is_empty()
+	}
+
+	/// The value of the polynomial at `x`, computed with Horner's method
+	/// $c_0 + x(c_1 + x(c_2 + \cdots))$, which takes one multiplication and
+	/// one addition per coefficient.
+	pub fn evaluate(&self, x: T) -> T
+		where T: Clone + From<u8> + Add<Output = T> + Mul<Output = T> {
+
+		self.coeffs.iter().rev()
+			.fold(T::from(0), |acc, c| acc * x.clone() + c.clone())
+	}
+
+	/// The derivative.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::poly::Polynomial;
+	/// // The derivative of 5 + 3x^2 + x^3 is 6x + 3x^2
+	/// let p = Polynomial::new(vec![5, 0, 3, 1]);
+	/// assert_eq!(Polynomial::new(vec![0, 6, 3]), p.derivative());
+	/// assert!(Polynomial::new(vec![5]).derivative().is_zero());
+	/// ```
+	pub fn derivative(&self) -> Self
+		where T: Clone + PartialEq + From<u8> + Add<Output = T> + Mul<Output = T> {
+
+		let mut k = T::from(0);
+		let coeffs = self.coeffs.iter().skip(1)
+			.map(|c| {
+				k = k.clone() + T::from(1);
+				c.clone() * k.clone()
+			})
+			.collect();
+		Self::new(coeffs)
+	}
+
+	/// The polynomial with every coefficient multiplied by `c`.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::poly::Polynomial;
+	/// let p = Polynomial::new(vec![1, 2, 3]);
+	/// assert_eq!(Polynomial::new(vec![2, 4, 6]), p.scale(2));
+	/// assert!(p.scale(0).is_zero());
+	/// ```
+	pub fn scale(&self, c: T) -> Self
+		where T: Clone + PartialEq + From<u8> + Mul<Output = T> {
+
+		Self::new(self.coeffs.iter().map(|a| a.clone() * c.clone()).collect())
+	}
+}
+
+impl Polynomial<i64> {
+	/// The distinct rational roots, in increasing order. By the rational
+	/// root theorem, each is of the form $\pm p/q$ where $p$ divides the
+	/// lowest nonzero coefficient and $q$ divides the leading one, so every
+	/// candidate is checked exactly. Panics for the zero polynomial, for
+	/// which every number is a root.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::Rational;
+	/// use segtrs::poly::Polynomial;
+	/// // 2x^3 - 3x^2 - 3x + 2 = (x + 1)(2x - 1)(x - 2)
+	/// let p = Polynomial::new(vec![2, -3, -3, 2]);
+	/// let roots = vec![Rational::from(-1i64), Rational::new(1, 2), Rational::from(2i64)];
+	/// assert_eq!(roots, p.roots_rational());
+	/// // x^2 + 1 has no real roots
+	/// assert!(Polynomial::new(vec![1, 0, 1]).roots_rational().is_empty());
+	/// ```
+	pub fn roots_rational(&self) -> Vec<Rational> {
+		let Some(&leading) = self.coeffs.last() else {
+			panic!("every number is a root of the zero polynomial");
+		};
+		let mut roots = BTreeSet::new();
+		// Factor out x^k, since 0 is the only root it contributes
+		let lowest = self.coeffs.iter().position(|&c| c != 0).unwrap();
+		if lowest > 0 {
+			roots.insert(Rational::from(0i64));
+		}
+		let constant = self.coeffs[lowest];
+
+		for num in numt::factors_of(constant.unsigned_abs()) {
+			for den in numt::factors_of(leading.unsigned_abs()) {
+				for sign in [-1, 1] {
+					let x = Rational::new(sign * num as i128, den as i128);
+					if is_root(&self.coeffs[lowest..], x) {
+						roots.insert(x);
+					}
+				}
+			}
+		}
+		roots.into_iter().collect()
+	}
+}
+
+/// Whether `x` is a root of the polynomial with the given coefficients. If
+/// $x = p/q$ in lowest terms is a root, the partial sums of Horner's method
+/// are the coefficients of the quotient by $x - p/q$, which are integer
+/// multiples of $q$ by Gauss's lemma. So the evaluation stays in integers,
+/// and a partial sum that is not an integer or that overflows rules `x` out.
+fn is_root(coeffs: &[i64], x: Rational) -> bool {
+	let (p, q) = (x.numer(), x.denom());
+	let mut sum: i128 = 0;
+	for &c in coeffs.iter().rev() {
+		if sum % q != 0 {
+			return false;
+		}
+		let Some(next) = (sum / q).checked_mul(p).and_then(|s| s.checked_add(c as i128)) else {
+			return false;
+		};
+		sum = next;
+	}
+	sum == 0
+}
+
+/// A prime of the form $c \cdot 2^k + 1$ with $k = 23$, so that
+/// [`Polynomial::mul_mod`] can use the number-theoretic transform for
+/// products of degree up to $2^{23}$.
+pub const NTT_PRIME: u64 = 998_244_353;
+
+/// The length of the shorter factor above which [`Polynomial::mul_mod`]
+/// switches from schoolbook multiplication to the number-theoretic
+/// transform.
+const NTT_THRESHOLD: usize = 64;
+
+/// The candidates tried when looking for a primitive root. The smallest
+/// primitive root of a prime is small in practice, and giving up early
+/// keeps a composite modulus from being searched exhaustively.
+const PRIMITIVE_ROOT_LIMIT: u64 = 1 << 12;
+
+impl Polynomial<u64> {
+	/// The product of `self` and `other` with coefficients reduced modulo
+	/// `p`. Long products are computed with the number-theoretic transform
+	/// in $O(n \log n)$ time when `p` is a prime and $p - 1$ is divisible by
+	/// a large enough power of 2, as it is for [`NTT_PRIME`]. Otherwise the
+	/// schoolbook method is used, which takes $O(n^2)$ time. Panics if `p`
+	/// is 0.
+	///
+	/// For any other prime, each long product first factors $p - 1$ by trial
+	/// division to find a primitive root, which can take $O(\sqrt{p})$ time
+	/// when $p - 1$ has a large prime factor. Prefer [`NTT_PRIME`] when the
+	/// modulus can be chosen.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use segtrs::poly::{self, Polynomial};
+	/// // Subsets of {1, ..., 200} by sum: the product of the 1 + x^k
+	/// let mut subsets = Polynomial::new(vec![1]);
+	/// for k in 1..=200 {
+	///     let mut factor = vec![0; k + 1];
+	///     (factor[0], factor[k]) = (1, 1);
+	///     subsets = subsets.mul_mod(&Polynomial::new(factor), poly::NTT_PRIME);
+	/// }
+	/// assert_eq!(Some(200 * 201 / 2), subsets.degree());
+	/// // 6, 1 + 5, 2 + 4 and 1 + 2 + 3
+	/// assert_eq!(4, subsets.coeffs()[6]);
+	/// ```
+	pub fn mul_mod(&self, other: &Polynomial<u64>, p: u64) -> Polynomial<u64> {
+		if p == 0 {
+			panic!("modulus must be positive");
+		}
+		if self.is_zero() || other.is_zero() || p == 1 {
+			return Polynomial::zero();
+		}
+		let len = self.coeffs.len() + other.coeffs.len() - 1;
+		let size = len.next_power_of_two() as u64;
+		let short = self.coeffs.len().min(other.coeffs.len());
+		if short > NTT_THRESHOLD && (p - 1).is_multiple_of(size)
+			&& let Some(g) = primitive_root(p) {
+
+			return Polynomial::new(ntt_multiply(&self.coeffs, &other.coeffs, len, g, p));
+		}
+
+		let mut coeffs = vec![0; len];
+		for (i, &a) in self.coeffs.iter().enumerate() {
+			for (j, &b) in other.coeffs.iter().enumerate() {
+				coeffs[i + j] = numt::add_mod(coeffs[i + j], numt::mul_mod(a, b, p), p);
+			}
+		}
+		Polynomial::new(coeffs)
+	}
+}
+
+/// Multiplies two coefficient lists modulo `p`, producing the first `len`
+/// coefficients, where $p - 1$ is divisible by the transform size and `g`
+/// is a primitive root of the prime `p`.
+fn ntt_multiply(a: &[u64], b: &[u64], len: usize, g: u64, p: u64) -> Vec<u64> {
+	let size = len.next_power_of_two();
+	let mut fa: Vec<u64> = a.iter().map(|&x| x % p).collect();
+	let mut fb: Vec<u64> = b.iter().map(|&x| x % p).collect();
+	fa.resize(size, 0);
+	fb.resize(size, 0);
+	ntt(&mut fa, g, p, false);
+	ntt(&mut fb, g, p, false);
+	for (x, y) in fa.iter_mut().zip(&fb) {
+		*x = numt::mul_mod(*x, *y, p);
+	}
+	ntt(&mut fa, g, p, true);
+	fa.truncate(len);
+	fa
+}
+
+/// Transforms `a` in place, or inverts the transform, where the length of
+/// `a` is a power of 2 dividing $p - 1$ and `g` is a primitive root of `p`.
+fn ntt(a: &mut [u64], g: u64, p: u64, invert: bool) {
+	let n = a.len();
+	// Put the values in bit-reversed order
+	let mut j = 0;
+	for i in 1..n {
+		let mut bit = n >> 1;
+		while j & bit != 0 {
+			j ^= bit;
+			bit >>= 1;
+		}
+		j |= bit;
+		if i < j {
+			a.swap(i, j);
+		}
+	}
+
+	let mut len = 2;
+	while len <= n {
+		let mut w = numt::pow_mod(g, (p - 1) / len as u64, p);
+		if invert {
+			w = numt::pow_mod(w, p - 2, p);
+		}
+		for chunk in a.chunks_mut(len) {
+			let (lo, hi) = chunk.split_at_mut(len / 2);
+			let mut wn = 1;
+			for (u, v) in lo.iter_mut().zip(hi) {
+				let t = numt::mul_mod(*v, wn, p);
+				*v = numt::sub_mod(*u, t, p);
+				*u = numt::add_mod(*u, t, p);
+				wn = numt::mul_mod(wn, w, p);
+			}
+		}
+		len <<= 1;
+	}
+
+	if invert {
+		let n_inv = numt::pow_mod(n as u64, p - 2, p);
+		for x in a.iter_mut() {
+			*x = numt::mul_mod(*x, n_inv, p);
+		}
+	}
+}
+
+/// The smallest primitive root of `p`, if it is below
+/// [`PRIMITIVE_ROOT_LIMIT`]. Only a prime has an element of order $p - 1$,
+/// so finding one also proves that `p` is prime.
+fn primitive_root(p: u64) -> Option<u64> {
+	if p == NTT_PRIME {
+		return Some(3);
+	}
+	// Most composite moduli fail Fermat's test, which is far cheaper than
+	// factoring p - 1
+	if p > 2 && numt::pow_mod(2, p - 1, p) != 1 {
+		return None;
+	}
+	let factors = numt::prime_factorization(p - 1);
+	(1..p.min(PRIMITIVE_ROOT_LIMIT)).find(|&g| {
+		numt::pow_mod(g, p - 1, p) == 1
+			&& factors.keys().all(|&q| numt::pow_mod(g, (p - 1) / q, p) != 1)
+	})
+}
+
+/// The polynomial of least degree through `points`, given as $(x, y)$
+/// pairs, found with Newton's divided differences. Panics if two points
+/// have the same $x$.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::Rational;
+/// use segtrs::poly;
+/// // Discover the formula for 1^2 + 2^2 + ... + n^2 from its first values
+/// let points: Vec<_> = (1..=4i64)
+///     .map(|n| (Rational::from(n), Rational::from((1..=n).map(|k| k * k).sum::<i64>())))
+///     .collect();
+/// let p = poly::interpolate(&points);
+/// // n/6 + n^2/2 + n^3/3
+/// let sixth = |k| Rational::new(k, 6);
+/// assert_eq!(&[sixth(0), sixth(1), sixth(3), sixth(2)], p.coeffs());
+/// assert_eq!(Rational::from(338350i64), p.evaluate(Rational::from(100i64)));
+/// ```
+pub fn interpolate(points: &[(Rational, Rational)]) -> Polynomial<Rational> {
+	check_distinct(points);
+	let n = points.len();
+	// After step k, diffs[i] holds the divided difference of points i - k..=i
+	let mut diffs: Vec<Rational> = points.iter().map(|&(_, y)| y).collect();
+	for k in 1..n {
+		for i in (k..n).rev() {
+			diffs[i] = (diffs[i] - diffs[i - 1]) / (points[i].0 - points[i - k].0);
+		}
+	}
+
+	// c_0 + (x - x_0)(c_1 + (x - x_1)(c_2 + ...)), from the inside out
+	let mut p = Polynomial::zero();
+	for k in (0..n).rev() {
+		let factor = Polynomial::new(vec![-points[k].0, Rational::from(1u8)]);
+		p = &(&p * &factor) + &Polynomial::new(vec![diffs[k]]);
+	}
+	p
+}
+
+/// The value at `x` of the polynomial of least degree through `points`,
+/// computed directly with Lagrange's formula, without finding the
+/// polynomial. Panics if two points have the same $x$.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::Rational;
+/// use segtrs::poly;
+/// let r = |n: i64| Rational::from(n);
+/// // The points lie on y = x^2 + 1
+/// let points = [(r(0), r(1)), (r(1), r(2)), (r(3), r(10))];
+/// assert_eq!(r(26), poly::evaluate_interpolation(&points, r(5)));
+/// ```
+pub fn evaluate_interpolation(points: &[(Rational, Rational)], x: Rational) -> Rational {
+	check_distinct(points);
+	let mut sum = Rational::from(0u8);
+	for (i, &(xi, yi)) in points.iter().enumerate() {
+		let mut term = yi;
+		for (j, &(xj, _)) in points.iter().enumerate() {
+			if i != j {
+				term = term * (x - xj) / (xi - xj);
+			}
+		}
+		sum = sum + term;
+	}
+	sum
+}
+
+fn check_distinct(points: &[(Rational, Rational)]) {
+	let xs: BTreeSet<Rational> = points.iter().map(|&(x, _)| x).collect();
+	if xs.len() != points.len() {
+		panic!("interpolation points must have distinct x values");
+	}
+}
+
+impl<T> Add for &Polynomial<T>
+	where T: Clone + PartialEq + From<u8> + Add<Output = T> {
+
+	type Output = Polynomial<T>;
+
+	fn add(self, other: &Polynomial<T>) -> Polynomial<T> {
+		let (long, short) = if self.coeffs.len() >= other.coeffs.len() {
+			(self, other)
+		} else {
+			(other, self)
+		};
+		let mut coeffs = long.coeffs.clone();
+		for (a, b) in coeffs.iter_mut().zip(&short.coeffs) {
+			*a = a.clone() + b.clone();
+		}
+		Polynomial::new(coeffs)
+	}
+}
+
+impl<T> Add for Polynomial<T>
+	where T: Clone + PartialEq + From<u8> + Add<Output = T> {
+
+	type Output = Polynomial<T>;
+
+	fn add(self, other: Polynomial<T>) -> Polynomial<T> {
+		&self + &other
+	}
+}
+
+impl<T> Neg for &Polynomial<T>
+	where T: Clone + Neg<Output = T> {
+
+	type Output = Polynomial<T>;
+
+	fn neg(self) -> Polynomial<T> {
+		// Negating nonzero coefficients leaves them nonzero
+		Polynomial {
+			coeffs: self.coeffs.iter().map(|c| -c.clone()).collect(),
+		}
+	}
+}
+
+impl<T> Neg for Polynomial<T>
+	where T: Clone + Neg<Output = T> {
+
+	type Output = Polynomial<T>;
+
+	fn neg(self) -> Polynomial<T> {
+		-&self
+	}
+}
+
+impl<T> Sub for &Polynomial<T>
+	where T: Clone + PartialEq + From<u8> + Sub<Output = T> {
+
+	type Output = Polynomial<T>;
+
+	fn sub(self, other: &Polynomial<T>) -> Polynomial<T> {
+		let len = self.coeffs.len().max(other.coeffs.len());
+		let coeff = |p: &Polynomial<T>, i: usize| p.coeffs.get(i).cloned().unwrap_or(T::from(0));
+		Polynomial::new((0..len).map(|i| coeff(self, i) - coeff(other, i)).collect())
+	}
+}
+
+impl<T> Sub for Polynomial<T>
+	where T: Clone + PartialEq + From<u8> + Sub<Output = T> {
+
+	type Output = Polynomial<T>;
+
+	fn sub(self, other: Polynomial<T>) -> Polynomial<T> {
+		&self - &other
+	}
+}
+
+impl<T> Mul for &Polynomial<T>
+	where T: Clone + PartialEq + From<u8> + Add<Output = T> + Mul<Output = T> {
+
+	type Output = Polynomial<T>;
+
+	fn mul(self, other: &Polynomial<T>) -> Polynomial<T> {
+		if self.is_zero() || other.is_zero() {
+			return Polynomial::zero();
+		}
+		let mut coeffs: Vec<T> = (0..self.coeffs.len() + other.coeffs.len() - 1)
+			.map(|_| T::from(0))
+			.collect();
+		for (i, a) in self.coeffs.iter().enumerate() {
+			for (j, b) in other.coeffs.iter().enumerate() {
+				coeffs[i + j] = coeffs[i + j].clone() + a.clone() * b.clone();
+			}
+		}
+		Polynomial::new(coeffs)
+	}
+}
+
+impl<T> Mul for Polynomial<T>
+	where T: Clone + PartialEq + From<u8> + Add<Output = T> + Mul<Output = T> {
+
+	type Output = Polynomial<T>;
+
+	fn mul(self, other: Polynomial<T>) -> Polynomial<T> {
+		&self * &other
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn polynomial_trims_zeros() {
+		let p = Polynomial::new(vec![0, 3, 0, 0]);
+		assert_eq!(&[0, 3], p.coeffs());
+		assert_eq!(Some(1), p.degree());
+		assert_eq!(None, Polynomial::new(vec![0u64, 0]).degree());
+		assert_eq!(Polynomial::new(vec![0, 0, 5]), Polynomial::monomial(5, 2));
+	}
+
+	#[test]
+	fn polynomial_arithmetic() {
+		let p = Polynomial::new(vec![1i64, 2, 3]);
+		let q = Polynomial::new(vec![4i64, 5]);
+		assert_eq!(Polynomial::new(vec![5, 7, 3]), &p + &q);
+		assert_eq!(Polynomial::new(vec![-3, -3, 3]), &p - &q);
+		assert_eq!(Polynomial::new(vec![4, 13, 22, 15]), &p * &q);
+		assert!((&p - &p).is_zero());
+		assert_eq!(Polynomial::new(vec![-1, -2, -3]), -p.clone());
+		assert_eq!(p.evaluate(7) * q.evaluate(7), (&p * &q).evaluate(7));
+	}
+
+	#[test]
+	fn polynomial_generating_function() {
+		// Ways to make 10 from coins of 1, 2 and 5: the coefficient of x^10
+		// in the product of the truncated series 1 + x^c + x^(2c) + ...
+		let series = |c: usize| Polynomial::new((0..=10).map(|i| u64::from(i % c == 0)).collect());
+		let product = &(&series(1) * &series(2)) * &series(5);
+		assert_eq!(10, product.coeffs()[10]);
+	}
+
+	#[test]
+	fn polynomial_derivative() {
+		let p = Polynomial::new(vec![1u64; 300]);
+		let d = p.derivative();
+		assert_eq!(Some(298), d.degree());
+		assert_eq!(299, d.coeffs()[298]);
+		assert_eq!((1..300).sum::<u64>(), d.evaluate(1));
+	}
+
+	#[test]
+	fn polynomial_rational_roots() {
+		// x^2 (3x - 2)^2 (x + 5)
+		let p = &(&Polynomial::new(vec![0, 0, 1]) * &Polynomial::new(vec![4, -12, 9]))
+			* &Polynomial::new(vec![5, 1]);
+		let roots = vec![Rational::from(-5i64), Rational::from(0i64), Rational::new(2, 3)];
+		assert_eq!(roots, p.roots_rational());
+		assert_eq!(vec![Rational::from(7i64)], Polynomial::new(vec![-7, 1]).roots_rational());
+		assert!(Polynomial::new(vec![3]).roots_rational().is_empty());
+	}
+
+	#[test]
+	fn roots_rational_with_large_coefficients() {
+		// Evaluating x^5 - (10^9 + 7) at its candidates overflows an i128
+		let p = Polynomial::new(vec![-1_000_000_007i64, 0, 0, 0, 0, 1]);
+		assert!(p.roots_rational().is_empty());
+
+		// (x + 1)(x - (10^9 + 7))(3x - 2)
+		let p = Polynomial::new(vec![2_000_000_014, -1_000_000_009, -3_000_000_020, 3]);
+		let roots = [Rational::from(-1i64), Rational::new(2, 3), Rational::from(1_000_000_007i64)];
+		assert_eq!(roots.to_vec(), p.roots_rational());
+	}
+
+	#[test]
+	fn ntt_matches_schoolbook() {
+		let p = Polynomial::new((0..500u64).map(|i| i * i % 1009).collect());
+		let q = Polynomial::new((0..300u64).map(|i| (i * 7919 + 13) % 1013).collect());
+		let exact = &p * &q;
+		let expected = Polynomial::new(exact.coeffs().iter().map(|c| c % NTT_PRIME).collect());
+		assert_eq!(expected, p.mul_mod(&q, NTT_PRIME));
+
+		// A prime without a large power of 2 in p - 1 uses the schoolbook method
+		let m = 1_000_000_007;
+		let expected = Polynomial::new(exact.coeffs().iter().map(|c| c % m).collect());
+		assert_eq!(expected, p.mul_mod(&q, m));
+	}
+
+	#[test]
+	fn ntt_reduces_coefficients() {
+		let p = Polynomial::new(vec![NTT_PRIME - 1; 100]);
+		let square = p.mul_mod(&p, NTT_PRIME);
+		// (-1)(-1) summed over each diagonal
+		let expected: Vec<u64> = (0..199).map(|k| 100 - (k as u64).abs_diff(99)).collect();
+		assert_eq!(expected, square.coeffs());
+		assert!(p.mul_mod(&Polynomial::zero(), NTT_PRIME).is_zero());
+	}
+
+	#[test]
+	fn mul_mod_with_large_modulus() {
+		// The Goldilocks prime, 2^64 - 2^32 + 1, above 2^63
+		let goldilocks = 0xFFFF_FFFF_0000_0001;
+		let ones = Polynomial::new(vec![1u64; 100]);
+		let q = Polynomial::new((0..100u64).map(|i| goldilocks - 1 - i).collect());
+		let expected = Polynomial::new((0..199u64).map(|k| {
+			// The sum of -(j + 1) over the terms of q contributing to x^k
+			let (lo, hi) = (k.saturating_sub(99), k.min(99));
+			goldilocks - (lo + 1..=hi + 1).sum::<u64>()
+		}).collect());
+		assert_eq!(expected, ones.mul_mod(&q, goldilocks));
+
+		let p = u64::MAX - 58;
+		let product = Polynomial::new(vec![p - 1, p - 1]).mul_mod(&Polynomial::new(vec![2, 2]), p);
+		assert_eq!(&[p - 2, p - 4, p - 2], product.coeffs());
+	}
+
+	#[test]
+	fn mul_mod_with_unusual_modulus() {
+		let p = Polynomial::new((1..=100u64).collect());
+		let q = Polynomial::new((0..100u64).map(|i| i * i).collect());
+		assert!(p.mul_mod(&q, 1).is_zero());
+
+		// 1025 = 5^2 * 41 is composite, although 1024 divides 1025 - 1
+		let exact = &p * &q;
+		let expected = Polynomial::new(exact.coeffs().iter().map(|c| c % 1025).collect());
+		assert_eq!(expected, p.mul_mod(&q, 1025));
+	}
+
+	#[test]
+	fn primitive_roots() {
+		// The shortcut for NTT_PRIME agrees with the search it skips
+		let p = NTT_PRIME;
+		let factors = numt::prime_factorization(p - 1);
+		let is_root = |g| factors.keys().all(|&q| numt::pow_mod(g, (p - 1) / q, p) != 1);
+		assert_eq!(Some(3), (1..p).find(|&g| is_root(g)));
+		assert_eq!(Some(3), primitive_root(NTT_PRIME));
+
+		assert_eq!(Some(7), primitive_root(0xFFFF_FFFF_0000_0001));
+		assert_eq!(None, primitive_root(1025));
+		// 561 = 3 * 11 * 17 passes Fermat's test for base 2
+		assert_eq!(None, primitive_root(561));
+	}
+
+	#[test]
+	fn interpolation_recovers_polynomial() {
+		let r = |n: i64| Rational::from(n);
+		let p = Polynomial::new(vec![r(3), Rational::new(-1, 2), r(0), r(2)]);
+		let points: Vec<_> = [-2, 0, 1, 7].into_iter().map(|x| (r(x), p.evaluate(r(x)))).collect();
+		assert_eq!(p, interpolate(&points));
+		for x in -3..10 {
+			assert_eq!(p.evaluate(r(x)), evaluate_interpolation(&points, r(x)));
+		}
+		assert!(interpolate(&[]).is_zero());
+		assert_eq!(Polynomial::new(vec![r(4)]), interpolate(&[(r(9), r(4))]));
+	}
+
+	#[test]
+	#[should_panic(expected = "distinct x values")]
+	fn interpolation_with_repeated_x() {
+		let r = |n: i64| Rational::from(n);
+		interpolate(&[(r(1), r(2)), (r(1), r(3))]);
+	}
+}
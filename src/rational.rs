@@ -0,0 +1,243 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::str::FromStr;
+
+/// An exact fraction of two `i128` values, always kept in lowest terms with
+/// a positive denominator. Arithmetic panics on overflow, like that of the
+/// primitive integers in debug builds.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::Rational;
+/// let x = Rational::new(1, 6) + Rational::new(1, 3);
+/// assert_eq!(Rational::new(1, 2), x);
+/// assert_eq!((1, 2), (x.numer(), x.denom()));
+/// assert_eq!("-3/4", Rational::new(6, -8).to_string());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+	num: i128,
+	den: i128,
+}
+
+impl Rational {
+	/// Create the fraction `num / den`, reduced to lowest terms. Panics if
+	/// `den` is 0.
+	pub fn new(num: i128, den: i128) -> Self {
+		if den == 0 {
+			panic!("denominator must not be zero");
+		}
+		let g = gcd(num.unsigned_abs(), den.unsigned_abs()) as i128;
+		let sign = if den < 0 { -1 } else { 1 };
+		Self {
+			num: sign * num / g,
+			den: sign * den / g,
+		}
+	}
+
+	pub fn numer(&self) -> i128 {
+		self.num
+	}
+
+	/// The denominator, which is always positive.
+	pub fn denom(&self) -> i128 {
+		self.den
+	}
+
+	pub fn is_integer(&self) -> bool {
+		self.den == 1
+	}
+
+	pub fn abs(&self) -> Self {
+		Self {
+			num: self.num.abs(),
+			den: self.den,
+		}
+	}
+
+	/// The reciprocal `1 / self`. Panics if `self` is 0.
+	pub fn recip(&self) -> Self {
+		if self.num == 0 {
+			panic!("zero has no reciprocal");
+		}
+		Rational::new(self.den, self.num)
+	}
+}
+
+fn gcd(mut p: u128, mut q: u128) -> u128 {
+	while q != 0 {
+		let r = p % q;
+		p = q;
+		q = r;
+	}
+	// Only 0 / 0 would give 0, and the denominator is never 0
+	p.max(1)
+}
+
+impl From<u8> for Rational {
+	fn from(n: u8) -> Self {
+		Rational::from(n as i128)
+	}
+}
+
+impl From<i64> for Rational {
+	fn from(n: i64) -> Self {
+		Rational::from(n as i128)
+	}
+}
+
+impl From<i128> for Rational {
+	fn from(n: i128) -> Self {
+		Self {
+			num: n,
+			den: 1,
+		}
+	}
+}
+
+impl Add for Rational {
+	type Output = Rational;
+
+	fn add(self, other: Rational) -> Rational {
+		let g = gcd(self.den as u128, other.den as u128) as i128;
+		let den = self.den / g * other.den;
+		Rational::new(self.num * (den / self.den) + other.num * (den / other.den), den)
+	}
+}
+
+impl Sub for Rational {
+	type Output = Rational;
+
+	fn sub(self, other: Rational) -> Rational {
+		self + -other
+	}
+}
+
+impl Mul for Rational {
+	type Output = Rational;
+
+	fn mul(self, other: Rational) -> Rational {
+		// Reduce across the fractions first to keep intermediate values small
+		let g1 = gcd(self.num.unsigned_abs(), other.den as u128) as i128;
+		let g2 = gcd(other.num.unsigned_abs(), self.den as u128) as i128;
+		Rational::new((self.num / g1) * (other.num / g2), (self.den / g2) * (other.den / g1))
+	}
+}
+
+impl Div for Rational {
+	type Output = Rational;
+
+	/// Panics if `other` is 0.
+	fn div(self, other: Rational) -> Rational {
+		if other.num == 0 {
+			panic!("division by zero");
+		}
+		let g1 = gcd(self.num.unsigned_abs(), other.num.unsigned_abs()) as i128;
+		let g2 = gcd(self.den as u128, other.den as u128) as i128;
+		Rational::new((self.num / g1) * (other.den / g2), (self.den / g2) * (other.num / g1))
+	}
+}
+
+impl Neg for Rational {
+	type Output = Rational;
+
+	fn neg(self) -> Rational {
+		Self {
+			num: -self.num,
+			den: self.den,
+		}
+	}
+}
+
+impl Ord for Rational {
+	fn cmp(&self, other: &Self) -> Ordering {
+		// Denominators are positive, so cross-multiplying keeps the order
+		(self.num * other.den).cmp(&(other.num * self.den))
+	}
+}
+
+impl PartialOrd for Rational {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl fmt::Display for Rational {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if self.den == 1 {
+			write!(f, "{}", self.num)
+		} else {
+			write!(f, "{}/{}", self.num, self.den)
+		}
+	}
+}
+
+/// The error returned when parsing a [`Rational`] from a string that is not
+/// an integer or a fraction of two integers with a nonzero denominator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseRationalError;
+
+impl fmt::Display for ParseRationalError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "invalid fraction for Rational")
+	}
+}
+
+impl std::error::Error for ParseRationalError {}
+
+/// Parses an integer such as `-7`, or a fraction such as `3/4`.
+///
+/// # Examples
+///
+/// ```
+/// use segtrs::Rational;
+/// assert_eq!(Ok(Rational::new(3, 4)), "6/8".parse());
+/// assert_eq!(Ok(Rational::from(-7i64)), "-7".parse());
+/// assert!("1/0".parse::<Rational>().is_err());
+/// ```
+impl FromStr for Rational {
+	type Err = ParseRationalError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (num, den) = s.split_once('/').unwrap_or((s, "1"));
+		let num = num.trim().parse().map_err(|_| ParseRationalError)?;
+		let den = den.trim().parse().map_err(|_| ParseRationalError)?;
+		if den == 0 {
+			return Err(ParseRationalError);
+		}
+		Ok(Rational::new(num, den))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn rational_lowest_terms() {
+		let x = Rational::new(-10, -4);
+		assert_eq!((5, 2), (x.numer(), x.denom()));
+		assert_eq!((0, 1), (Rational::new(0, -5).numer(), Rational::new(0, -5).denom()));
+		assert!(Rational::new(12, 4).is_integer());
+	}
+
+	#[test]
+	fn rational_arithmetic() {
+		let a = Rational::new(2, 3);
+		let b = Rational::new(-5, 4);
+		assert_eq!(Rational::new(-7, 12), a + b);
+		assert_eq!(Rational::new(23, 12), a - b);
+		assert_eq!(Rational::new(-5, 6), a * b);
+		assert_eq!(Rational::new(-8, 15), a / b);
+		assert!(b < a);
+		assert_eq!(Rational::new(3, 2), a.recip());
+	}
+
+	#[test]
+	#[should_panic(expected = "denominator must not be zero")]
+	fn rational_zero_denominator() {
+		Rational::new(1, 0);
+	}
+}